@@ -0,0 +1,248 @@
+//! Tracking positions in the original string through the splices.
+
+use crate::{Chunk, Multisplice};
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// Identifies an anchor in a [`Multisplice`].
+///
+/// Returned by [`Multisplice::anchor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AnchorId(usize);
+
+impl<'a> Multisplice<'a> {
+    /// Find where an index into the original string ends up in the output.
+    ///
+    /// Indices inside a replaced range map to the start of its replacement. Insertions at the
+    /// index are output before it. The end of the original string maps to the end of the output.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.insert_left(6, "!");
+    /// assert_eq!(splicer.to_string(), "a beep c !d e");
+    /// assert_eq!(splicer.map_offset(0), 0);
+    /// assert_eq!(splicer.map_offset(2), 2);
+    /// assert_eq!(splicer.map_offset(4), 7);
+    /// assert_eq!(splicer.map_offset(6), 10);
+    /// assert_eq!(splicer.map_offset(9), 13);
+    /// ```
+    pub fn map_offset(&self, offset: usize) -> usize {
+        assert!(offset <= self.source.len());
+
+        let mut position = 0;
+        for chunk in self.chunks(0, self.source.len()) {
+            match &chunk {
+                Chunk::Source(range) if range.contains(&offset) => {
+                    return position + offset - range.start;
+                }
+                Chunk::Splice(index) => {
+                    let s = &self.splices[*index];
+                    if !s.is_insertion() && s.range().contains(&offset) {
+                        return position;
+                    }
+                }
+                Chunk::Source(_) => (),
+            }
+            position += self.chunk_str(&chunk).len();
+        }
+        position
+    }
+
+    /// Find which part of the output a range of the original string ends up as.
+    ///
+    /// Ranges that start or end inside a replaced range are widened to cover its whole
+    /// replacement. Insertions at either end of the range are not included, and neither is a
+    /// replacement of a range that only touches it. An empty range maps like
+    /// [`Multisplice::map_offset`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.insert_left(6, "!");
+    /// assert_eq!(splicer.to_string(), "a beep c !d e");
+    /// assert_eq!(splicer.map_range(0..3), 0..6);
+    /// assert_eq!(splicer.map_range(3..5), 6..8);
+    /// assert_eq!(splicer.map_range(4..7), 7..11);
+    /// assert_eq!(splicer.map_range(6..6), 10..10);
+    /// ```
+    pub fn map_range(&self, range: Range<usize>) -> Range<usize> {
+        assert!(range.start <= range.end);
+        assert!(range.end <= self.source.len());
+
+        let start = self.map_offset(range.start);
+        let mut end = 0;
+        let mut position = 0;
+        for chunk in self.chunks(0, self.source.len()) {
+            let len = self.chunk_str(&chunk).len();
+            match &chunk {
+                Chunk::Source(source) if source.start < range.end && range.end <= source.end => {
+                    end = position + range.end - source.start;
+                    break;
+                }
+                Chunk::Splice(index) => {
+                    let s = &self.splices[*index];
+                    if s.start() < range.end && range.end <= s.end() {
+                        end = position + len;
+                        break;
+                    }
+                }
+                Chunk::Source(_) => (),
+            }
+            position += len;
+        }
+        start..end.max(start)
+    }
+
+    /// Find which range of the original string a part of the output came from.
+    ///
+    /// Ranges that start or end inside a replacement are widened to cover the whole range of the
+    /// original string that it replaced; inside an insertion, that is the index it was inserted
+    /// at. Text that was deleted next to the range is not included. The end of the output maps to
+    /// the end of the original string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.insert_left(6, "!");
+    /// assert_eq!(splicer.to_string(), "a beep c !d e");
+    /// assert_eq!(splicer.unmap_range(0..6), 0..3);
+    /// assert_eq!(splicer.unmap_range(3..5), 2..3);
+    /// assert_eq!(splicer.unmap_range(9..11), 6..7);
+    /// assert_eq!(splicer.unmap_range(13..13), 9..9);
+    /// ```
+    pub fn unmap_range(&self, range: Range<usize>) -> Range<usize> {
+        assert!(range.start <= range.end);
+
+        let mut start = None;
+        let mut end = None;
+        let mut position = 0;
+        for chunk in self.chunks(0, self.source.len()) {
+            let len = self.chunk_str(&chunk).len();
+            let output = position..position + len;
+            let original = |index, is_end| match &chunk {
+                Chunk::Source(source) => source.start + index - position,
+                Chunk::Splice(i) if is_end => self.splices[*i].end(),
+                Chunk::Splice(i) => self.splices[*i].start(),
+            };
+            if start.is_none() && output.contains(&range.start) {
+                start = Some(original(range.start, false));
+            }
+            if end.is_none() && output.start < range.end && range.end <= output.end {
+                end = Some(original(range.end, true));
+            }
+            if start.is_some() && end.is_some() {
+                break;
+            }
+            position += len;
+        }
+
+        let len = self.source.len();
+        let start = start.unwrap_or_else(|| {
+            assert!(range.start <= position, "range is out of bounds");
+            len
+        });
+        let end = match end {
+            Some(end) => end,
+            None if range.end == 0 => 0,
+            None => {
+                assert!(range.end <= position, "range is out of bounds");
+                len
+            }
+        };
+        start..end.max(start)
+    }
+
+    /// Track an index into the original string through the splices. Use
+    /// [`Multisplice::anchor_position`] to find where it ends up in the output.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// let cursor = splicer.anchor(4);
+    /// splicer.splice(2, 3, "beep");
+    /// assert_eq!(splicer.anchor_position(cursor), 7);
+    /// ```
+    pub fn anchor(&mut self, offset: usize) -> AnchorId {
+        assert!(offset <= self.source.len());
+        self.anchors.push(offset);
+        AnchorId(self.anchors.len() - 1)
+    }
+
+    /// Find where an anchor ends up in the output. See [`Multisplice::map_offset`] for how
+    /// positions are mapped.
+    ///
+    /// # Panics
+    /// Panics if the anchor was created by a different splicer.
+    pub fn anchor_position(&self, anchor: AnchorId) -> usize {
+        self.map_offset(self.anchors[anchor.0])
+    }
+
+    /// Find where all the anchors end up in the output, in the order they were created.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.anchor(0);
+    /// splicer.anchor(8);
+    /// splicer.splice(2, 3, "beep");
+    /// assert_eq!(splicer.anchor_positions(), vec![0, 11]);
+    /// ```
+    pub fn anchor_positions(&self) -> Vec<usize> {
+        self.anchors
+            .iter()
+            .map(|&offset| self.map_offset(offset))
+            .collect()
+    }
+
+    /// Insert a named marker at `index`, attached to the content to the left of it like
+    /// [`Multisplice::insert_left`]. Markers do not add anything to the output, but their output
+    /// positions can be found with [`Multisplice::marker_positions`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("fn a() {}");
+    /// splicer.insert_left(8, "\n    ");
+    /// splicer.mark(8, "body");
+    /// splicer.insert_left(8, "\n");
+    /// splicer.splice(3, 4, "main");
+    /// assert_eq!(splicer.to_string(), "fn main() {\n    \n}");
+    /// assert_eq!(splicer.marker_positions(), vec![("body", 16)]);
+    /// ```
+    pub fn mark(&mut self, index: usize, name: impl Into<Cow<'a, str>>) {
+        let id = self.insert_left(index, "");
+        self.markers.push((name.into(), id));
+    }
+
+    /// Find the output positions of the markers inserted with [`Multisplice::mark`], in output
+    /// order. Markers inside a spliced range are not part of the output, and are not included.
+    pub fn marker_positions(&self) -> Vec<(&str, usize)> {
+        let mut positions = vec![];
+        let mut position = 0;
+        for chunk in self.chunks(0, self.source.len()) {
+            if let Chunk::Splice(index) = chunk {
+                let id = self.splices[index].id;
+                if let Some((name, _)) = self.markers.iter().find(|(_, marker)| *marker == id) {
+                    positions.push((&**name, position));
+                }
+            }
+            position += self.chunk_str(&chunk).len();
+        }
+        positions
+    }
+}
@@ -0,0 +1,30 @@
+//! Streaming the output to asynchronous writers.
+
+use crate::Multisplice;
+use std::io;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+impl Multisplice<'_> {
+    /// Write the output to an asynchronous writer, one segment at a time, without building the
+    /// new string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(6, 7, "boop");
+    ///
+    /// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// let mut output = vec![];
+    /// runtime.block_on(splicer.write_to_async(&mut output)).unwrap();
+    /// assert_eq!(output, b"a beep c boop e");
+    /// ```
+    pub async fn write_to_async(&self, mut writer: impl AsyncWrite + Unpin) -> io::Result<()> {
+        for segment in self.segments() {
+            writer.write_all(segment.as_bytes()).await?;
+        }
+        writer.flush().await
+    }
+}
@@ -0,0 +1,76 @@
+//! Apply a saved splice plan to a file.
+//!
+//! The plan can be in the patch format written by `Multisplice::save`, or in the JSON format
+//! written by `Multisplice::to_json`. With `--dry-run`, the changes are printed as a unified diff
+//! instead of being written to the file, preceded by the notes explaining them as `#` comments.
+
+use multisplice::{splice_file, Multisplice};
+use similar::TextDiff;
+use std::{env, fs, process};
+
+const USAGE: &str = "usage: multisplice [--dry-run] <plan> <file>";
+
+fn main() {
+    if let Err(message) = run() {
+        eprintln!("multisplice: {}", message);
+        process::exit(1);
+    }
+}
+
+/// Parse a splice plan in either format.
+fn parse_plan<'a>(source: &'a str, plan: &'a str) -> Result<Multisplice<'a>, String> {
+    if plan.starts_with("multisplice ") {
+        Multisplice::from_patch(source, plan).map_err(|err| err.to_string())
+    } else {
+        Multisplice::from_json(source, plan).map_err(|err| err.to_string())
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut dry_run = false;
+    let mut paths = vec![];
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ => paths.push(arg),
+        }
+    }
+    let (plan_path, file_path) = match paths.as_slice() {
+        [plan_path, file_path] => (plan_path, file_path),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let plan = fs::read_to_string(plan_path).map_err(|err| format!("{}: {}", plan_path, err))?;
+
+    if dry_run {
+        let source =
+            fs::read_to_string(file_path).map_err(|err| format!("{}: {}", file_path, err))?;
+        let splicer = parse_plan(&source, &plan)?;
+        for (range, note) in splicer.notes() {
+            println!("# {:?}: {}", range, note);
+        }
+        let output = splicer.render();
+        let diff = TextDiff::from_lines(source.as_str(), &output);
+        print!("{}", diff.unified_diff().header(file_path, file_path));
+        return Ok(());
+    }
+
+    let mut error = None;
+    splice_file(file_path, |splicer| {
+        match parse_plan(splicer.source(), &plan) {
+            Ok(plan) => {
+                let edits = plan
+                    .into_iter()
+                    .map(|(range, value)| (range, value.into_owned()));
+                splicer.extend(edits);
+            }
+            Err(err) => error = Some(err),
+        }
+    })
+    .map_err(|err| format!("{}: {}", file_path, err))?;
+    error.map_or(Ok(()), Err)
+}
@@ -0,0 +1,70 @@
+//! Splicing strings that start with a byte order mark.
+
+use crate::Multisplice;
+
+/// What to do with a leading byte order mark in the output.
+///
+/// Used by [`Multisplice::new_bom_aware`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bom {
+    /// Keep the byte order mark at the start of the output.
+    Keep,
+    /// Remove the byte order mark from the output.
+    Drop,
+}
+
+impl<'a> Multisplice<'a> {
+    /// Create a "multisplicer" for the given string, which treats a leading UTF-8 byte order mark
+    /// as transparent.
+    ///
+    /// Indices into the original string do not count the byte order mark, matching tools that
+    /// strip it before parsing. [`Multisplice::source`] returns the string without it. Whether it
+    /// is part of the output depends on `bom`.
+    ///
+    /// The byte order mark is not a splice. With [`Bom::Keep`], it is part of every full
+    /// output: [`render`](Multisplice::render), [`segments`](Multisplice::segments) and
+    /// everything built on them, like [`reader`](Multisplice::reader),
+    /// [`write_to`](Multisplice::write_to), [`freeze`](Multisplice::freeze) and the source map,
+    /// which does not map it. The queued splices, exported plans, slices and
+    /// [`segments_with_spans`](Multisplice::segments_with_spans) only cover the text after it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Bom, Multisplice, OutputTooLarge, SourceMapOptions};
+    /// use std::io::Read;
+    ///
+    /// let source = "\u{feff}a b c";
+    /// let mut splicer = Multisplice::new_bom_aware(source, Bom::Keep);
+    /// assert_eq!(splicer.source(), "a b c");
+    /// assert_eq!(splicer.render(), source);
+    /// splicer.splice(2, 3, "beep");
+    /// assert_eq!(splicer.to_string(), "\u{feff}a beep c");
+    /// assert_eq!(splicer.deltas(), vec![(2..3, 4, 3)]);
+    /// let mut output = vec![];
+    /// splicer.write_to(&mut output).unwrap();
+    /// assert_eq!(output, "\u{feff}a beep c".as_bytes());
+    /// let mut output = String::new();
+    /// splicer.reader().read_to_string(&mut output).unwrap();
+    /// assert_eq!(output, "\u{feff}a beep c");
+    /// assert_eq!(splicer.render_bounded(9), Err(OutputTooLarge { limit: 9 }));
+    /// // The first column of the source map is the byte order mark
+    /// let map = splicer.generate_map(&SourceMapOptions::new("input.txt"));
+    /// assert_eq!(map.mappings, "CAAA,EAAE,IAAC");
+    /// assert_eq!(splicer.freeze().to_string(), "\u{feff}a beep c");
+    ///
+    /// let mut splicer = Multisplice::new_bom_aware(source, Bom::Drop);
+    /// splicer.splice(2, 3, "beep");
+    /// assert_eq!(splicer.to_string(), "a beep c");
+    /// ```
+    pub fn new_bom_aware(source: &'a str, bom: Bom) -> Self {
+        let rest = match source.strip_prefix('\u{feff}') {
+            Some(rest) => rest,
+            None => return Self::new(source),
+        };
+        let mut splicer = Self::new(rest);
+        if bom == Bom::Keep {
+            splicer.bom = Some(&source[..source.len() - rest.len()]);
+        }
+        splicer
+    }
+}
@@ -0,0 +1,50 @@
+//! Limiting the size of the output.
+
+use crate::Multisplice;
+use std::{borrow::Cow, error::Error, fmt};
+
+/// The error returned by [`Multisplice::render_bounded`] when the output is too large.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputTooLarge {
+    /// The maximum length of the output, in bytes.
+    pub limit: usize,
+}
+
+impl fmt::Display for OutputTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Output is longer than {} bytes", self.limit)
+    }
+}
+
+impl Error for OutputTooLarge {}
+
+impl<'a> Multisplice<'a> {
+    /// Execute the splices, returning the new string, unless it would be longer than `max_len`
+    /// bytes.
+    ///
+    /// The length is checked before the output is assembled, and the check stops at the first
+    /// piece that goes over the limit, so huge replacement values are never copied.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, OutputTooLarge};
+    ///
+    /// let mut splicer = Multisplice::new("Hello {name}!");
+    /// splicer.splice(6, 12, "world");
+    /// assert_eq!(splicer.render_bounded(16).unwrap(), "Hello world!");
+    ///
+    /// let mut splicer = Multisplice::new("Hello {name}!");
+    /// splicer.splice(6, 12, "world".repeat(1000));
+    /// assert_eq!(splicer.render_bounded(16), Err(OutputTooLarge { limit: 16 }));
+    /// ```
+    pub fn render_bounded(&self, max_len: usize) -> Result<Cow<'a, str>, OutputTooLarge> {
+        let mut len = 0usize;
+        for segment in self.segments() {
+            len += segment.len();
+            if len > max_len {
+                return Err(OutputTooLarge { limit: max_len });
+            }
+        }
+        Ok(self.render())
+    }
+}
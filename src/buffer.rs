@@ -0,0 +1,106 @@
+//! Rendering without allocating.
+
+use crate::Multisplice;
+use std::{error::Error, fmt};
+
+/// The error returned by [`Multisplice::render_to_slice`] when the output does not fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferTooSmall {
+    /// The number of bytes needed to hold the output.
+    pub needed: usize,
+    /// The number of bytes that were available.
+    pub available: usize,
+}
+
+impl fmt::Display for BufferTooSmall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Buffer too small: output needs {} bytes, but only {} are available",
+            self.needed, self.available
+        )
+    }
+}
+
+impl Error for BufferTooSmall {}
+
+impl Multisplice<'_> {
+    /// Execute the splices, writing the new string to the start of `buffer`. Returns the number
+    /// of bytes written.
+    ///
+    /// If the output does not fit, nothing is written, and the error says how large the buffer
+    /// needs to be.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{BufferTooSmall, Multisplice};
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    ///
+    /// let mut buffer = [0; 16];
+    /// let len = splicer.render_to_slice(&mut buffer).unwrap();
+    /// assert_eq!(&buffer[..len], b"a beep c d e");
+    ///
+    /// let mut buffer = [0; 8];
+    /// assert_eq!(
+    ///     splicer.render_to_slice(&mut buffer),
+    ///     Err(BufferTooSmall { needed: 12, available: 8 }),
+    /// );
+    /// ```
+    pub fn render_to_slice(&self, buffer: &mut [u8]) -> Result<usize, BufferTooSmall> {
+        let needed = self.rendered_len();
+        if needed > buffer.len() {
+            return Err(BufferTooSmall {
+                needed,
+                available: buffer.len(),
+            });
+        }
+        let mut position = 0;
+        for segment in self.segments() {
+            let end = position + segment.len();
+            buffer[position..end].copy_from_slice(segment.as_bytes());
+            position = end;
+        }
+        Ok(position)
+    }
+    /// Execute the splices, passing each piece of the new string to `sink`, without allocating.
+    ///
+    /// Pieces are passed in order, and empty pieces are skipped. If `sink` returns an error,
+    /// rendering stops and the error is returned. Values that are escaped or post-processed are
+    /// rendered into a buffer the first time they are needed, so this only avoids allocations when
+    /// there is no escaper or render hook.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    ///
+    /// let mut ring = [0u8; 4];
+    /// let mut written = 0;
+    /// let result = splicer.render_with(|segment| {
+    ///     for byte in segment.bytes() {
+    ///         ring[written % ring.len()] = byte;
+    ///         written += 1;
+    ///     }
+    ///     Ok::<(), ()>(())
+    /// });
+    /// assert_eq!(result, Ok(()));
+    /// assert_eq!(written, 12);
+    ///
+    /// let mut segments = 0;
+    /// let result = splicer.render_with(|_| {
+    ///     segments += 1;
+    ///     if segments == 2 { Err("full") } else { Ok(()) }
+    /// });
+    /// assert_eq!(result, Err("full"));
+    /// ```
+    pub fn render_with<E>(&self, mut sink: impl FnMut(&str) -> Result<(), E>) -> Result<(), E> {
+        for segment in self.segments() {
+            sink(segment)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+//! Allocating replacement values in an arena.
+
+use crate::{Multisplice, SpliceId};
+use bumpalo::{collections::String as BumpString, Bump};
+use std::fmt::{self, Write};
+
+impl<'a> Multisplice<'a> {
+    /// Replace the characters from index `start` up to (but not including) index `end` by the
+    /// formatted `value`, which is allocated in `bump`.
+    ///
+    /// This avoids a heap allocation for each generated replacement value: they are all freed at
+    /// once when the arena is dropped or reset.
+    ///
+    /// # Example
+    /// ```rust
+    /// use bumpalo::Bump;
+    /// use multisplice::Multisplice;
+    ///
+    /// let bump = Bump::new();
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// for (index, word) in [(2, "beep"), (6, "boop")] {
+    ///     splicer.splice_alloc(&bump, index, index + 1, format_args!("<{}>", word));
+    /// }
+    /// assert_eq!(splicer.to_string(), "a <beep> c <boop> e");
+    /// ```
+    pub fn splice_alloc(
+        &mut self,
+        bump: &'a Bump,
+        start: usize,
+        end: usize,
+        value: impl fmt::Display,
+    ) -> SpliceId {
+        let mut buffer = BumpString::new_in(bump);
+        write!(buffer, "{}", value).expect("a Display implementation returned an error");
+        self.splice(start, end, buffer.into_bump_str())
+    }
+}
@@ -0,0 +1,52 @@
+//! Building the output as shared bytes.
+
+use crate::Multisplice;
+use bytes::Bytes;
+use std::borrow::Cow;
+
+impl Multisplice<'_> {
+    /// Execute the splices, returning the new string as [`Bytes`].
+    ///
+    /// The output is built in a single allocation, which the `Bytes` takes over without copying.
+    /// If the output borrows from the original string or a replacement value, it is copied; see
+    /// [`Multisplice::to_static_bytes`] to avoid that for `'static` strings.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// assert_eq!(splicer.to_bytes(), "a beep c d e");
+    /// ```
+    pub fn to_bytes(&self) -> Bytes {
+        match self.render() {
+            Cow::Borrowed(output) => Bytes::copy_from_slice(output.as_bytes()),
+            Cow::Owned(output) => Bytes::from(output),
+        }
+    }
+}
+
+impl Multisplice<'static> {
+    /// Execute the splices, returning the new string as [`Bytes`].
+    ///
+    /// Like [`Multisplice::to_bytes`], but if no splices change the output, this returns a view of
+    /// the original string without copying it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// static TEMPLATE: &str = "<p>{name}</p>";
+    ///
+    /// let splicer = Multisplice::new(TEMPLATE);
+    /// let body = splicer.to_static_bytes();
+    /// assert_eq!(body.as_ptr(), TEMPLATE.as_ptr());
+    /// ```
+    pub fn to_static_bytes(&self) -> Bytes {
+        match self.render() {
+            Cow::Borrowed(output) => Bytes::from_static(output.as_bytes()),
+            Cow::Owned(output) => Bytes::from(output),
+        }
+    }
+}
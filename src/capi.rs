@@ -0,0 +1,125 @@
+//! A C API.
+//!
+//! Build a shared library with `cargo rustc --release --features capi --crate-type cdylib`. All
+//! strings are passed as a pointer to UTF-8 bytes and a length, and are copied, so callers can free
+//! them as soon as a function returns.
+
+use crate::owned::OwnedSplicer;
+use std::{ffi::c_int, ptr, slice};
+
+/// An opaque splicer handle for C code.
+#[derive(Debug)]
+pub struct MsSplicer {
+    inner: OwnedSplicer,
+}
+
+/// Copy a string passed in from C. Returns `None` if it is not valid UTF-8.
+///
+/// # Safety
+/// `ptr` must point to `len` readable bytes, or `len` must be 0.
+unsafe fn copy_str(ptr: *const u8, len: usize) -> Option<String> {
+    if len == 0 {
+        return Some(String::new());
+    }
+    let bytes = unsafe { slice::from_raw_parts(ptr, len) };
+    String::from_utf8(bytes.to_vec()).ok()
+}
+
+/// Create a splicer for the `len` bytes at `source`.
+///
+/// Returns a null pointer if the source is not valid UTF-8. The splicer must be freed with
+/// [`ms_free`].
+///
+/// # Safety
+/// `source` must point to `len` readable bytes, or `len` must be 0.
+///
+/// # Example
+/// ```rust
+/// use multisplice::capi::*;
+///
+/// let source = "a b c d e";
+/// unsafe {
+///     let splicer = ms_new(source.as_ptr(), source.len());
+///     assert_eq!(ms_splice(splicer, 2, 3, "beep".as_ptr(), 4), 0);
+///     // Overlapping splices are rejected
+///     assert_eq!(ms_splice(splicer, 0, 4, "".as_ptr(), 0), -1);
+///     // So are ranges past the end
+///     assert_eq!(ms_splice(splicer, 8, 12, "".as_ptr(), 0), -1);
+///
+///     let mut output = vec![0; ms_render(splicer, std::ptr::null_mut(), 0)];
+///     ms_render(splicer, output.as_mut_ptr(), output.len());
+///     assert_eq!(output, b"a beep c d e");
+///     ms_free(splicer);
+/// }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn ms_new(source: *const u8, len: usize) -> *mut MsSplicer {
+    match unsafe { copy_str(source, len) } {
+        Some(source) => Box::into_raw(Box::new(MsSplicer {
+            inner: OwnedSplicer::new(source),
+        })),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Replace the bytes from index `start` up to (but not including) index `end` by the `len` bytes
+/// at `value`.
+///
+/// Returns 0 on success, or -1 if the range is out of bounds, does not fall on UTF-8 character
+/// boundaries, overlaps a range that was already spliced, or if the value is not valid UTF-8.
+///
+/// # Safety
+/// `splicer` must be a splicer returned by [`ms_new`]. `value` must point to `len` readable
+/// bytes, or `len` must be 0.
+#[no_mangle]
+pub unsafe extern "C" fn ms_splice(
+    splicer: *mut MsSplicer,
+    start: usize,
+    end: usize,
+    value: *const u8,
+    len: usize,
+) -> c_int {
+    let splicer = unsafe { &mut *splicer };
+    let value = match unsafe { copy_str(value, len) } {
+        Some(value) => value,
+        None => return -1,
+    };
+    match splicer.inner.splice(start..end, value) {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+
+/// Execute the splices, writing the new string to `output` if it fits in `capacity` bytes.
+///
+/// Returns the length of the new string in bytes. If that is larger than `capacity`, nothing is
+/// written, so callers can pass a null pointer and a capacity of 0 to get the length first. The
+/// output is not null-terminated.
+///
+/// # Safety
+/// `splicer` must be a splicer returned by [`ms_new`]. `output` must point to `capacity`
+/// writable bytes, or `capacity` must be 0.
+#[no_mangle]
+pub unsafe extern "C" fn ms_render(
+    splicer: *const MsSplicer,
+    output: *mut u8,
+    capacity: usize,
+) -> usize {
+    let splicer = unsafe { &*splicer };
+    let rendered = splicer.inner.splicer().render();
+    if rendered.len() <= capacity && !rendered.is_empty() {
+        unsafe { ptr::copy_nonoverlapping(rendered.as_ptr(), output, rendered.len()) };
+    }
+    rendered.len()
+}
+
+/// Free a splicer returned by [`ms_new`]. Passing a null pointer does nothing.
+///
+/// # Safety
+/// `splicer` must be a splicer returned by [`ms_new`] that was not freed before, or null.
+#[no_mangle]
+pub unsafe extern "C" fn ms_free(splicer: *mut MsSplicer) {
+    if !splicer.is_null() {
+        drop(unsafe { Box::from_raw(splicer) });
+    }
+}
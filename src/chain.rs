@@ -0,0 +1,51 @@
+//! Queueing splices in a single expression.
+
+use crate::Multisplice;
+use std::{borrow::Cow, ops::RangeBounds};
+
+impl<'a> Multisplice<'a> {
+    /// Replace the characters in the range `range` by the string `value`, returning the splicer
+    /// so more splices can be chained onto it.
+    ///
+    /// This is [`Multisplice::splice_range`] for building a splicer in a single expression.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let output = Multisplice::new("a b c d e")
+    ///     .replace(2..3, "beep")
+    ///     .delete(3..5)
+    ///     .insert(9, "!")
+    ///     .render();
+    /// assert_eq!(output, "a beep d e!");
+    /// ```
+    #[inline]
+    pub fn replace(
+        mut self,
+        range: impl RangeBounds<usize>,
+        value: impl Into<Cow<'a, str>>,
+    ) -> Self {
+        self.splice_range(range, value);
+        self
+    }
+
+    /// Remove the characters in the range `range`, returning the splicer so more splices can be
+    /// chained onto it.
+    ///
+    /// See [`Multisplice::replace`].
+    #[inline]
+    pub fn delete(self, range: impl RangeBounds<usize>) -> Self {
+        self.replace(range, "")
+    }
+
+    /// Insert `value` at index `index`, returning the splicer so more splices can be chained onto
+    /// it. Like empty splices, the value is attached to the content to the right of it.
+    ///
+    /// See [`Multisplice::replace`] and [`Multisplice::insert_right`].
+    #[inline]
+    pub fn insert(mut self, index: usize, value: impl Into<Cow<'a, str>>) -> Self {
+        self.insert_right(index, value);
+        self
+    }
+}
@@ -0,0 +1,129 @@
+//! Methods with the names and semantics of the `magic-string` JavaScript package, for porting
+//! code that uses it.
+//!
+//! Import the [`MagicString`] trait to use them on a [`Multisplice`]. Indices are byte offsets
+//! into the original string, instead of UTF-16 code units.
+//!
+//! # Example
+//! ```rust
+//! use multisplice::{compat::MagicString, Multisplice};
+//!
+//! let mut s = Multisplice::new("problems = 99");
+//! s.overwrite(0, 8, "answer")?
+//!     .overwrite(11, 13, "42")?
+//!     .append_left(13, ";");
+//! assert!(s.has_changed());
+//! assert_eq!(s.to_string(), "answer = 42;");
+//! assert_eq!(s.snip(11, 13).to_string(), "42;");
+//! # Ok::<(), multisplice::SpliceError>(())
+//! ```
+
+use crate::{Multisplice, SpliceError};
+use std::borrow::Cow;
+
+/// The editing methods of `magic-string`'s `MagicString` class.
+pub trait MagicString<'a> {
+    /// Replace the characters from index `start` up to (but not including) index `end` by
+    /// `content`, like `s.overwrite(start, end, content)`.
+    ///
+    /// # Errors
+    /// Returns an error if the range overlaps a range that was already overwritten or removed,
+    /// or is out of bounds, where `magic-string` would throw.
+    fn overwrite(
+        &mut self,
+        start: usize,
+        end: usize,
+        content: impl Into<Cow<'a, str>>,
+    ) -> Result<&mut Self, SpliceError>;
+
+    /// Insert `content` at `index`, attached to the content before it, like
+    /// `s.appendLeft(index, content)`.
+    fn append_left(&mut self, index: usize, content: impl Into<Cow<'a, str>>) -> &mut Self;
+
+    /// Insert `content` at `index`, attached to the content after it, like
+    /// `s.appendRight(index, content)`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{compat::MagicString, Multisplice};
+    ///
+    /// let mut s = Multisplice::new("ab");
+    /// s.append_right(1, "2").append_left(1, "1").append_right(1, "3");
+    /// assert_eq!(s.to_string(), "a123b");
+    /// ```
+    fn append_right(&mut self, index: usize, content: impl Into<Cow<'a, str>>) -> &mut Self;
+
+    /// Remove the characters from index `start` up to (but not including) index `end`, like
+    /// `s.remove(start, end)`.
+    ///
+    /// # Errors
+    /// Returns an error in the same cases as [`MagicString::overwrite`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{compat::MagicString, Multisplice};
+    ///
+    /// let mut s = Multisplice::new("a b c");
+    /// s.remove(1, 3)?;
+    /// assert_eq!(s.to_string(), "a c");
+    /// assert!(s.remove(2, 4).is_err());
+    /// # Ok::<(), multisplice::SpliceError>(())
+    /// ```
+    fn remove(&mut self, start: usize, end: usize) -> Result<&mut Self, SpliceError>;
+
+    /// Create a splicer for the part of the original string from index `start` up to (but not
+    /// including) index `end`, with copies of the splices in that part, like `s.snip(start, end)`.
+    ///
+    /// See [`Multisplice::snip`].
+    fn snip(&self, start: usize, end: usize) -> Self;
+
+    /// Check if the output is different from the original string, like `s.hasChanged()`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{compat::MagicString, Bom, Multisplice};
+    ///
+    /// let mut s = Multisplice::new_bom_aware("\u{feff}a b c", Bom::Keep);
+    /// assert!(!s.has_changed());
+    /// s.overwrite(2, 3, "b")?;
+    /// assert!(!s.has_changed());
+    /// s.overwrite(4, 5, "beep")?;
+    /// assert!(s.has_changed());
+    /// # Ok::<(), multisplice::SpliceError>(())
+    /// ```
+    fn has_changed(&self) -> bool;
+}
+
+impl<'a> MagicString<'a> for Multisplice<'a> {
+    fn overwrite(
+        &mut self,
+        start: usize,
+        end: usize,
+        content: impl Into<Cow<'a, str>>,
+    ) -> Result<&mut Self, SpliceError> {
+        self.try_splice(start, end, content)?;
+        Ok(self)
+    }
+
+    fn append_left(&mut self, index: usize, content: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.insert_left(index, content);
+        self
+    }
+
+    fn append_right(&mut self, index: usize, content: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.insert_right(index, content);
+        self
+    }
+
+    fn remove(&mut self, start: usize, end: usize) -> Result<&mut Self, SpliceError> {
+        self.overwrite(start, end, "")
+    }
+
+    fn snip(&self, start: usize, end: usize) -> Self {
+        Multisplice::snip(self, start, end)
+    }
+
+    fn has_changed(&self) -> bool {
+        self.has_changes()
+    }
+}
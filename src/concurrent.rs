@@ -0,0 +1,83 @@
+//! Collecting splices from several threads.
+
+use crate::{Multisplice, SpliceError};
+use std::{
+    borrow::Cow,
+    ops::Range,
+    sync::{Mutex, PoisonError},
+};
+
+/// Collects splices for a source string from several threads at once, and turns them into a
+/// [`Multisplice`] when all of them are done.
+///
+/// Queueing a splice only takes `&self`, so a collector can be shared between threads — for
+/// example with [`std::thread::scope`] — without wrapping it in a mutex. Conflicts between the
+/// splices are only resolved by [`ConcurrentMultisplice::finish`].
+#[derive(Debug)]
+pub struct ConcurrentMultisplice<'a> {
+    source: &'a str,
+    edits: Mutex<Vec<(Range<usize>, Cow<'a, str>)>>,
+}
+
+impl<'a> ConcurrentMultisplice<'a> {
+    /// Create a collector for splices of the given string.
+    pub fn new(source: &'a str) -> Self {
+        ConcurrentMultisplice {
+            source,
+            edits: Mutex::new(vec![]),
+        }
+    }
+
+    /// Get the original string.
+    #[inline]
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Queue a replacement of the characters in `range` by `value`.
+    ///
+    /// Conflicts are not checked until [`ConcurrentMultisplice::finish`].
+    pub fn splice(&self, range: Range<usize>, value: impl Into<Cow<'a, str>>) {
+        self.edits
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push((range, value.into()));
+    }
+
+    /// Build a [`Multisplice`] with all the queued splices.
+    ///
+    /// The order in which threads queued their splices does not matter: splices are applied
+    /// sorted by range, so of two overlapping splices, the one that starts first wins, or the
+    /// shorter one if they start at the same index. The splices that could not be applied are
+    /// returned as errors, like [`Multisplice::splice_all`] does.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{ConcurrentMultisplice, SpliceError};
+    ///
+    /// let collector = ConcurrentMultisplice::new("a b c d e");
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| collector.splice(2..3, "beep"));
+    ///     scope.spawn(|| collector.splice(6..7, "boop"));
+    ///     scope.spawn(|| collector.splice(2..5, "conflict"));
+    /// });
+    /// let (splicer, errors) = collector.finish();
+    /// assert_eq!(splicer.to_string(), "a beep c boop e");
+    /// assert_eq!(errors, vec![SpliceError::Overlap { existing: 2..3, new: 2..5 }]);
+    /// ```
+    pub fn finish(self) -> (Multisplice<'a>, Vec<SpliceError>) {
+        let mut edits = self
+            .edits
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        edits.sort_by(|(a, a_value), (b, b_value)| {
+            (a.start, a.end, a_value).cmp(&(b.start, b.end, b_value))
+        });
+        let mut splicer = Multisplice::with_capacity(self.source, edits.len());
+        let errors = match splicer.splice_all(edits) {
+            Ok(()) => vec![],
+            Err(errors) => errors.into_iter().map(|(_, error)| error).collect(),
+        };
+        (splicer, errors)
+    }
+}
@@ -0,0 +1,109 @@
+//! Human-readable views of the queued splices, for debugging.
+
+use crate::{Chunk, Multisplice, Splice};
+use std::fmt;
+
+/// Number of characters of the original string to show around a splice in `Debug` output.
+const SNIPPET_CONTEXT: usize = 8;
+
+impl Multisplice<'_> {
+    /// Render the output with visible markers at each splice, like `⟦old⟧→⟦new⟧`.
+    ///
+    /// Splices that are hidden by an earlier splice covering their range are not shown.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.insert_left(6, "!");
+    /// assert_eq!(splicer.debug_render(), "a ⟦b⟧→⟦beep⟧ c ⟦⟧→⟦!⟧d e");
+    /// ```
+    pub fn debug_render(&self) -> String {
+        let mut output = String::with_capacity(self.source.len());
+        for chunk in self.chunks(0, self.source.len()) {
+            match chunk {
+                Chunk::Source(range) => output.push_str(&self.source[range]),
+                Chunk::Splice(index) => {
+                    let splice = &self.splices[index];
+                    output.push('⟦');
+                    output.push_str(&self.source[splice.range()]);
+                    output.push_str("⟧→⟦");
+                    output.push_str(self.chunk_str(&chunk));
+                    output.push('⟧');
+                }
+            }
+        }
+        output
+    }
+}
+
+/// A splice with some of the original string around it, for `Debug` output.
+struct DebugSplice<'s, 'a> {
+    source: &'s str,
+    splice: &'s Splice<'a>,
+}
+
+impl fmt::Debug for DebugSplice<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (start, end) = (self.splice.start(), self.splice.end());
+        let before = &self.source[..start];
+        let before = match before.char_indices().rev().nth(SNIPPET_CONTEXT - 1) {
+            Some((i, _)) => &before[i..],
+            None => before,
+        };
+        let after = &self.source[end..];
+        let after = match after.char_indices().nth(SNIPPET_CONTEXT) {
+            Some((i, _)) => &after[..i],
+            None => after,
+        };
+        let snippet = format!("{}⟦{}⟧{}", before, &self.source[start..end], after);
+        write!(
+            f,
+            "{:?} {:?} → {:?}",
+            self.splice.range(),
+            snippet,
+            self.splice.value
+        )
+    }
+}
+
+/// Shows the original string and each queued splice with some context.
+///
+/// # Example
+/// ```rust
+/// use multisplice::Multisplice;
+///
+/// let mut splicer = Multisplice::new("a b c d e");
+/// splicer.splice(2, 3, "beep");
+/// assert_eq!(
+///     format!("{:?}", splicer),
+///     r#"Multisplice { source: "a b c d e", splices: [2..3 "a ⟦b⟧ c d e" → "beep"] }"#,
+/// );
+/// ```
+impl fmt::Debug for Multisplice<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let splices = self.splices.iter().map(|splice| DebugSplice {
+            source: self.source,
+            splice,
+        });
+        f.debug_struct("Multisplice")
+            .field("source", &self.source)
+            .field("splices", &DebugList(splices))
+            .finish()
+    }
+}
+
+/// Formats the items of an iterator as a list.
+struct DebugList<I>(I);
+
+impl<I> fmt::Debug for DebugList<I>
+where
+    I: Iterator + Clone,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.0.clone()).finish()
+    }
+}
@@ -0,0 +1,188 @@
+//! Splicing fields of CSV and TSV data.
+
+use crate::{Multisplice, SpliceId};
+use std::{borrow::Cow, ops::Range};
+
+/// How the fields of delimited data, like CSV or TSV, are separated and quoted.
+///
+/// Records are separated by `\n` or `\r\n`. Fields are separated by the delimiter, and may be
+/// surrounded by quote characters to contain delimiters, quotes and line breaks. Quote characters
+/// inside a quoted field are doubled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// The byte that separates fields.
+    delimiter: u8,
+    /// The byte that quotes fields.
+    quote: u8,
+}
+
+impl Default for CsvOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CsvOptions {
+    /// Create the options for comma-separated values, quoted with `"`.
+    #[inline]
+    pub fn new() -> Self {
+        CsvOptions {
+            delimiter: b',',
+            quote: b'"',
+        }
+    }
+
+    /// Create the options for tab-separated values, quoted with `"`.
+    #[inline]
+    pub fn tsv() -> Self {
+        Self::new().delimiter(b'\t')
+    }
+
+    /// Set the byte that separates fields. It must be an ASCII character.
+    #[inline]
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        assert!(delimiter.is_ascii());
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set the byte that quotes fields. It must be an ASCII character.
+    #[inline]
+    pub fn quote(mut self, quote: u8) -> Self {
+        assert!(quote.is_ascii());
+        self.quote = quote;
+        self
+    }
+
+    /// Find the range of a field in `source`, including its quotes.
+    fn field_range(&self, source: &str, record: usize, field: usize) -> Option<Range<usize>> {
+        let bytes = source.as_bytes();
+        let mut current_record = 0;
+        let mut current_field = 0;
+        let mut start = 0;
+        let mut in_quotes = false;
+        let mut i = 0;
+        while i < bytes.len() {
+            let byte = bytes[i];
+            if in_quotes {
+                if byte == self.quote {
+                    if bytes.get(i + 1) == Some(&self.quote) {
+                        i += 1;
+                    } else {
+                        in_quotes = false;
+                    }
+                }
+            } else if byte == self.quote && i == start {
+                in_quotes = true;
+            } else if byte == self.delimiter || byte == b'\n' {
+                if current_record == record && current_field == field {
+                    let end = if byte == b'\n' && i > start && bytes[i - 1] == b'\r' {
+                        i - 1
+                    } else {
+                        i
+                    };
+                    return Some(start..end);
+                }
+                if byte == b'\n' {
+                    if current_record == record {
+                        return None;
+                    }
+                    current_record += 1;
+                    current_field = 0;
+                } else {
+                    current_field += 1;
+                }
+                start = i + 1;
+            }
+            i += 1;
+        }
+        // A line break at the very end does not start another record
+        let trailing = start == bytes.len() && current_field == 0 && !bytes.is_empty();
+        if current_record == record && current_field == field && !trailing {
+            Some(start..bytes.len())
+        } else {
+            None
+        }
+    }
+
+    /// Quote `value` as a field, if it was quoted before or if it needs quotes.
+    fn quote_field<'v>(&self, value: Cow<'v, str>, was_quoted: bool) -> Cow<'v, str> {
+        let quote = char::from(self.quote);
+        let needs_quotes = value.bytes().any(|byte| {
+            byte == self.delimiter || byte == self.quote || byte == b'\n' || byte == b'\r'
+        });
+        if !was_quoted && !needs_quotes {
+            return value;
+        }
+        let mut quoted = String::with_capacity(value.len() + 2);
+        quoted.push(quote);
+        for c in value.chars() {
+            if c == quote {
+                quoted.push(quote);
+            }
+            quoted.push(c);
+        }
+        quoted.push(quote);
+        Cow::Owned(quoted)
+    }
+}
+
+impl<'a> Multisplice<'a> {
+    /// Find the range of the original string holding field `field` of record `record`, both
+    /// zero-based, of delimited data like CSV. The range includes the quotes around the field,
+    /// if it has any, but not the delimiters or line breaks around it.
+    ///
+    /// Returns `None` if there is no such field.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{CsvOptions, Multisplice};
+    ///
+    /// let splicer = Multisplice::new("name,note\r\nbeep,\"a, b\"\r\nboop,c\r\n");
+    /// let options = CsvOptions::new();
+    /// assert_eq!(splicer.csv_field_range(&options, 0, 1), Some(5..9));
+    /// assert_eq!(splicer.csv_field_range(&options, 1, 1), Some(16..22));
+    /// assert_eq!(splicer.csv_field_range(&options, 2, 0), Some(24..28));
+    /// assert_eq!(splicer.csv_field_range(&options, 2, 2), None);
+    /// assert_eq!(splicer.csv_field_range(&options, 3, 0), None);
+    /// ```
+    pub fn csv_field_range(
+        &self,
+        options: &CsvOptions,
+        record: usize,
+        field: usize,
+    ) -> Option<Range<usize>> {
+        options.field_range(self.source, record, field)
+    }
+
+    /// Replace field `field` of record `record`, both zero-based, of delimited data like CSV.
+    ///
+    /// The new value is quoted if the old value was quoted, or if it contains a delimiter, a
+    /// quote or a line break. Returns `None` if there is no such field.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{CsvOptions, Multisplice};
+    ///
+    /// let mut splicer = Multisplice::new("name\tnote\nbeep\t\"a\"\nboop\tb\n");
+    /// let options = CsvOptions::tsv();
+    /// splicer.replace_field(&options, 1, 1, "c").unwrap();
+    /// splicer.replace_field(&options, 2, 1, "say \"hi\"").unwrap();
+    /// splicer.replace_field(&options, 2, 0, "boop").unwrap();
+    /// assert!(splicer.replace_field(&options, 3, 0, "nope").is_none());
+    /// assert_eq!(splicer.to_string(), "name\tnote\nbeep\t\"c\"\nboop\t\"say \"\"hi\"\"\"\n");
+    /// ```
+    pub fn replace_field(
+        &mut self,
+        options: &CsvOptions,
+        record: usize,
+        field: usize,
+        value: impl Into<Cow<'a, str>>,
+    ) -> Option<SpliceId> {
+        let range = options.field_range(self.source, record, field)?;
+        let was_quoted = self.source.as_bytes().get(range.start) == Some(&options.quote);
+        let value = options.quote_field(value.into(), was_quoted);
+        Some(self.splice_cow(range.start, range.end, value))
+    }
+}
@@ -0,0 +1,135 @@
+//! Importing machine-applicable suggestions from rustc and clippy diagnostics.
+
+use crate::Workspace;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// A line of `--message-format=json` output.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Message {
+    /// A diagnostic wrapped in a cargo message.
+    Cargo { message: Diagnostic },
+    /// A diagnostic printed by rustc directly.
+    Rustc(Diagnostic),
+    /// Any other cargo message, like a build artifact notification.
+    Other(serde::de::IgnoredAny),
+}
+
+/// The parts of a rustc diagnostic that are needed to find suggestions.
+#[derive(Deserialize)]
+struct Diagnostic {
+    #[serde(default)]
+    spans: Vec<DiagnosticSpan>,
+    #[serde(default)]
+    children: Vec<Diagnostic>,
+}
+
+/// The parts of a rustc diagnostic span that are needed to apply suggestions.
+#[derive(Deserialize)]
+struct DiagnosticSpan {
+    file_name: PathBuf,
+    byte_start: usize,
+    byte_end: usize,
+    suggested_replacement: Option<String>,
+    suggestion_applicability: Option<String>,
+}
+
+/// A suggestion for a single file.
+type Suggestion = (PathBuf, Vec<(Range<usize>, String)>);
+
+impl Diagnostic {
+    /// Collect the machine-applicable suggestions of this diagnostic and its children. All the
+    /// replacements of a multi-part suggestion are kept together, split up by file.
+    fn collect_suggestions(self, suggestions: &mut Vec<Suggestion>) {
+        let mut parts: Vec<Suggestion> = vec![];
+        for span in self.spans {
+            if span.suggestion_applicability.as_deref() != Some("MachineApplicable") {
+                continue;
+            }
+            let replacement = match span.suggested_replacement {
+                Some(replacement) => replacement,
+                None => continue,
+            };
+            let (file_name, edit) = (
+                span.file_name,
+                (span.byte_start..span.byte_end, replacement),
+            );
+            match parts.iter_mut().find(|(path, _)| *path == file_name) {
+                Some((_, edits)) => edits.push(edit),
+                None => parts.push((file_name, vec![edit])),
+            }
+        }
+        suggestions.extend(parts);
+        for child in self.children {
+            child.collect_suggestions(suggestions);
+        }
+    }
+}
+
+impl<'a> Workspace<'a> {
+    /// Create a workspace from the JSON diagnostics printed by `cargo check --message-format=json`,
+    /// `cargo clippy --message-format=json` or `rustc --error-format=json`, with the
+    /// machine-applicable suggestions queued as splices.
+    ///
+    /// `source` is called with the path of every file that has suggestions, as printed by the
+    /// compiler, and returns the contents of the file. Files for which it returns `None` are
+    /// skipped. Each suggestion is queued with [`Workspace::splice_many`], so the parts of a
+    /// multi-part suggestion are applied together or not at all, and suggestions that conflict
+    /// with each other are added to [`Workspace::conflicts`]. Suggestions that appear more than
+    /// once, for example because a file is part of several targets, are only queued once.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Workspace;
+    ///
+    /// let json = r#"{"reason":"compiler-message","message":{"message":"unused variable: `x`",
+    /// "spans":[{"file_name":"src/main.rs","byte_start":8,"byte_end":9}],
+    /// "children":[{"message":"prefix it with an underscore","spans":[{"file_name":"src/main.rs",
+    /// "byte_start":8,"byte_end":9,"suggested_replacement":"_x",
+    /// "suggestion_applicability":"MachineApplicable"}],"children":[]}]}}
+    /// {"reason":"build-finished","success":true}"#;
+    /// // Diagnostics are printed one per line.
+    /// let json = json.replace("\n\"", "\"");
+    ///
+    /// let source = "let mut x = 1;";
+    /// let workspace = Workspace::from_diagnostics(&json, |_| Some(source)).unwrap();
+    /// let splicer = workspace.file("src/main.rs").unwrap();
+    /// assert_eq!(splicer.to_string(), "let mut _x = 1;");
+    /// ```
+    pub fn from_diagnostics(
+        json: &str,
+        mut source: impl FnMut(&Path) -> Option<&'a str>,
+    ) -> Result<Self, serde_json::Error> {
+        let mut suggestions = vec![];
+        for line in json.lines().filter(|line| !line.trim().is_empty()) {
+            match serde_json::from_str(line)? {
+                Message::Cargo { message } | Message::Rustc(message) => {
+                    message.collect_suggestions(&mut suggestions)
+                }
+                Message::Other(_) => {}
+            }
+        }
+
+        let mut workspace = Workspace::new();
+        let mut seen = HashSet::new();
+        for suggestion in suggestions {
+            if !seen.insert(suggestion.clone()) {
+                continue;
+            }
+            let (path, edits) = suggestion;
+            if workspace.file(&path).is_none() {
+                match source(&path) {
+                    Some(contents) => {
+                        workspace.add_file(path.clone(), contents);
+                    }
+                    None => continue,
+                }
+            }
+            workspace.splice_many(&path, edits);
+        }
+        Ok(workspace)
+    }
+}
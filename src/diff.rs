@@ -0,0 +1,67 @@
+//! Creating splices by diffing two strings using `similar`.
+
+use crate::Multisplice;
+use similar::{DiffOp, TextDiff};
+
+/// Get the byte index of every token in a tokenized string, plus the total length.
+fn token_offsets(tokens: &[&str]) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(tokens.len() + 1);
+    let mut offset = 0;
+    offsets.push(offset);
+    for token in tokens {
+        offset += token.len();
+        offsets.push(offset);
+    }
+    offsets
+}
+
+impl<'a> Multisplice<'a> {
+    /// Create a "multisplicer" with the splices that turn `original` into `modified`.
+    ///
+    /// The splices are computed using a character diff, and borrow from `modified`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let splicer = Multisplice::from_diff("a b c d e", "a beep c e");
+    /// assert_eq!(splicer.to_string(), "a beep c e");
+    /// assert_eq!(splicer.slice(0, 4), "a beep ");
+    /// ```
+    pub fn from_diff(original: &'a str, modified: &'a str) -> Self {
+        let diff = TextDiff::from_chars(original, modified);
+        let old_offsets = token_offsets(diff.old_slices());
+        let new_offsets = token_offsets(diff.new_slices());
+        let mut splicer = Multisplice::new(original);
+        for op in diff.ops() {
+            let (old, new) = match *op {
+                DiffOp::Equal { .. } => continue,
+                DiffOp::Delete {
+                    old_index,
+                    old_len,
+                    new_index,
+                } => (old_index..old_index + old_len, new_index..new_index),
+                DiffOp::Insert {
+                    old_index,
+                    new_index,
+                    new_len,
+                } => (old_index..old_index, new_index..new_index + new_len),
+                DiffOp::Replace {
+                    old_index,
+                    old_len,
+                    new_index,
+                    new_len,
+                } => (
+                    old_index..old_index + old_len,
+                    new_index..new_index + new_len,
+                ),
+            };
+            splicer.splice(
+                old_offsets[old.start],
+                old_offsets[old.end],
+                &modified[new_offsets[new.start]..new_offsets[new.end]],
+            );
+        }
+        splicer
+    }
+}
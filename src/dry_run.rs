@@ -0,0 +1,121 @@
+//! Checking whether edits would apply, without queueing them.
+
+use crate::{check_range, Multisplice, Splice, SpliceError, SpliceId};
+use std::ops::Range;
+
+/// The outcome of checking a single edit with [`Multisplice::apply_dry_run`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DryRunEdit {
+    /// The range of the original string that the edit replaces.
+    pub range: Range<usize>,
+    /// Whether the edit would apply cleanly, or why not.
+    pub result: Result<(), SpliceError>,
+    /// The lines of the original string that the edit touches. Empty if the range does not fit
+    /// the original string.
+    pub before: String,
+    /// The same lines with only this edit applied. Empty if the range does not fit the original
+    /// string.
+    pub after: String,
+}
+
+impl<'a> Multisplice<'a> {
+    /// Check whether each of `edits` would apply cleanly, on top of the splices that are already
+    /// queued and the edits before it, without queueing anything.
+    ///
+    /// Each result includes a before and after view of the lines that the edit touches. Edits
+    /// whose range does not fit the original string are reported as errors, and do not have a
+    /// before and after view.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SpliceError};
+    ///
+    /// let mut splicer = Multisplice::new("a b\nc d\ne f");
+    /// splicer.splice(4, 5, "beep");
+    /// let results = splicer.apply_dry_run(vec![(6..7, "boop"), (2..5, "x")]);
+    /// assert_eq!(results[0].result, Ok(()));
+    /// assert_eq!(results[0].before, "c d");
+    /// assert_eq!(results[0].after, "c boop");
+    /// assert_eq!(
+    ///     results[1].result,
+    ///     Err(SpliceError::Overlap { existing: 4..5, new: 2..5 }),
+    /// );
+    /// assert_eq!(results[1].before, "a b\nc d");
+    /// assert_eq!(results[1].after, "a x d");
+    /// // Nothing was queued
+    /// assert_eq!(splicer.to_string(), "a b\nbeep d\ne f");
+    /// ```
+    ///
+    /// ```rust
+    /// use multisplice::{Multisplice, SpliceError};
+    ///
+    /// let splicer = Multisplice::new("aéb");
+    /// let results = splicer.apply_dry_run(vec![(2..10, "x"), (2..3, "x"), (2..1, "x")]);
+    /// assert_eq!(
+    ///     results[0].result,
+    ///     Err(SpliceError::OutOfBounds { new: 2..10, len: 4 }),
+    /// );
+    /// assert_eq!(
+    ///     results[1].result,
+    ///     Err(SpliceError::NotCharBoundary { new: 2..3, index: 2 }),
+    /// );
+    /// assert_eq!(results[2].result, Err(SpliceError::Inverted { new: 2..1 }));
+    /// assert!(results.iter().all(|r| r.before.is_empty() && r.after.is_empty()));
+    /// ```
+    pub fn apply_dry_run<'e>(
+        &self,
+        edits: impl IntoIterator<Item = (Range<usize>, &'e str)>,
+    ) -> Vec<DryRunEdit> {
+        let source = self.source;
+        let mut accepted: Vec<Splice<'e>> = vec![];
+        edits
+            .into_iter()
+            .map(|(range, value)| {
+                if let Err(err) = check_range(source, &range) {
+                    return DryRunEdit {
+                        range,
+                        result: Err(err),
+                        before: String::new(),
+                        after: String::new(),
+                    };
+                }
+                let splice = Splice::new(SpliceId(0), range.clone(), value.into());
+                let existing =
+                    self.splices
+                        .iter()
+                        .map(|s| (s.range(), s.overlaps(&range) && !s.is_duplicate_of(&splice)))
+                        .chain(accepted.iter().map(|s| {
+                            (s.range(), s.overlaps(&range) && !s.is_duplicate_of(&splice))
+                        }))
+                        .find(|(_, overlaps)| *overlaps);
+                let result = match existing {
+                    Some((existing, _)) => Err(SpliceError::Overlap {
+                        existing,
+                        new: range.clone(),
+                    }),
+                    None => self.check_protected(&splice),
+                };
+
+                let line_start = source[..range.start].rfind('\n').map_or(0, |i| i + 1);
+                let line_end = source[range.end..]
+                    .find('\n')
+                    .map_or(source.len(), |i| range.end + i);
+                let after = format!(
+                    "{}{}{}",
+                    &source[line_start..range.start],
+                    value,
+                    &source[range.end..line_end]
+                );
+                if result.is_ok() {
+                    accepted.push(splice);
+                }
+                DryRunEdit {
+                    range,
+                    result,
+                    before: source[line_start..line_end].to_string(),
+                    after,
+                }
+            })
+            .collect()
+    }
+}
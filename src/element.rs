@@ -0,0 +1,182 @@
+//! Rewriting HTML and XML elements by the spans of their tags.
+
+use crate::{Multisplice, SpliceId};
+use std::{borrow::Cow, ops::Range};
+
+/// The tags of an HTML or XML element in the original string, as reported by a parser.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ElementSpan {
+    /// The range of the start tag, from `<` up to and including `>`.
+    pub start_tag: Range<usize>,
+    /// The range of the end tag, from `<` up to and including `>`. `None` for void and
+    /// self-closing elements.
+    pub end_tag: Option<Range<usize>>,
+}
+
+/// An attribute in a start tag.
+struct Attribute {
+    /// The range of the name.
+    name: Range<usize>,
+    /// The range of the whole attribute, including its value.
+    range: Range<usize>,
+}
+
+/// Find the attributes in the start tag `tag`, which starts at index `offset`, and the index at
+/// which new attributes can be inserted.
+fn parse_start_tag(tag: &str, offset: usize) -> (Vec<Attribute>, usize) {
+    let bytes = tag.as_bytes();
+    let is_name_end = |b: u8| b.is_ascii_whitespace() || b == b'=' || b == b'>' || b == b'/';
+    let skip_whitespace = |mut i: usize| {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        i
+    };
+
+    let mut i = 1;
+    while i < bytes.len() && !is_name_end(bytes[i]) {
+        i += 1;
+    }
+    let mut attributes = vec![];
+    let mut end = i;
+    loop {
+        i = skip_whitespace(i);
+        if i >= bytes.len() || bytes[i] == b'>' || bytes[i] == b'/' {
+            break;
+        }
+        let start = i;
+        while i < bytes.len() && !is_name_end(bytes[i]) {
+            i += 1;
+        }
+        let name = start..i;
+        let after_name = i;
+        i = skip_whitespace(i);
+        if i < bytes.len() && bytes[i] == b'=' {
+            i = skip_whitespace(i + 1);
+            match bytes.get(i) {
+                Some(&quote) if quote == b'"' || quote == b'\'' => {
+                    i += 1;
+                    while i < bytes.len() && bytes[i] != quote {
+                        i += 1;
+                    }
+                    i = (i + 1).min(bytes.len());
+                }
+                _ => {
+                    while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' {
+                        i += 1;
+                    }
+                }
+            }
+        } else {
+            i = after_name;
+        }
+        if name.is_empty() {
+            // Not an attribute, skip the stray character
+            i += 1;
+            continue;
+        }
+        attributes.push(Attribute {
+            name: offset + name.start..offset + name.end,
+            range: offset + start..offset + i,
+        });
+        end = i;
+    }
+    (attributes, offset + end)
+}
+
+/// Escape an attribute value for use inside double quotes.
+fn escape_attribute(value: &str) -> Cow<'_, str> {
+    if !value.contains(&['&', '"', '<'][..]) {
+        return Cow::Borrowed(value);
+    }
+    Cow::Owned(
+        value
+            .replace('&', "&amp;")
+            .replace('"', "&quot;")
+            .replace('<', "&lt;"),
+    )
+}
+
+impl<'a> Multisplice<'a> {
+    /// Replace the contents of `element`, between its start and end tags, by `value`.
+    ///
+    /// Returns `None` for elements without an end tag.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{ElementSpan, Multisplice};
+    ///
+    /// let mut splicer = Multisplice::new("<ul><li>beep</li></ul>");
+    /// let item = ElementSpan { start_tag: 4..8, end_tag: Some(12..17) };
+    /// splicer.replace_inner(&item, "<b>boop</b>");
+    /// assert_eq!(splicer.to_string(), "<ul><li><b>boop</b></li></ul>");
+    /// ```
+    pub fn replace_inner(
+        &mut self,
+        element: &ElementSpan,
+        value: impl Into<Cow<'a, str>>,
+    ) -> Option<SpliceId> {
+        let end_tag = element.end_tag.as_ref()?;
+        Some(self.splice_cow(element.start_tag.end, end_tag.start, value.into()))
+    }
+
+    /// Replace all of `element`, including its tags, by `value`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{ElementSpan, Multisplice};
+    ///
+    /// let mut splicer = Multisplice::new("<p>beep<br>boop</p>");
+    /// let br = ElementSpan { start_tag: 7..11, end_tag: None };
+    /// splicer.replace_outer(&br, " ");
+    /// assert_eq!(splicer.to_string(), "<p>beep boop</p>");
+    /// ```
+    pub fn replace_outer(
+        &mut self,
+        element: &ElementSpan,
+        value: impl Into<Cow<'a, str>>,
+    ) -> SpliceId {
+        let end = element
+            .end_tag
+            .as_ref()
+            .map_or(element.start_tag.end, |end_tag| end_tag.end);
+        self.splice_cow(element.start_tag.start, end, value.into())
+    }
+
+    /// Set the attribute `name` of `element` to `value`, replacing the attribute if the start tag
+    /// already has it, and adding it at the end of the start tag otherwise.
+    ///
+    /// Attribute names are compared ignoring ASCII case. The value is escaped and written in
+    /// double quotes.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{ElementSpan, Multisplice};
+    ///
+    /// let mut splicer = Multisplice::new("<a HREF=old.html>link</a><img src='a.png' />");
+    /// let a = ElementSpan { start_tag: 0..17, end_tag: Some(21..25) };
+    /// let img = ElementSpan { start_tag: 25..44, end_tag: None };
+    /// splicer.set_attribute(&a, "href", "new.html?a=1&b=2");
+    /// splicer.set_attribute(&img, "alt", "\"A\"");
+    /// assert_eq!(
+    ///     splicer.to_string(),
+    ///     "<a href=\"new.html?a=1&amp;b=2\">link</a><img src='a.png' alt=\"&quot;A&quot;\" />",
+    /// );
+    /// ```
+    pub fn set_attribute(&mut self, element: &ElementSpan, name: &str, value: &str) -> SpliceId {
+        let tag = &self.source[element.start_tag.clone()];
+        let (attributes, insert_at) = parse_start_tag(tag, element.start_tag.start);
+        let existing = attributes
+            .iter()
+            .find(|attribute| self.source[attribute.name.clone()].eq_ignore_ascii_case(name));
+        let value = escape_attribute(value);
+        match existing {
+            Some(attribute) => {
+                let range = attribute.range.clone();
+                let value = format!("{}=\"{}\"", name, value);
+                self.splice_cow(range.start, range.end, Cow::Owned(value))
+            }
+            None => self.insert_left(insert_at, format!(" {}=\"{}\"", name, value)),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Splicing files in place.
+
+use crate::Multisplice;
+use std::{fs, io, path::Path};
+
+/// Splice a file in place.
+///
+/// Reads the file at `path` and calls `f` to queue splices. The output is written to a temporary
+/// file next to the original, which then replaces the original file, so other processes never see
+/// a partially written file. The permissions of the original file are preserved. If no splices
+/// change the contents, the file is not written.
+///
+/// # Example
+/// ```rust
+/// use multisplice::splice_file;
+///
+/// let path = std::env::temp_dir().join("multisplice-splice-file-example.txt");
+/// std::fs::write(&path, "a b c d e").unwrap();
+/// splice_file(&path, |splicer| {
+///     splicer.splice(2, 3, "beep");
+///     splicer.splice(6, 7, "boop");
+/// })
+/// .unwrap();
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "a beep c boop e");
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+///
+/// ```rust
+/// use multisplice::splice_file;
+///
+/// let path = std::env::temp_dir().join("multisplice-splice-file-whole.txt");
+/// std::fs::write(&path, "abc").unwrap();
+/// splice_file(&path, |splicer| {
+///     splicer.splice(0, 3, "xyz");
+/// })
+/// .unwrap();
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "xyz");
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn splice_file<F>(path: impl AsRef<Path>, f: F) -> io::Result<()>
+where
+    F: for<'a> FnOnce(&mut Multisplice<'a>),
+{
+    let path = path.as_ref();
+    let source = fs::read_to_string(path)?;
+    let mut splicer = Multisplice::new(&source);
+    f(&mut splicer);
+    let output = splicer.render();
+    if output == splicer.source() {
+        return Ok(());
+    }
+    write_atomic(path, &output)
+}
+
+/// Replace the contents of the file at `path` by `output`, through a temporary file next to it.
+pub(crate) fn write_atomic(path: &Path, output: &str) -> io::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path is not a file"))?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(".multisplice-tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let write = || {
+        fs::write(&temp_path, output.as_bytes())?;
+        fs::set_permissions(&temp_path, fs::metadata(path)?.permissions())?;
+        fs::rename(&temp_path, path)
+    };
+    write().inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })
+}
@@ -0,0 +1,107 @@
+//! Rendering with or keeping a subset of the queued splices.
+
+use crate::{get_end_bound, get_start_bound, Multisplice, Severity, Splice, SpliceId};
+use std::ops::{Range, RangeBounds};
+
+/// A queued splice, as seen by the predicates that select splices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpliceInfo<'s> {
+    /// The identifier of the splice.
+    pub id: SpliceId,
+    /// The range of the original string that is replaced.
+    pub range: Range<usize>,
+    /// The replacement value, before escaping.
+    pub value: &'s str,
+    /// The note explaining why the splice was made.
+    pub note: Option<&'s str>,
+    /// How confident the author of the splice is that it is correct.
+    pub severity: Severity,
+    /// The group the splice belongs to.
+    pub group: Option<&'s str>,
+}
+
+impl Splice<'_> {
+    /// Describe this splice for predicates.
+    pub(crate) fn info(&self) -> SpliceInfo<'_> {
+        SpliceInfo {
+            id: self.id,
+            range: self.range(),
+            value: &self.value,
+            note: self.meta().note.as_deref(),
+            severity: self.severity,
+            group: self.meta().group.as_deref(),
+        }
+    }
+}
+
+impl Multisplice<'_> {
+    /// Get the output for a range of the original string, with only the splices for which
+    /// `predicate` returns true. The other splices are left queued, so one splicer can render
+    /// several variants of the output.
+    ///
+    /// Splices that are hidden inside a larger splice are included if the larger splice is not.
+    /// Splices in disabled groups are never included.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("let  x = 1");
+    /// splicer.splice_with_note(3..5, " ", "formatting");
+    /// splicer.splice_with_note(9..10, "2", "bump the version");
+    /// assert_eq!(splicer.to_string(), "let x = 2");
+    /// let formatting = splicer.slice_filtered(.., |s| s.note == Some("formatting"));
+    /// assert_eq!(formatting, "let x = 1");
+    /// assert_eq!(splicer.slice_filtered(7.., |s| s.value != " "), "= 2");
+    /// ```
+    pub fn slice_filtered(
+        &self,
+        range: impl RangeBounds<usize>,
+        predicate: impl Fn(&SpliceInfo<'_>) -> bool,
+    ) -> String {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        self.slice_where(start, end, &|s| predicate(&s.info()))
+    }
+
+    /// Remove the queued splices for which `keep` returns false, along with the markers and
+    /// slots that refer to them.
+    ///
+    /// This removes any number of splices in a single pass over the queue. With history enabled,
+    /// the removal is undone as one step.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("let a = 1;\nlet b = 2;\nlet c = 3;\n");
+    /// splicer.splice(4, 5, "x");
+    /// splicer.splice(15, 16, "y");
+    /// splicer.splice(26, 27, "z");
+    /// // Discard the edits on the second line
+    /// splicer.retain(|s| !(11..22).contains(&s.range.start));
+    /// assert_eq!(splicer.to_string(), "let x = 1;\nlet b = 2;\nlet z = 3;\n");
+    /// ```
+    ///
+    /// Undoing the removal brings back the splices and their markers:
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.enable_history();
+    /// splicer.splice(0, 1, "x");
+    /// splicer.mark(4, "c");
+    /// splicer.splice(8, 9, "z");
+    /// splicer.retain(|s| s.value == "x");
+    /// assert_eq!(splicer.to_string(), "x b c d e");
+    /// assert_eq!(splicer.marker_positions(), vec![]);
+    /// assert!(splicer.undo());
+    /// assert_eq!(splicer.to_string(), "x b c d z");
+    /// assert_eq!(splicer.marker_positions(), vec![("c", 4)]);
+    /// assert!(splicer.redo());
+    /// assert_eq!(splicer.to_string(), "x b c d e");
+    /// ```
+    pub fn retain(&mut self, mut keep: impl FnMut(&SpliceInfo<'_>) -> bool) {
+        self.retain_splices(|s| keep(&s.info()));
+    }
+}
@@ -0,0 +1,98 @@
+//! Conversions from `rustfix` suggestions to splices.
+
+use crate::{Multisplice, SpliceError, Workspace};
+use rustfix::{Replacement, Solution, Suggestion};
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// Get the range and replacement value of a rustfix replacement.
+#[inline]
+fn edit(replacement: &Replacement) -> (Range<usize>, Cow<'_, str>) {
+    (
+        replacement.snippet.range.clone(),
+        Cow::Borrowed(replacement.replacement.as_str()),
+    )
+}
+
+impl<'a> Multisplice<'a> {
+    /// Queue all the replacements of a single rustfix solution.
+    ///
+    /// The replacements are queued with [`Multisplice::splice_many`], so either all of them are
+    /// applied, or none are. The file names of the replacements are not checked.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    /// use rustfix::{LinePosition, LineRange, Replacement, Snippet, Solution};
+    ///
+    /// let replace = |range: std::ops::Range<usize>, replacement: &str| Replacement {
+    ///     snippet: Snippet {
+    ///         file_name: "src/main.rs".to_string(),
+    ///         line_range: LineRange {
+    ///             start: LinePosition { line: 1, column: range.start },
+    ///             end: LinePosition { line: 1, column: range.end },
+    ///         },
+    ///         range,
+    ///     },
+    ///     replacement: replacement.to_string(),
+    /// };
+    /// let solution = Solution {
+    ///     message: "use `u32` everywhere".to_string(),
+    ///     replacements: vec![replace(7..10, "u32"), replace(18..21, "u32")],
+    /// };
+    ///
+    /// let mut splicer = Multisplice::new("let a: u16 = 1 as u16;");
+    /// splicer.splice_solution(&solution).unwrap();
+    /// assert_eq!(splicer.to_string(), "let a: u32 = 1 as u32;");
+    /// ```
+    pub fn splice_solution(&mut self, solution: &'a Solution) -> Result<(), Vec<SpliceError>> {
+        self.splice_many(solution.replacements.iter().map(edit))
+    }
+
+    /// Queue the replacements of all the solutions of a rustfix suggestion, like
+    /// `rustfix::CodeFix::apply`.
+    ///
+    /// The replacements are queued with [`Multisplice::splice_many`], so either all of them are
+    /// applied, or none are. The file names of the replacements are not checked.
+    pub fn splice_suggestion(
+        &mut self,
+        suggestion: &'a Suggestion,
+    ) -> Result<(), Vec<SpliceError>> {
+        self.splice_many(
+            suggestion
+                .solutions
+                .iter()
+                .flat_map(|solution| solution.replacements.iter().map(edit)),
+        )
+    }
+}
+
+impl<'a> Workspace<'a> {
+    /// Queue the replacements of all the solutions of a rustfix suggestion, in the files they
+    /// belong to.
+    ///
+    /// The replacements for each file are queued with [`Workspace::splice_many`]. Returns whether
+    /// all the replacements were queued.
+    ///
+    /// # Panics
+    /// Panics if one of the files was not added to the workspace.
+    pub fn splice_suggestion(&mut self, suggestion: &'a Suggestion) -> bool {
+        let mut files: Vec<(&str, Vec<_>)> = vec![];
+        let replacements = suggestion
+            .solutions
+            .iter()
+            .flat_map(|solution| &solution.replacements);
+        for replacement in replacements {
+            let file_name = replacement.snippet.file_name.as_str();
+            match files.iter_mut().find(|(name, _)| *name == file_name) {
+                Some((_, edits)) => edits.push(edit(replacement)),
+                None => files.push((file_name, vec![edit(replacement)])),
+            }
+        }
+        let mut queued = true;
+        for (file_name, edits) in files {
+            queued &= self.splice_many(file_name, edits);
+        }
+        queued
+    }
+}
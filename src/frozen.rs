@@ -0,0 +1,144 @@
+//! An immutable view of the output, optimized for repeated reads.
+
+use crate::{Chunk, Multisplice};
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Range;
+
+/// The output of a [`Multisplice`] that can no longer be changed, with the position of every
+/// piece of the output precomputed.
+///
+/// Slicing the output and mapping offsets take `O(log n)` time in the number of splices, instead
+/// of walking all the splices every time.
+///
+/// Returned by [`Multisplice::freeze`].
+#[derive(Debug, Clone)]
+pub struct Spliced<'a> {
+    /// The pieces of text that make up the output.
+    pieces: Vec<Cow<'a, str>>,
+    /// The index in the output where each piece starts.
+    starts: Vec<usize>,
+    /// The range of the original string that each piece replaces or copies.
+    original: Vec<Range<usize>>,
+    /// Whether each piece is a part of the original string.
+    is_source: Vec<bool>,
+    /// The length of the output.
+    len: usize,
+}
+
+impl<'a> Multisplice<'a> {
+    /// Execute the splices, returning an immutable view of the output that is optimized for
+    /// repeated slicing and offset mapping.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.insert_left(6, "!");
+    /// let spliced = splicer.freeze();
+    /// assert_eq!(spliced.to_string(), "a beep c !d e");
+    /// assert_eq!(spliced.slice_output(4..11), "ep c !d");
+    /// assert_eq!(spliced.map_offset(4), 7);
+    /// assert_eq!(spliced.map_offset(6), 10);
+    /// ```
+    pub fn freeze(mut self) -> Spliced<'a> {
+        let chunks = self.chunks(0, self.source.len()).collect::<Vec<_>>();
+        let capacity = chunks.len() + usize::from(self.bom.is_some());
+        let mut spliced = Spliced {
+            pieces: Vec::with_capacity(capacity),
+            starts: Vec::with_capacity(capacity),
+            original: Vec::with_capacity(capacity),
+            is_source: Vec::with_capacity(capacity),
+            len: 0,
+        };
+        let (source, splices, rendering) = (self.source, &mut self.splices, &self.rendering);
+        // The byte order mark is generated text in front of the original string
+        let bom = self.bom.map(|bom| (Cow::Borrowed(bom), 0..0, false));
+        let pieces = chunks.into_iter().map(|chunk| match chunk {
+            Chunk::Source(range) => (Cow::Borrowed(&source[range.clone()]), range, true),
+            Chunk::Splice(index) => {
+                let splice = &mut splices[index];
+                let piece = if rendering.applies_to(splice) {
+                    Cow::Owned(splice.output(rendering).to_string())
+                } else {
+                    std::mem::take(&mut splice.value)
+                };
+                (piece, splice.range(), false)
+            }
+        });
+        for (piece, original, is_source) in bom.into_iter().chain(pieces) {
+            spliced.starts.push(spliced.len);
+            spliced.len += piece.len();
+            spliced.pieces.push(piece);
+            spliced.original.push(original);
+            spliced.is_source.push(is_source);
+        }
+        spliced
+    }
+}
+
+impl<'a> Spliced<'a> {
+    /// The length of the output in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the output is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the text in the range `range` of the output.
+    ///
+    /// This borrows if the range is entirely inside a single piece of the output.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds or not on character boundaries.
+    pub fn slice_output(&self, range: Range<usize>) -> Cow<'_, str> {
+        assert!(range.start <= range.end && range.end <= self.len);
+        if range.is_empty() {
+            return Cow::Borrowed("");
+        }
+        let first = self.starts.partition_point(|start| *start <= range.start) - 1;
+        let offset = range.start - self.starts[first];
+        if range.end <= self.starts[first] + self.pieces[first].len() {
+            return Cow::Borrowed(&self.pieces[first][offset..range.end - self.starts[first]]);
+        }
+
+        let mut result = String::with_capacity(range.len());
+        result.push_str(&self.pieces[first][offset..]);
+        for (piece, start) in self.pieces.iter().zip(&self.starts).skip(first + 1) {
+            if range.end <= start + piece.len() {
+                result.push_str(&piece[..range.end - start]);
+                break;
+            }
+            result.push_str(piece);
+        }
+        Cow::Owned(result)
+    }
+
+    /// Find where an index into the original string ends up in the output.
+    ///
+    /// This maps positions the same way as [`Multisplice::map_offset`].
+    pub fn map_offset(&self, offset: usize) -> usize {
+        let index = self.original.partition_point(|range| range.end <= offset);
+        match self.original.get(index) {
+            Some(range) if self.is_source[index] => self.starts[index] + offset - range.start,
+            Some(_) => self.starts[index],
+            None => self.len,
+        }
+    }
+}
+
+impl fmt::Display for Spliced<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for piece in &self.pieces {
+            f.write_str(piece)?;
+        }
+        Ok(())
+    }
+}
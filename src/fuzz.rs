@@ -0,0 +1,69 @@
+//! Structurally valid splice plans for fuzzing.
+
+use crate::Multisplice;
+use arbitrary::{Arbitrary, Result, Unstructured};
+use std::ops::Range;
+
+/// A source string with a list of sorted, non-overlapping edits to it.
+///
+/// All ranges are on character boundaries of the source string, so the edits can always be
+/// applied. This is meant for fuzzing code that uses the splicer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplicePlan {
+    /// The original string.
+    pub source: String,
+    /// The ranges to replace, sorted by their start index, and their replacement values.
+    pub edits: Vec<(Range<usize>, String)>,
+}
+
+impl SplicePlan {
+    /// Generate a plan of edits for a given source string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use arbitrary::Unstructured;
+    /// use multisplice::SplicePlan;
+    ///
+    /// let mut u = Unstructured::new(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+    /// let plan = SplicePlan::arbitrary_for_source(&mut u, "a b c d e").unwrap();
+    /// let splicer = plan.splicer();
+    /// assert_eq!(splicer.source(), "a b c d e");
+    /// ```
+    pub fn arbitrary_for_source(u: &mut Unstructured<'_>, source: &str) -> Result<Self> {
+        let boundaries = source
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(source.len()))
+            .collect::<Vec<_>>();
+        let mut edits = vec![];
+        let mut position = 0;
+        while !u.is_empty() && u.arbitrary::<bool>()? {
+            let last = boundaries.len() - 1;
+            let start = u.int_in_range(position..=last)?;
+            let end = u.int_in_range(start..=last)?;
+            let value = String::arbitrary(u)?;
+            edits.push((boundaries[start]..boundaries[end], value));
+            position = end;
+        }
+        Ok(SplicePlan {
+            source: source.to_string(),
+            edits,
+        })
+    }
+
+    /// Create a "multisplicer" for the source string, with all the edits queued.
+    pub fn splicer(&self) -> Multisplice<'_> {
+        let mut splicer = Multisplice::new(&self.source);
+        for (range, value) in &self.edits {
+            splicer.splice_range(range.clone(), value.as_str());
+        }
+        splicer
+    }
+}
+
+impl<'u> Arbitrary<'u> for SplicePlan {
+    fn arbitrary(u: &mut Unstructured<'u>) -> Result<Self> {
+        let source = String::arbitrary(u)?;
+        Self::arbitrary_for_source(u, &source)
+    }
+}
@@ -0,0 +1,64 @@
+//! Named groups of splices that can be switched on and off.
+
+use crate::{Multisplice, Splice};
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+impl<'a> Multisplice<'a> {
+    /// Add the splices that are queued inside `f` to the group `name`.
+    ///
+    /// Groups can be disabled with [`Multisplice::set_group_enabled`] to leave their splices out
+    /// of the output. Disabled splices are still queued, so they still conflict with overlapping
+    /// splices. Groups do not nest: splices queued in an inner group only belong to that group.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("fn main() { run(); }");
+    /// splicer.group("logging", |splicer| {
+    ///     splicer.insert_right(12, "log(\"start\"); ");
+    ///     splicer.insert_left(18, " log(\"end\");");
+    /// });
+    /// splicer.splice(12, 15, "start");
+    /// assert_eq!(splicer.to_string(), r#"fn main() { log("start"); start(); log("end"); }"#);
+    ///
+    /// splicer.set_group_enabled("logging", false);
+    /// assert_eq!(splicer.to_string(), "fn main() { start(); }");
+    /// assert!(!splicer.is_group_enabled("logging"));
+    /// ```
+    pub fn group<R>(&mut self, name: impl Into<Cow<'a, str>>, f: impl FnOnce(&mut Self) -> R) -> R {
+        let outer = self.group.replace(name.into());
+        let result = f(self);
+        self.group = outer;
+        result
+    }
+
+    /// Enable or disable rendering the splices in a group. All groups are enabled by default.
+    pub fn set_group_enabled(&mut self, name: &str, enabled: bool) {
+        let position = self.disabled_groups.iter().position(|group| group == name);
+        match (position, enabled) {
+            (Some(index), true) => {
+                self.disabled_groups.remove(index);
+            }
+            (None, false) => self.disabled_groups.push(Cow::Owned(name.to_string())),
+            _ => return,
+        }
+        self.output = OnceLock::new();
+    }
+
+    /// Check if the splices in a group are rendered.
+    #[inline]
+    pub fn is_group_enabled(&self, name: &str) -> bool {
+        !self.disabled_groups.iter().any(|group| group == name)
+    }
+
+    /// Check if a splice is left out of the output because its group is disabled.
+    #[inline]
+    pub(crate) fn is_disabled(&self, splice: &Splice<'_>) -> bool {
+        match &splice.meta().group {
+            Some(group) => !self.disabled_groups.is_empty() && !self.is_group_enabled(group),
+            None => false,
+        }
+    }
+}
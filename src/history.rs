@@ -0,0 +1,288 @@
+//! Undoing and redoing changes to the queued splices.
+
+use crate::{Multisplice, Splice, SpliceId};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// A single change to the queued splices.
+#[derive(Debug)]
+pub(crate) enum Edit<'a> {
+    /// A splice was queued.
+    Added(SpliceId),
+    /// A splice was removed, along with the markers and slots that referred to it.
+    Removed {
+        splice: Box<Splice<'a>>,
+        markers: Vec<Cow<'a, str>>,
+        slots: Vec<(Cow<'a, str>, bool)>,
+    },
+    /// Several splices were queued at once, and are removed again in a single pass.
+    AddedMany(HashSet<SpliceId>),
+    /// Several splices were removed at once, along with the markers and slots that referred to
+    /// them. The splices are in queue order.
+    RemovedMany {
+        splices: Vec<Splice<'a>>,
+        markers: Vec<(Cow<'a, str>, SpliceId)>,
+        slots: Vec<(Cow<'a, str>, SpliceId, bool)>,
+    },
+    /// The value of a splice was changed. Holds the previous value.
+    Changed(SpliceId, Cow<'a, str>),
+    /// A slot was filled. Holds whether it was filled before.
+    Filled(SpliceId, bool),
+}
+
+/// The changes that can be undone and redone.
+#[derive(Debug, Default)]
+pub(crate) struct History<'a> {
+    /// Steps that can be undone, most recent last. Each step is a list of changes, in the order
+    /// they were made.
+    undo: Vec<Vec<Edit<'a>>>,
+    /// Steps that were undone and can be redone, most recently undone last.
+    redo: Vec<Vec<Edit<'a>>>,
+    /// How many operations that should be undone as one step are in progress.
+    depth: usize,
+}
+
+impl<'a> Multisplice<'a> {
+    /// Start recording changes, so they can be undone with [`Multisplice::undo`].
+    ///
+    /// Every method that queues, removes or changes splices is one step, including bulk
+    /// operations like [`Multisplice::splice_many`]. Changes made before calling this method can
+    /// not be undone. Combining splicers, for example with [`Multisplice::compose`] or
+    /// [`Multisplice::rebase`], starts a fresh splicer without history.
+    #[inline]
+    pub fn enable_history(&mut self) {
+        if self.history.is_none() {
+            self.history = Some(History::default());
+        }
+    }
+
+    /// Undo the last step. Returns false if there is nothing to undo.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.enable_history();
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice_many(vec![(6..7, "boop"), (8..9, "!")]).unwrap();
+    /// assert_eq!(splicer.to_string(), "a beep c boop !");
+    /// assert!(splicer.undo());
+    /// assert_eq!(splicer.to_string(), "a beep c d e");
+    /// assert!(splicer.undo());
+    /// assert_eq!(splicer.to_string(), "a b c d e");
+    /// assert!(!splicer.undo());
+    /// assert!(splicer.redo());
+    /// assert_eq!(splicer.to_string(), "a beep c d e");
+    /// ```
+    pub fn undo(&mut self) -> bool {
+        let step = match self.history.as_mut().and_then(|history| history.undo.pop()) {
+            Some(step) => step,
+            None => return false,
+        };
+        let inverse = self.revert(step);
+        if let Some(history) = &mut self.history {
+            history.redo.push(inverse);
+        }
+        true
+    }
+
+    /// Redo the last step that was undone. Returns false if there is nothing to redo.
+    ///
+    /// Making a new change after undoing discards the steps that can be redone.
+    pub fn redo(&mut self) -> bool {
+        let step = match self.history.as_mut().and_then(|history| history.redo.pop()) {
+            Some(step) => step,
+            None => return false,
+        };
+        let inverse = self.revert(step);
+        if let Some(history) = &mut self.history {
+            history.undo.push(inverse);
+        }
+        true
+    }
+
+    /// Check if there is a step that can be undone.
+    #[inline]
+    pub fn can_undo(&self) -> bool {
+        self.history
+            .as_ref()
+            .is_some_and(|history| !history.undo.is_empty())
+    }
+
+    /// Check if there is a step that can be redone.
+    #[inline]
+    pub fn can_redo(&self) -> bool {
+        self.history
+            .as_ref()
+            .is_some_and(|history| !history.redo.is_empty())
+    }
+
+    /// Revert the changes of a step, returning the changes that revert them again.
+    fn revert(&mut self, step: Vec<Edit<'a>>) -> Vec<Edit<'a>> {
+        self.output = OnceLock::new();
+        let mut inverse = Vec::with_capacity(step.len());
+        for edit in step.into_iter().rev() {
+            match edit {
+                Edit::Added(id) => {
+                    if let Some(index) = self.splices.iter().position(|s| s.id == id) {
+                        inverse.push(self.take_splice(index));
+                    }
+                }
+                Edit::Removed {
+                    splice,
+                    markers,
+                    slots,
+                } => {
+                    let id = splice.id;
+                    let key = (splice.order_key(), id);
+                    let index = self
+                        .splices
+                        .iter()
+                        .position(|s| (s.order_key(), s.id) > key)
+                        .unwrap_or(self.splices.len());
+                    let mut splice = *splice;
+                    self.rendering.prepare(&mut splice);
+                    self.splices.insert(index, splice);
+                    self.markers
+                        .extend(markers.into_iter().map(|name| (name, id)));
+                    self.slots
+                        .extend(slots.into_iter().map(|(key, filled)| (key, id, filled)));
+                    inverse.push(Edit::Added(id));
+                }
+                Edit::AddedMany(ids) => inverse.push(self.take_splices(&ids)),
+                Edit::RemovedMany {
+                    splices,
+                    markers,
+                    slots,
+                } => {
+                    let ids = splices.iter().map(|s| s.id).collect();
+                    let mut removed = splices.into_iter().peekable();
+                    let mut merged = Vec::with_capacity(self.splices.len() + removed.len());
+                    for splice in std::mem::take(&mut self.splices) {
+                        let key = (splice.order_key(), splice.id);
+                        while let Some(next) = removed.next_if(|s| (s.order_key(), s.id) < key) {
+                            merged.push(next);
+                        }
+                        merged.push(splice);
+                    }
+                    merged.extend(removed);
+                    self.splices = merged.into_iter().collect();
+                    self.prepare_rendering();
+                    self.markers.extend(markers);
+                    self.slots.extend(slots);
+                    inverse.push(Edit::AddedMany(ids));
+                }
+                Edit::Filled(id, filled) => {
+                    if let Some(slot) = self.slots.iter_mut().find(|(_, slot, _)| *slot == id) {
+                        let previous = std::mem::replace(&mut slot.2, filled);
+                        inverse.push(Edit::Filled(id, previous));
+                    }
+                }
+                Edit::Changed(id, value) => {
+                    if let Some(splice) = self.splices.iter_mut().find(|s| s.id == id) {
+                        let previous = std::mem::replace(splice.value_mut(), value);
+                        inverse.push(Edit::Changed(id, previous));
+                    }
+                }
+            }
+        }
+        inverse.reverse();
+        inverse
+    }
+
+    /// Remove the splice at `index`, along with the markers and slots that refer to it.
+    pub(crate) fn take_splice(&mut self, index: usize) -> Edit<'a> {
+        let splice = Box::new(self.splices.remove(index));
+        let id = splice.id;
+        let mut markers = vec![];
+        self.markers.retain(|(name, marker)| {
+            if *marker == id {
+                markers.push(name.clone());
+            }
+            *marker != id
+        });
+        let mut slots = vec![];
+        self.slots.retain(|(key, slot, filled)| {
+            if *slot == id {
+                slots.push((key.clone(), *filled));
+            }
+            *slot != id
+        });
+        Edit::Removed {
+            splice,
+            markers,
+            slots,
+        }
+    }
+
+    /// Remove the splices with the given IDs in a single pass, along with the markers and slots
+    /// that refer to them.
+    pub(crate) fn take_splices(&mut self, ids: &HashSet<SpliceId>) -> Edit<'a> {
+        let mut splices = vec![];
+        for splice in std::mem::take(&mut self.splices) {
+            if ids.contains(&splice.id) {
+                splices.push(splice);
+            } else {
+                self.splices.push(splice);
+            }
+        }
+        let (markers, kept) = std::mem::take(&mut self.markers)
+            .into_iter()
+            .partition(|(_, id)| ids.contains(id));
+        self.markers = kept;
+        let (slots, kept) = std::mem::take(&mut self.slots)
+            .into_iter()
+            .partition(|(_, id, _)| ids.contains(id));
+        self.slots = kept;
+        Edit::RemovedMany {
+            splices,
+            markers,
+            slots,
+        }
+    }
+
+    /// Record a change, if history is enabled.
+    pub(crate) fn record(&mut self, edit: impl FnOnce(&Self) -> Edit<'a>) {
+        if self.history.is_none() {
+            return;
+        }
+        let edit = edit(self);
+        let history = self.history.as_mut().unwrap();
+        if history.depth == 0 {
+            history.undo.push(vec![]);
+        }
+        history.redo.clear();
+        history.undo.last_mut().unwrap().push(edit);
+    }
+
+    /// Record the value of the splice at `index` before changing it, if history is enabled.
+    #[inline]
+    pub(crate) fn record_value(&mut self, index: usize) {
+        self.record(|this| {
+            let splice = &this.splices[index];
+            Edit::Changed(splice.id, splice.value.clone())
+        });
+    }
+
+    /// Start an operation whose changes are undone as one step.
+    pub(crate) fn begin_step(&mut self) {
+        if let Some(history) = &mut self.history {
+            if history.depth == 0 {
+                history.undo.push(vec![]);
+            }
+            history.depth += 1;
+        }
+    }
+
+    /// Finish an operation started with `begin_step`.
+    pub(crate) fn end_step(&mut self) {
+        if let Some(history) = &mut self.history {
+            history.depth -= 1;
+            if history.depth == 0 && history.undo.last().is_some_and(Vec::is_empty) {
+                history.undo.pop();
+            }
+        }
+    }
+}
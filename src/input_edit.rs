@@ -0,0 +1,89 @@
+//! Edit descriptions for incremental parsers.
+
+use crate::{lines::LineIndex, Multisplice};
+
+/// A position in a string, as a zero-based row and byte column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    /// The zero-based line number.
+    pub row: usize,
+    /// The zero-based column, in bytes.
+    pub column: usize,
+}
+
+/// A description of a single edit, shaped like tree-sitter's `InputEdit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InputEdit {
+    /// The index where the edit starts.
+    pub start_byte: usize,
+    /// The index where the replaced text ended.
+    pub old_end_byte: usize,
+    /// The index where the replacement text ends.
+    pub new_end_byte: usize,
+    /// The position where the edit starts.
+    pub start_position: Point,
+    /// The position where the replaced text ended.
+    pub old_end_position: Point,
+    /// The position where the replacement text ends.
+    pub new_end_position: Point,
+}
+
+impl Multisplice<'_> {
+    /// Describe the queued splices as edits for an incremental parser such as tree-sitter.
+    ///
+    /// The edits are returned from the end of the string to the start, so each edit can be applied
+    /// to a syntax tree in order (for example using tree-sitter's `Tree::edit`) without adjusting
+    /// the positions of the edits after it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{InputEdit, Multisplice, Point};
+    ///
+    /// let mut splicer = Multisplice::new("a b\nc d");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(6, 7, "boop\n");
+    /// let edits = splicer.input_edits();
+    /// assert_eq!(edits[0], InputEdit {
+    ///     start_byte: 6,
+    ///     old_end_byte: 7,
+    ///     new_end_byte: 11,
+    ///     start_position: Point { row: 1, column: 2 },
+    ///     old_end_position: Point { row: 1, column: 3 },
+    ///     new_end_position: Point { row: 2, column: 0 },
+    /// });
+    /// assert_eq!(edits[1].new_end_position, Point { row: 0, column: 6 });
+    /// ```
+    pub fn input_edits(&self) -> Vec<InputEdit> {
+        let lines = LineIndex::new(self.source);
+        let point = |index| {
+            let (row, column) = lines.byte_position(index);
+            Point { row, column }
+        };
+        self.splices
+            .iter()
+            .rev()
+            .map(|s| {
+                let value = s.output(&self.rendering);
+                let start_position = point(s.start());
+                let new_end_position = match value.rfind('\n') {
+                    Some(last_newline) => Point {
+                        row: start_position.row + value.matches('\n').count(),
+                        column: value.len() - last_newline - 1,
+                    },
+                    None => Point {
+                        row: start_position.row,
+                        column: start_position.column + value.len(),
+                    },
+                };
+                InputEdit {
+                    start_byte: s.start(),
+                    old_end_byte: s.end(),
+                    new_end_byte: s.start() + value.len(),
+                    start_position,
+                    old_end_position: point(s.end()),
+                    new_end_position,
+                }
+            })
+            .collect()
+    }
+}
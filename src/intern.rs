@@ -0,0 +1,95 @@
+//! Sharing storage between repeated replacement values.
+
+use std::{
+    cell::{OnceCell, RefCell},
+    collections::HashMap,
+    sync::Arc,
+};
+
+/// The number of slot segments. Segment `k` has room for `2^k` values, so together they have
+/// room for as many values as fit in memory.
+const SEGMENTS: usize = usize::BITS as usize;
+
+/// A segment of slots for stored values, allocated when it is first needed.
+type Segment = OnceCell<Box<[OnceCell<Arc<str>>]>>;
+
+/// Stores a single copy of each distinct replacement value.
+///
+/// Splicers borrow replacement values from the interner, so inserting the same generated string
+/// many times only allocates it once. The interner must outlive the splicers that use it.
+///
+/// # Example
+/// ```rust
+/// use multisplice::{Interner, Multisplice};
+///
+/// let interner = Interner::new();
+/// let mut splicer = Multisplice::new("a b c d e");
+/// for index in [2, 6] {
+///     splicer.splice(index, index + 1, interner.intern(&format!("{}{}", "be", "ep")));
+/// }
+/// assert_eq!(splicer.to_string(), "a beep c beep e");
+/// assert_eq!(interner.len(), 1);
+/// ```
+#[derive(Debug)]
+pub struct Interner {
+    /// The index of each stored value in the slots.
+    indices: RefCell<HashMap<Arc<str>, usize>>,
+    /// The stored values. Slots are filled once and never change, so references to them stay
+    /// valid while more values are added.
+    segments: [Segment; SEGMENTS],
+}
+
+impl Default for Interner {
+    fn default() -> Self {
+        Interner {
+            indices: RefCell::default(),
+            segments: std::array::from_fn(|_| OnceCell::new()),
+        }
+    }
+}
+
+impl Interner {
+    /// Create an empty interner.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the stored copy of `value`, storing it first if this is the first time it is seen.
+    pub fn intern(&self, value: &str) -> &str {
+        let mut indices = self.indices.borrow_mut();
+        let index = match indices.get(value) {
+            Some(&index) => index,
+            None => {
+                let index = indices.len();
+                let stored = Arc::<str>::from(value);
+                indices.insert(Arc::clone(&stored), index);
+                let _ = self.slot(index).set(stored);
+                index
+            }
+        };
+        self.slot(index)
+            .get()
+            .expect("values are stored before they are returned")
+    }
+
+    /// Get the slot for the value with the given index, allocating its segment if needed.
+    fn slot(&self, index: usize) -> &OnceCell<Arc<str>> {
+        let segment = (usize::BITS - 1 - (index + 1).leading_zeros()) as usize;
+        let slots = self.segments[segment]
+            .get_or_init(|| (0..1usize << segment).map(|_| OnceCell::new()).collect());
+        &slots[index + 1 - (1 << segment)]
+    }
+
+    /// Get the number of distinct values stored in the interner.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.indices.borrow().len()
+    }
+
+    /// Check if the interner is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.indices.borrow().is_empty()
+    }
+}
@@ -0,0 +1,52 @@
+//! Building a splicer that undoes the splices.
+
+use crate::{Chunk, Multisplice};
+
+impl<'a> Multisplice<'a> {
+    /// Build a splicer over the rendered `output` of this splicer whose splices turn it back into
+    /// the original string.
+    ///
+    /// Every visible splice becomes a splice that replaces its output with the range of the
+    /// original string that it replaced. Splices that are hidden by a larger splice, or that
+    /// belong to a disabled group, are not part of the output and have no counterpart.
+    ///
+    /// # Panics
+    /// Panics if `output` is not as long as the output of this splicer.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.insert_left(6, "!");
+    /// splicer.splice(6, 8, "");
+    /// let output = splicer.to_string();
+    /// assert_eq!(output, "a beep c !e");
+    ///
+    /// let inverse = splicer.invert(&output);
+    /// assert_eq!(inverse.to_string(), "a b c d e");
+    /// ```
+    pub fn invert<'o>(&self, output: &'o str) -> Multisplice<'o>
+    where
+        'a: 'o,
+    {
+        let source = self.source;
+        let mut inverse = Multisplice::new(output);
+        let mut position = 0;
+        for chunk in self.chunks(0, source.len()) {
+            let len = self.chunk_str(&chunk).len();
+            if let Chunk::Splice(index) = chunk {
+                let original = &source[self.splices[index].range()];
+                inverse.splice(position, position + len, original);
+            }
+            position += len;
+        }
+        assert_eq!(
+            position,
+            output.len(),
+            "output does not belong to this splicer"
+        );
+        inverse
+    }
+}
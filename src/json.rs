@@ -0,0 +1,189 @@
+//! Exporting and importing splices as JSON.
+//!
+//! The splices are stored as an array of objects with the `start` and `end` indices of the range
+//! to replace, and the `replace`ment value:
+//!
+//! ```json
+//! [{"start":2,"end":3,"replace":"beep"},{"start":6,"end":7,"replace":"boop"}]
+//! ```
+//!
+//! Splices with a note also store it in the `note` field. Insertions that belong to the content
+//! before them, like the ones queued by `Multisplice::insert_left`, have `"side":"left"`, and
+//! generated content, like banners and footers, has `"generated":true`.
+
+use crate::{Multisplice, Side, Splice, SpliceError};
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, error::Error, fmt};
+
+/// A single splice in the JSON format.
+#[derive(Serialize, Deserialize)]
+struct JsonSplice<'a> {
+    start: usize,
+    end: usize,
+    #[serde(borrow)]
+    replace: Cow<'a, str>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    side: Option<JsonSide>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    generated: bool,
+    #[serde(borrow, default, skip_serializing_if = "Option::is_none")]
+    note: Option<Cow<'a, str>>,
+}
+
+/// Which side an insertion belongs to, in the JSON format.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum JsonSide {
+    Left,
+    Right,
+}
+
+/// An error that occurred while importing splices from JSON.
+#[derive(Debug)]
+pub enum FromJsonError {
+    /// The JSON could not be parsed, or did not match the expected format.
+    Json(serde_json::Error),
+    /// Some of the splices overlap, or have ranges that do not fit the source string.
+    Splice(Vec<SpliceError>),
+}
+
+impl fmt::Display for FromJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FromJsonError::Json(err) => write!(f, "Invalid splice JSON: {}", err),
+            FromJsonError::Splice(errors) => write!(f, "{}", errors[0]),
+        }
+    }
+}
+
+impl Error for FromJsonError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FromJsonError::Json(err) => Some(err),
+            FromJsonError::Splice(errors) => Some(&errors[0]),
+        }
+    }
+}
+
+impl<'a> Multisplice<'a> {
+    /// Export the queued splices as JSON.
+    ///
+    /// Lazy values are computed, and exported as they would be rendered without an escaper or
+    /// render hooks.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(6, 7, "boop");
+    /// assert_eq!(
+    ///     splicer.to_json(),
+    ///     r#"[{"start":2,"end":3,"replace":"beep"},{"start":6,"end":7,"replace":"boop"}]"#
+    /// );
+    /// ```
+    pub fn to_json(&self) -> String {
+        let splices = self
+            .splices
+            .iter()
+            .map(|s| JsonSplice {
+                start: s.start(),
+                end: s.end(),
+                replace: match &s.meta().lazy {
+                    Some(lazy) => Cow::Owned(lazy.0.compute()),
+                    None => Cow::Borrowed(&s.value),
+                },
+                side: match s.side {
+                    Side::Left if s.is_insertion() => Some(JsonSide::Left),
+                    _ => None,
+                },
+                generated: s.generated,
+                note: s.meta().note.as_deref().map(Cow::Borrowed),
+            })
+            .collect::<Vec<_>>();
+        serde_json::to_string(&splices).expect("splices can always be serialized")
+    }
+
+    /// Create a "multisplicer" for the given string, with the splices from `json` already
+    /// queued. Replacement values borrow from `json` where possible.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let json = r#"[{"start":2,"end":3,"replace":"beep"},{"start":6,"end":7,"replace":"boop"}]"#;
+    /// let splicer = Multisplice::from_json("a b c d e", json).unwrap();
+    /// assert_eq!(splicer.to_string(), "a beep c boop e");
+    ///
+    /// let json = r#"[{"start":2,"end":3,"replace":"beep","note":"b is deprecated"}]"#;
+    /// let splicer = Multisplice::from_json("a b c", json).unwrap();
+    /// assert_eq!(splicer.notes().collect::<Vec<_>>(), vec![(2..3, "b is deprecated")]);
+    /// assert_eq!(splicer.to_json(), json);
+    /// ```
+    ///
+    /// Exported splices keep the side of insertions, generated content and lazy values:
+    ///
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c");
+    /// splicer.add_banner("// ");
+    /// splicer.insert_left(1, "!");
+    /// splicer.insert_right(1, "?");
+    /// splicer.splice_lazy(2, 3, || "beep".to_string());
+    /// splicer.splice_with_note(4..5, "boop", "c is deprecated");
+    /// splicer.splice_with_note(5..5, ";", "missing semicolon");
+    ///
+    /// let json = splicer.to_json();
+    /// let imported = Multisplice::from_json("a b c", &json).unwrap();
+    /// assert_eq!(imported.to_string(), splicer.to_string());
+    /// assert_eq!(imported.to_json(), json);
+    /// assert_eq!(
+    ///     imported.notes().collect::<Vec<_>>(),
+    ///     vec![(4..5, "c is deprecated"), (5..5, "missing semicolon")],
+    /// );
+    ///
+    /// // Notes belong to the splice they are stored with
+    /// let json = r#"[{"start":1,"end":1,"replace":"!","note":"one"},{"start":1,"end":1,"replace":"?","note":"two"}]"#;
+    /// let imported = Multisplice::from_json("a b c", json).unwrap();
+    /// assert_eq!(imported.notes().collect::<Vec<_>>(), vec![(1..1, "one"), (1..1, "two")]);
+    /// ```
+    ///
+    /// ```rust
+    /// use multisplice::{FromJsonError, Multisplice, SpliceError};
+    ///
+    /// let json = r#"[{"start":2,"end":3,"replace":"x"}]"#;
+    /// let error = Multisplice::from_json("aéb", json).unwrap_err();
+    /// assert!(matches!(
+    ///     error,
+    ///     FromJsonError::Splice(errors) if errors == [SpliceError::NotCharBoundary { new: 2..3, index: 2 }]
+    /// ));
+    /// ```
+    pub fn from_json(source: &'a str, json: &'a str) -> Result<Self, FromJsonError> {
+        let splices: Vec<JsonSplice<'a>> =
+            serde_json::from_str(json).map_err(FromJsonError::Json)?;
+        let mut splicer = Self::new(source);
+        let splices = splices
+            .into_iter()
+            .map(|s| {
+                let mut splice = Splice {
+                    side: match s.side {
+                        Some(JsonSide::Left) => Side::Left,
+                        Some(JsonSide::Right) | None => Side::Right,
+                    },
+                    generated: s.generated,
+                    ..Splice::new(splicer.next_id(), s.start..s.end, s.replace)
+                };
+                if let Some(note) = s.note {
+                    splice.meta_mut().note = Some(note);
+                }
+                splice
+            })
+            .collect();
+        splicer
+            .insert_many(splices)
+            .map_err(FromJsonError::Splice)?;
+        Ok(splicer)
+    }
+}
@@ -0,0 +1,174 @@
+//! Replacing values in JSON documents without reformatting them.
+
+use crate::{Multisplice, SpliceId};
+use serde_json::Value;
+use std::{borrow::Cow, ops::Range};
+
+/// Locates values in a JSON document by their byte offsets.
+///
+/// The document is not validated: it is only scanned far enough to find the value, so it must
+/// be valid JSON for the result to be meaningful.
+struct Scanner<'s> {
+    bytes: &'s [u8],
+}
+
+impl Scanner<'_> {
+    fn skip_whitespace(&self, mut pos: usize) -> usize {
+        while matches!(self.bytes.get(pos), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            pos += 1;
+        }
+        pos
+    }
+
+    /// Find the end of the string starting at `pos`.
+    fn string_end(&self, mut pos: usize) -> Option<usize> {
+        pos += 1;
+        loop {
+            match self.bytes.get(pos)? {
+                b'"' => return Some(pos + 1),
+                b'\\' => pos += 2,
+                _ => pos += 1,
+            }
+        }
+    }
+
+    /// Find the end of the value starting at `pos`.
+    fn value_end(&self, pos: usize) -> Option<usize> {
+        match self.bytes.get(pos)? {
+            b'"' => self.string_end(pos),
+            b'{' | b'[' => {
+                let mut depth = 0;
+                let mut pos = pos;
+                loop {
+                    match self.bytes.get(pos)? {
+                        b'"' => {
+                            pos = self.string_end(pos)?;
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                return Some(pos + 1);
+                            }
+                        }
+                        _ => (),
+                    }
+                    pos += 1;
+                }
+            }
+            _ => {
+                let mut end = pos;
+                while self
+                    .bytes
+                    .get(end)
+                    .is_some_and(|b| !b",]} \t\r\n".contains(b))
+                {
+                    end += 1;
+                }
+                Some(end)
+            }
+        }
+    }
+
+    /// Find the start of the member `token` of the object or array starting at `pos`.
+    fn child(&self, source: &str, pos: usize, token: &str) -> Option<usize> {
+        let (is_object, close) = match self.bytes.get(pos)? {
+            b'{' => (true, b'}'),
+            b'[' => (false, b']'),
+            _ => return None,
+        };
+        let index = if is_object {
+            None
+        } else if token == "0" || !token.starts_with('0') {
+            Some(token.parse::<usize>().ok()?)
+        } else {
+            return None;
+        };
+
+        let mut pos = self.skip_whitespace(pos + 1);
+        let mut i = 0;
+        while *self.bytes.get(pos)? != close {
+            let matches = if is_object {
+                let key_end = self.string_end(pos)?;
+                let key = serde_json::from_str::<Cow<'_, str>>(&source[pos..key_end]).ok()?;
+                pos = self.skip_whitespace(key_end);
+                if *self.bytes.get(pos)? != b':' {
+                    return None;
+                }
+                pos = self.skip_whitespace(pos + 1);
+                key == token
+            } else {
+                index == Some(i)
+            };
+            if matches {
+                return Some(pos);
+            }
+            pos = self.skip_whitespace(self.value_end(pos)?);
+            if *self.bytes.get(pos)? == b',' {
+                pos = self.skip_whitespace(pos + 1);
+            }
+            i += 1;
+        }
+        None
+    }
+}
+
+impl<'a> Multisplice<'a> {
+    /// Find the range of the original string holding the value that the JSON Pointer `pointer`
+    /// refers to, like `/dependencies/0/name`.
+    ///
+    /// The original string must be a JSON document. Returns `None` if the pointer does not refer
+    /// to a value in it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let splicer = Multisplice::new(r#"{ "name": "beep", "tags": [1, {"a/b": null}] }"#);
+    /// assert_eq!(splicer.json_pointer_range("/name"), Some(10..16));
+    /// assert_eq!(splicer.json_pointer_range("/tags/1/a~1b"), Some(38..42));
+    /// assert_eq!(splicer.json_pointer_range(""), Some(0..46));
+    /// assert_eq!(splicer.json_pointer_range("/tags/2"), None);
+    /// ```
+    pub fn json_pointer_range(&self, pointer: &str) -> Option<Range<usize>> {
+        let scanner = Scanner {
+            bytes: self.source.as_bytes(),
+        };
+        let mut pos = scanner.skip_whitespace(0);
+        if !pointer.is_empty() {
+            if !pointer.starts_with('/') {
+                return None;
+            }
+            for token in pointer[1..].split('/') {
+                let token = token.replace("~1", "/").replace("~0", "~");
+                pos = scanner.child(self.source, pos, &token)?;
+            }
+        }
+        Some(pos..scanner.value_end(pos)?)
+    }
+
+    /// Replace the value that the JSON Pointer `pointer` refers to by `value`, keeping the
+    /// formatting of the rest of the document.
+    ///
+    /// The new value is written as compact JSON. Returns `None` if the pointer does not refer to a
+    /// value in the original string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    /// use serde_json::json;
+    ///
+    /// let mut splicer = Multisplice::new("{\n  \"name\": \"beep\",\n  \"port\": 80\n}\n");
+    /// splicer.splice_json_pointer("/name", &json!("say \"boop\"")).unwrap();
+    /// splicer.splice_json_pointer("/port", &json!([80, 443])).unwrap();
+    /// assert_eq!(
+    ///     splicer.to_string(),
+    ///     "{\n  \"name\": \"say \\\"boop\\\"\",\n  \"port\": [80,443]\n}\n",
+    /// );
+    /// ```
+    pub fn splice_json_pointer(&mut self, pointer: &str, value: &Value) -> Option<SpliceId> {
+        let range = self.json_pointer_range(pointer)?;
+        Some(self.splice_cow(range.start, range.end, Cow::Owned(value.to_string())))
+    }
+}
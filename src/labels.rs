@@ -0,0 +1,96 @@
+//! Describing the queued splices as `codespan-reporting` and `ariadne` labels.
+
+use crate::{Multisplice, Splice};
+
+/// Describe what a splice does, for labels pointing at the range it replaces.
+fn describe(splice: &Splice<'_>, original: &str) -> String {
+    match (original.is_empty(), splice.value.is_empty()) {
+        (true, _) => format!("insert {:?}", splice.value),
+        (false, true) => "remove this".to_string(),
+        (false, false) => format!("replace with {:?}", splice.value),
+    }
+}
+
+impl Multisplice<'_> {
+    /// Describe each queued splice as a `codespan-reporting` label in the file `file_id`,
+    /// pointing at the replaced range in the original string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use codespan_reporting::diagnostic::Diagnostic;
+    /// use codespan_reporting::files::SimpleFile;
+    /// use codespan_reporting::term::{self, termcolor::NoColor, Config};
+    /// use multisplice::Multisplice;
+    ///
+    /// let source = "let a = 1;\nlet b = 2;\n";
+    /// let mut splicer = Multisplice::new(source);
+    /// splicer.splice(15, 16, "beep");
+    ///
+    /// let file = SimpleFile::new("src/main.rs", source);
+    /// let diagnostic = Diagnostic::note()
+    ///     .with_message("pending changes")
+    ///     .with_labels(splicer.codespan_labels(()));
+    /// let mut output = NoColor::new(vec![]);
+    /// term::emit(&mut output, &Config::default(), &file, &diagnostic).unwrap();
+    /// let output = String::from_utf8(output.into_inner()).unwrap();
+    /// assert!(output.contains("src/main.rs:2:5"));
+    /// assert!(output.contains(r#"replace with "beep""#));
+    /// ```
+    #[cfg(feature = "codespan-reporting")]
+    pub fn codespan_labels<FileId: Copy>(
+        &self,
+        file_id: FileId,
+    ) -> Vec<codespan_reporting::diagnostic::Label<FileId>> {
+        self.splices
+            .iter()
+            .map(|s| {
+                codespan_reporting::diagnostic::Label::primary(file_id, s.range())
+                    .with_message(describe(s, &self.source[s.range()]))
+            })
+            .collect()
+    }
+
+    /// Describe each queued splice as an `ariadne` label in the source `source_id`, pointing at
+    /// the replaced range in the original string.
+    ///
+    /// The label spans are byte offsets, so the report must be configured with
+    /// `IndexType::Byte`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use ariadne::{Config, IndexType, Report, ReportKind, Source};
+    /// use multisplice::Multisplice;
+    ///
+    /// let source = "let a = 1;\nlet b = 2;\n";
+    /// let mut splicer = Multisplice::new(source);
+    /// splicer.splice(15, 16, "beep");
+    ///
+    /// let mut output = vec![];
+    /// Report::build(ReportKind::Advice, ("src/main.rs", 0..0))
+    ///     .with_config(Config::default().with_color(false).with_index_type(IndexType::Byte))
+    ///     .with_message("pending changes")
+    ///     .with_labels(splicer.ariadne_labels("src/main.rs"))
+    ///     .finish()
+    ///     .write(("src/main.rs", Source::from(source)), &mut output)
+    ///     .unwrap();
+    /// let output = String::from_utf8(output).unwrap();
+    /// assert!(output.contains("let b = 2;"));
+    /// assert!(output.contains(r#"replace with "beep""#));
+    /// ```
+    #[cfg(feature = "ariadne")]
+    pub fn ariadne_labels<SourceId: Clone>(
+        &self,
+        source_id: SourceId,
+    ) -> Vec<ariadne::Label<(SourceId, std::ops::Range<usize>)>>
+    where
+        (SourceId, std::ops::Range<usize>): ariadne::Span,
+    {
+        self.splices
+            .iter()
+            .map(|s| {
+                ariadne::Label::new((source_id.clone(), s.range()))
+                    .with_message(describe(s, &self.source[s.range()]))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,67 @@
+//! Replacement values that are computed when the output is rendered.
+
+use crate::{Multisplice, Splice, SpliceId};
+use std::fmt;
+
+/// A replacement value that is computed when the output is rendered.
+///
+/// This is implemented for closures returning a `String`.
+pub trait LazyReplacement: Send + Sync {
+    /// Compute the replacement value.
+    fn compute(&self) -> String;
+}
+
+impl<F> LazyReplacement for F
+where
+    F: Fn() -> String + Send + Sync,
+{
+    #[inline]
+    fn compute(&self) -> String {
+        self()
+    }
+}
+
+/// A boxed lazy replacement value, so `Splice` can still derive `Debug`.
+pub(crate) struct LazyValue<'a>(pub(crate) Box<dyn LazyReplacement + 'a>);
+
+impl fmt::Debug for LazyValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LazyValue")
+    }
+}
+
+impl<'a> Multisplice<'a> {
+    /// Replace the characters from index `start` up to (but not including) index `end` by a
+    /// value that is computed when the output is rendered.
+    ///
+    /// The value is computed the first time the splice is rendered, and then reused. It is not
+    /// computed at all if the output is never produced. Methods that change replacement values,
+    /// like [`Multisplice::splice_in`] and [`Multisplice::indent`], do not see lazy values.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// let calls = AtomicUsize::new(0);
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice_lazy(2, 3, || {
+    ///     calls.fetch_add(1, Ordering::Relaxed);
+    ///     "beep".to_string()
+    /// });
+    /// assert_eq!(calls.load(Ordering::Relaxed), 0);
+    /// assert_eq!(splicer.to_string(), "a beep c d e");
+    /// assert_eq!(splicer.to_string(), "a beep c d e");
+    /// assert_eq!(calls.load(Ordering::Relaxed), 1);
+    /// ```
+    pub fn splice_lazy(
+        &mut self,
+        start: usize,
+        end: usize,
+        value: impl LazyReplacement + 'a,
+    ) -> SpliceId {
+        let mut splice = Splice::new(self.next_id(), start..end, "".into());
+        splice.meta_mut().lazy = Some(LazyValue(Box::new(value)));
+        self.insert_splice(splice)
+    }
+}
@@ -16,6 +16,73 @@
 //! assert_eq!(splicer.to_string(), "a beep c boop e");
 //! assert_eq!(splicer.slice_range((3..7)), " c boop");
 //! ```
+//!
+//! ## Lifetimes
+//!
+//! Replacement values share the lifetime of the original string, but a `Multisplice` is
+//! covariant over that lifetime: a splicer for a long-lived string can accept borrowed
+//! replacements that live much shorter, as long as the splicer is not used after they are gone.
+//! There is no need to call `.to_string()` on short-lived replacements.
+//!
+//! ```rust
+//! use multisplice::Multisplice;
+//!
+//! let source = String::from("a b c d e");
+//! let mut splicer = Multisplice::new(&source);
+//! splicer.splice(6, 7, "boop");
+//! let output = {
+//!     let beep = "beep".to_uppercase();
+//!     let mut splicer = splicer;
+//!     splicer.splice(2, 3, &beep);
+//!     splicer.to_string()
+//! };
+//! assert_eq!(output, "a BEEP c boop e");
+//! ```
+//!
+//! With the `smallvec` feature, `Multisplice` is invariant, because `SmallVec` is. Short-lived
+//! replacements must then outlive the splicer, and a `Multisplice<'long>` that was passed in can
+//! not be shortened to accept them.
+//!
+//! ## Cargo features
+//!
+//! - `aho-corasick`: replace the matches of many patterns found in a single scan.
+//! - `arbitrary`: generate structurally valid `SplicePlan`s for fuzzing.
+//! - `ariadne`: describe the queued splices as `ariadne` labels.
+//! - `bumpalo`: allocate generated replacement values in a `bumpalo::Bump` arena.
+//! - `bytes`: build the output as `bytes::Bytes`.
+//! - `capi`: a C API in the [`capi`] module, for embedding the splicer in other languages.
+//! - `codespan-reporting`: describe the queued splices as `codespan-reporting` labels.
+//! - `cli`: build the `multisplice` command line tool, which applies a saved patch or JSON
+//!   splice plan to a file.
+//! - `serde_json`: export and import splices as JSON, import machine-applicable suggestions
+//!   from rustc and clippy JSON diagnostics, and replace values in JSON documents by JSON Pointer.
+//! - `similar`: create splices by diffing two strings.
+//! - `smallvec`: store up to 4 splices inline, avoiding a heap allocation for splicers that only
+//!   make a few changes.
+//! - `annotate-snippets`: render the queued splices as compiler-style diagnostics.
+//! - `lsp-types`: convert between splices and Language Server Protocol `TextEdit`s.
+//! - `memmap2`: splice memory-mapped files with `MappedFile`.
+//! - `metrics`: count the splices queued, output strings allocated, bytes copied and time spent
+//!   rendering, exposed through `Multisplice::metrics`.
+//! - `percent-encoding`: percent-encode replacement values that are spliced into URLs.
+//! - `preview`: render colored terminal previews of the queued splices.
+//! - `proc-macro2`: splice the source code of `proc_macro2::Span`s.
+//! - `pulldown-cmark`: rewrite the links, headings and code blocks of Markdown documents.
+//! - `rayon`: assemble large outputs on multiple threads.
+//! - `ropey`: build the output as a `ropey::Rope`.
+//! - `rustfix`: queue the replacements of `rustfix` suggestions.
+//! - `sourcemap`: convert generated source maps to `sourcemap::SourceMap`.
+//! - `saphyr-parser`: splice the YAML source of `saphyr_parser::Span`s.
+//! - `swc`: splice the source code of `swc_common::Span`s.
+//! - `syn`: splice the source code of `syn` syntax tree nodes.
+//! - `test-utils`: the [`assert_spliced!`] macro, for testing code that uses the splicer.
+//! - `text-size`: splice and slice using `text_size::TextRange`s.
+//! - `toml_edit`: splice the TOML source of `toml_edit` nodes, keeping comments and formatting.
+//! - `u32-index`: store indices as `u32` instead of `usize`, using less memory per splice on
+//!   64-bit platforms. Original strings must be smaller than 4GiB.
+//! - `tokio`: stream the output to a `tokio::io::AsyncWrite`.
+//! - `wasm`: JavaScript bindings through `wasm-bindgen`, with an API like the `magic-string`
+//!   package.
 
 #![deny(future_incompatible)]
 #![deny(nonstandard_style)]
@@ -24,11 +91,186 @@
 #![warn(missing_docs)]
 #![warn(unused)]
 
+mod anchor;
+#[cfg(feature = "tokio")]
+mod async_write;
+mod bom;
+mod bounded;
+mod buffer;
+#[cfg(feature = "bumpalo")]
+mod bump;
+#[cfg(feature = "bytes")]
+mod bytes_output;
+#[cfg(feature = "capi")]
+#[allow(unsafe_code)]
+pub mod capi;
+mod chain;
+pub mod compat;
+mod concurrent;
+mod debug;
+mod delimited;
+#[cfg(feature = "serde_json")]
+mod diagnostics;
+#[cfg(feature = "similar")]
+mod diff;
+mod dry_run;
+mod element;
+mod file;
+mod filter;
+#[cfg(feature = "rustfix")]
+mod fix;
+mod frozen;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+mod group;
+mod history;
+mod input_edit;
+mod intern;
+mod invert;
+#[cfg(feature = "serde_json")]
+mod json;
+#[cfg(feature = "serde_json")]
+mod json_pointer;
+#[cfg(any(feature = "ariadne", feature = "codespan-reporting"))]
+mod labels;
+mod lazy;
+mod line_ending;
+mod lines;
+#[cfg(feature = "lsp-types")]
+mod lsp;
+mod macros;
+#[cfg(feature = "pulldown-cmark")]
+mod markdown;
+mod metrics;
+#[cfg(feature = "memmap2")]
+#[allow(unsafe_code)]
+mod mmap;
+mod operation;
+mod origin;
+#[cfg(any(feature = "capi", feature = "wasm"))]
+mod owned;
+#[cfg(feature = "rayon")]
+#[allow(unsafe_code)]
+mod parallel;
+mod patch;
+#[cfg(feature = "aho-corasick")]
+mod patterns;
+#[cfg(feature = "percent-encoding")]
+mod percent;
+mod placeholder;
+#[cfg(feature = "preview")]
+mod preview;
+mod reader;
+mod rebase;
+#[cfg(feature = "ropey")]
+mod rope;
+mod search;
+mod severity;
+mod shared;
+mod slot;
+mod snip;
+#[cfg(feature = "annotate-snippets")]
+mod snippets;
+mod sourcemap;
+#[cfg(feature = "sourcemap")]
+mod sourcemap_crate;
+#[cfg(any(
+    feature = "proc-macro2",
+    feature = "saphyr-parser",
+    feature = "swc",
+    feature = "toml_edit"
+))]
+mod span;
+mod sql;
+mod stable_id;
+#[cfg(feature = "syn")]
+mod syn_span;
+#[cfg(feature = "test-utils")]
+#[doc(hidden)]
+pub mod test_utils;
+#[cfg(feature = "text-size")]
+mod text_range;
+mod tokens;
+mod tracker;
+mod validate;
+mod validated;
+mod variant;
+mod vec;
+pub mod vlq;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod workspace;
+mod wrap;
+
+use history::{Edit, History};
+use lazy::LazyValue;
+use metrics::Counters;
 use std::{
     borrow::Cow,
+    collections::{HashSet, VecDeque},
+    convert::TryFrom,
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
     ops::{Bound, Range, RangeBounds},
+    sync::OnceLock,
 };
 
+pub use anchor::AnchorId;
+pub use bom::Bom;
+pub use bounded::OutputTooLarge;
+pub use buffer::BufferTooSmall;
+pub use concurrent::ConcurrentMultisplice;
+pub use delimited::CsvOptions;
+pub use dry_run::DryRunEdit;
+pub use element::ElementSpan;
+pub use file::splice_file;
+pub use filter::SpliceInfo;
+pub use frozen::Spliced;
+#[cfg(feature = "arbitrary")]
+pub use fuzz::SplicePlan;
+pub use input_edit::{InputEdit, Point};
+pub use intern::Interner;
+#[cfg(feature = "serde_json")]
+pub use json::FromJsonError;
+pub use lazy::LazyReplacement;
+pub use line_ending::LineEnding;
+pub use lines::{OutputLine, OutputLines};
+#[cfg(feature = "metrics")]
+pub use metrics::Metrics;
+#[cfg(feature = "memmap2")]
+pub use mmap::MappedFile;
+pub use operation::Operation;
+pub use origin::{CharsWithOrigin, Origin};
+pub use patch::PatchError;
+#[cfg(feature = "percent-encoding")]
+pub use percent::UrlComponent;
+pub use placeholder::PlaceholderError;
+pub use reader::Reader;
+pub use rebase::{RebaseOptions, Rebased};
+pub use search::{MatchIndices, OutputMatch};
+pub use severity::Severity;
+pub use shared::SharedMultisplice;
+pub use slot::UnfilledSlots;
+pub use sourcemap::{Mapping, OriginalPosition, SourceMap, SourceMapOptions};
+#[cfg(any(
+    feature = "proc-macro2",
+    feature = "saphyr-parser",
+    feature = "swc",
+    feature = "toml_edit"
+))]
+pub use span::SourceSpan;
+pub use sql::SqlDialect;
+pub use stable_id::{IdConflict, StableMergeConflict};
+pub use tokens::Token;
+pub use tracker::OffsetTracker;
+pub use validated::InvalidOutput;
+pub use variant::Variant;
+pub use vec::MultispliceVec;
+#[cfg(feature = "wasm")]
+pub use wasm::JsMultisplice;
+pub use workspace::{ConflictEntry, ConflictReport, Workspace, WorkspaceStats};
+
 fn get_start_bound(bound: Bound<&usize>) -> usize {
     match bound {
         Bound::Included(n) => *n,
@@ -45,22 +287,619 @@ fn get_end_bound(bound: Bound<&usize>, unbounded: usize) -> usize {
     }
 }
 
+/// Check that a range can be spliced into `source`: it does not start after it ends, does not
+/// end after the end of the string, and starts and ends on character boundaries.
+fn check_range(source: &str, new: &Range<usize>) -> Result<(), SpliceError> {
+    if new.start > new.end {
+        return Err(SpliceError::Inverted { new: new.clone() });
+    }
+    if new.end > source.len() {
+        return Err(SpliceError::OutOfBounds {
+            new: new.clone(),
+            len: source.len(),
+        });
+    }
+    let boundary = [new.start, new.end]
+        .iter()
+        .copied()
+        .find(|index| !source.is_char_boundary(*index));
+    match boundary {
+        Some(index) => Err(SpliceError::NotCharBoundary {
+            new: new.clone(),
+            index,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// An error that occurred while queueing a splice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpliceError {
+    /// The new splice overlaps a range that was already spliced.
+    Overlap {
+        /// The range that was already spliced.
+        existing: Range<usize>,
+        /// The range that could not be spliced.
+        new: Range<usize>,
+    },
+    /// The replacement value does not have the same length as the range it replaces. Returned by
+    /// [`Multisplice::splice_same_len`].
+    LengthMismatch {
+        /// The range that could not be spliced.
+        new: Range<usize>,
+        /// The length of the replacement value in bytes.
+        len: usize,
+    },
+    /// The new splice touches a range that was protected with [`Multisplice::protect`].
+    Protected {
+        /// The protected range.
+        protected: Range<usize>,
+        /// The range that could not be spliced.
+        new: Range<usize>,
+    },
+    /// The range ends after the end of the original string.
+    OutOfBounds {
+        /// The range that could not be spliced.
+        new: Range<usize>,
+        /// The length of the original string.
+        len: usize,
+    },
+    /// The range starts after it ends.
+    Inverted {
+        /// The range that could not be spliced.
+        new: Range<usize>,
+    },
+    /// The range starts or ends in the middle of a UTF-8 character.
+    NotCharBoundary {
+        /// The range that could not be spliced.
+        new: Range<usize>,
+        /// The index that is not on a character boundary.
+        index: usize,
+    },
+}
+
+impl fmt::Display for SpliceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpliceError::Overlap { existing, new } => write!(
+                f,
+                "Trying to splice an already spliced range: {:?} overlaps {:?}",
+                new, existing
+            ),
+            SpliceError::LengthMismatch { new, len } => write!(
+                f,
+                "Trying to splice a range of length {} with a value of length {}: {:?}",
+                new.len(),
+                len,
+                new
+            ),
+            SpliceError::Protected { protected, new } => write!(
+                f,
+                "Trying to splice a protected range: {:?} overlaps {:?}",
+                new, protected
+            ),
+            SpliceError::OutOfBounds { new, len } => write!(
+                f,
+                "Trying to splice past the end of a string of length {}: {:?}",
+                len, new
+            ),
+            SpliceError::Inverted { new } => {
+                write!(
+                    f,
+                    "Trying to splice a range that starts after it ends: {:?}",
+                    new
+                )
+            }
+            SpliceError::NotCharBoundary { new, index } => write!(
+                f,
+                "Trying to splice at index {}, which is inside a character: {:?}",
+                index, new
+            ),
+        }
+    }
+}
+
+impl Error for SpliceError {}
+
+/// Two splices from different splicers that change the same part of the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict<'a> {
+    /// The range and value of the splice in the splicer that was merged into.
+    pub ours: (Range<usize>, Cow<'a, str>),
+    /// The range and value of the splice in the splicer that was merged in.
+    pub theirs: (Range<usize>, Cow<'a, str>),
+}
+
+/// An error that occurred while merging two splicers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict<'a> {
+    /// All the conflicting splices.
+    pub conflicts: Vec<Conflict<'a>>,
+}
+
+impl fmt::Display for MergeConflict<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Trying to merge splicers with conflicting splices")?;
+        for (i, Conflict { ours, theirs }) in self.conflicts.iter().enumerate() {
+            let separator = if i == 0 { ": " } else { ", " };
+            write!(
+                f,
+                "{}{:?} => {:?} overlaps {:?} => {:?}",
+                separator, ours.0, ours.1, theirs.0, theirs.1
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for MergeConflict<'_> {}
+
+/// Which neighbouring content an insertion sticks to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Side {
+    /// The insertion belongs to the content before it.
+    Left,
+    /// The insertion belongs to the content after it.
+    Right,
+}
+
+/// Identifies a splice in a [`Multisplice`].
+///
+/// Returned by the methods that queue a splice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SpliceId(usize);
+
+/// An index into the original string, as stored in a splice.
+#[cfg(not(feature = "u32-index"))]
+type Index = usize;
+/// An index into the original string, as stored in a splice. Halves the memory used for indices
+/// on 64-bit platforms.
+#[cfg(feature = "u32-index")]
+type Index = u32;
+
+/// Convert an index into the storage type.
+#[inline]
+#[allow(clippy::useless_conversion)]
+fn to_index(index: usize) -> Index {
+    Index::try_from(index).expect("the u32-index feature only supports strings up to 4GiB")
+}
+
+/// Convert a stored index back into a `usize`.
+#[inline]
+#[allow(clippy::unnecessary_cast)]
+fn from_index(index: Index) -> usize {
+    index as usize
+}
+
 /// A single splice range.
 #[derive(Debug)]
 struct Splice<'a> {
-    /// The range to replace.
-    range: Range<usize>,
+    /// Identifier for this splice.
+    id: SpliceId,
+    /// The start of the range to replace.
+    start: Index,
+    /// The end of the range to replace.
+    end: Index,
     /// Replacement value.
     value: Cow<'a, str>,
+    /// Which side an insertion (empty range) belongs to.
+    side: Side,
+    /// Whether this is generated content that does not correspond to anything in the original
+    /// string.
+    generated: bool,
+    /// Used to resolve conflicts with overlapping splices.
+    priority: i32,
+    /// How confident the author of the splice is that it is correct.
+    severity: Severity,
+    /// Details that most splices do not have. `None` if none of them are set.
+    meta: Option<Box<SpliceMeta<'a>>>,
+    /// Whether the value is rendered as-is, even if the splicer has an escaper.
+    raw: bool,
+}
+
+/// Details of a splice that most splices do not have. They are stored behind a pointer, so
+/// splicers with many splices use less memory and the render walk touches less of it.
+///
+/// Splices whose value may be changed by rendering always have them, to store the rendered
+/// value. See [`Rendering::prepare`].
+#[derive(Debug, Default)]
+struct SpliceMeta<'a> {
+    /// If the value is a copy of a part of the original string, the index it was copied from.
+    origin: Option<Index>,
+    /// Computes the value when it is rendered, instead of using `value`.
+    lazy: Option<LazyValue<'a>>,
+    /// The escaped and post-processed value, computed when it is first rendered. `None` if
+    /// rendering did not change the value.
+    rendered: OnceLock<Option<String>>,
+    /// The original name of an identifier that was renamed by this splice.
+    name: Option<Cow<'a, str>>,
+    /// A human-readable explanation of why this splice was made, shown in previews and reports.
+    note: Option<Cow<'a, str>>,
+    /// The group this splice belongs to. Splices in disabled groups are not rendered.
+    group: Option<Cow<'a, str>>,
+    /// An identifier chosen by the author of the splice, used to match it up with the same
+    /// splice made by another splicer.
+    stable_id: Option<Cow<'a, str>>,
+    /// Mutually exclusive values for this splice, one of which is `value`. Empty if the splice
+    /// has no alternatives.
+    alternatives: Vec<Cow<'a, str>>,
+}
+
+/// The details of splices that have none.
+static NO_META: SpliceMeta<'static> = SpliceMeta {
+    origin: None,
+    lazy: None,
+    rendered: OnceLock::new(),
+    name: None,
+    note: None,
+    group: None,
+    stable_id: None,
+    alternatives: Vec::new(),
+};
+
+impl<'a> SpliceMeta<'a> {
+    /// Copy the details that describe the splice, without its origin, lazy value or rendered
+    /// value.
+    fn copy_details(&self) -> Self {
+        SpliceMeta {
+            name: self.name.clone(),
+            note: self.note.clone(),
+            group: self.group.clone(),
+            stable_id: self.stable_id.clone(),
+            alternatives: self.alternatives.clone(),
+            ..SpliceMeta::default()
+        }
+    }
+}
+
+impl<'a> Splice<'a> {
+    fn new(id: SpliceId, range: Range<usize>, value: Cow<'a, str>) -> Self {
+        Splice {
+            id,
+            start: to_index(range.start),
+            end: to_index(range.end),
+            value,
+            side: Side::Right,
+            generated: false,
+            priority: 0,
+            severity: Severity::MachineApplicable,
+            meta: None,
+            raw: false,
+        }
+    }
+
+    /// The start of the range to replace.
+    #[inline]
+    fn start(&self) -> usize {
+        from_index(self.start)
+    }
+
+    /// The end of the range to replace.
+    #[inline]
+    fn end(&self) -> usize {
+        from_index(self.end)
+    }
+
+    /// The range to replace.
+    #[inline]
+    fn range(&self) -> Range<usize> {
+        self.start()..self.end()
+    }
+
+    /// If the value is a copy of a part of the original string, the index it was copied from.
+    #[inline]
+    fn origin(&self) -> Option<usize> {
+        self.meta().origin.map(from_index)
+    }
+
+    /// The details of this splice that most splices do not have.
+    #[inline]
+    fn meta(&self) -> &SpliceMeta<'a> {
+        self.meta.as_deref().unwrap_or(&NO_META)
+    }
+
+    /// The details of this splice that most splices do not have, for changing them.
+    fn meta_mut(&mut self) -> &mut SpliceMeta<'a> {
+        self.meta.get_or_insert_with(Box::default)
+    }
+
+    /// The text this splice renders to.
+    fn output(&self, rendering: &Rendering<'_>) -> &str {
+        if !rendering.applies_to(self) {
+            return &self.value;
+        }
+        self.meta
+            .as_ref()
+            .expect("rendered splices are prepared")
+            .rendered
+            .get_or_init(|| rendering.render(self))
+            .as_deref()
+            .unwrap_or(&self.value)
+    }
+
+    /// The text this splice renders to, borrowing the original value if it is unchanged.
+    fn output_cow(&self, rendering: &Rendering<'_>) -> Cow<'a, str> {
+        let output = self.output(rendering);
+        if std::ptr::eq(output, &*self.value) {
+            self.value.clone()
+        } else {
+            Cow::Owned(output.to_string())
+        }
+    }
+
+    /// Get the value for changing it, discarding the rendered value.
+    #[inline]
+    fn value_mut(&mut self) -> &mut Cow<'a, str> {
+        self.clear_rendered();
+        &mut self.value
+    }
+
+    /// Discard the rendered value, after the value or the way it is rendered changed.
+    #[inline]
+    fn clear_rendered(&mut self) {
+        if let Some(meta) = &mut self.meta {
+            meta.rendered = OnceLock::new();
+        }
+    }
+
+    #[inline]
+    fn is_insertion(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Check if this splice overlaps `range`, so they can not both be applied. Insertions may go
+    /// at the edges of a replaced range.
+    fn overlaps(&self, range: &Range<usize>) -> bool {
+        if self.is_insertion() {
+            range.start < self.start() && self.start() < range.end
+        } else if range.is_empty() {
+            self.start() < range.start && range.start < self.end()
+        } else {
+            self.start() < range.end && range.start < self.end()
+        }
+    }
+
+    /// Check if this splice makes the exact same change as `other`. Queueing the same change
+    /// twice is not a conflict.
+    #[inline]
+    fn is_duplicate_of(&self, other: &Splice<'_>) -> bool {
+        !self.is_insertion()
+            && self.meta().lazy.is_none()
+            && other.meta().lazy.is_none()
+            && self.start == other.start
+            && self.end == other.end
+            && self.value == other.value
+    }
+
+    /// The key the splices are sorted by. At the same offset, left insertions come first, then
+    /// right insertions, then the splice replacing the content at that offset. Banners and
+    /// footers go before and after everything else.
+    #[inline]
+    fn order_key(&self) -> (usize, u8) {
+        let rank = match (self.is_insertion(), self.generated, self.side) {
+            (true, true, Side::Right) => 0,
+            (true, false, Side::Left) => 1,
+            (true, false, Side::Right) => 2,
+            (false, _, _) => 3,
+            (true, true, Side::Left) => 4,
+        };
+        (self.start(), rank)
+    }
+}
+
+/// Merge two sorted lists of splices, tagging each splice with whether it came from `b`. On ties,
+/// splices from `a` go first.
+fn merge_sorted<'a>(
+    a: impl IntoIterator<Item = Splice<'a>>,
+    b: impl IntoIterator<Item = Splice<'a>>,
+) -> Vec<(bool, Splice<'a>)> {
+    let mut a = a.into_iter().map(|s| (false, s)).peekable();
+    let mut b = b.into_iter().map(|s| (true, s)).peekable();
+    let mut merged = Vec::with_capacity(a.size_hint().0 + b.size_hint().0);
+    loop {
+        let take_a = match (a.peek(), b.peek()) {
+            (Some((_, x)), Some((_, y))) => x.order_key() <= y.order_key(),
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => break,
+        };
+        let next = if take_a { a.next() } else { b.next() };
+        merged.extend(next);
+    }
+    merged
+}
+
+/// Find the overlapping splices in a merged list of splices.
+///
+/// Returns the indices of duplicate splices, and pairs of indices of conflicting splices. In each
+/// pair, the splice from the first list comes first.
+fn find_conflicts(merged: &[(bool, Splice<'_>)]) -> (Vec<usize>, Vec<(usize, usize)>) {
+    let mut duplicates = vec![];
+    let mut conflicts = vec![];
+    let mut furthest: Option<usize> = None;
+    for (i, current) in merged.iter().enumerate() {
+        if let Some(prev_index) = furthest {
+            let prev = &merged[prev_index];
+            if prev.1.is_duplicate_of(&current.1) {
+                duplicates.push(i);
+                continue;
+            }
+            if prev.1.end() > current.1.start() {
+                if current.0 || !prev.0 {
+                    conflicts.push((prev_index, i));
+                } else {
+                    conflicts.push((i, prev_index));
+                }
+                continue;
+            }
+        }
+        furthest = Some(i);
+    }
+    (duplicates, conflicts)
+}
+
+/// Remove the items at the given sorted `indices`.
+fn remove_indices<T>(list: Vec<T>, indices: Vec<usize>) -> impl Iterator<Item = T> {
+    let mut indices = indices.into_iter().peekable();
+    list.into_iter()
+        .enumerate()
+        .filter(move |(i, _)| indices.next_if_eq(i).is_none())
+        .map(|(_, item)| item)
+}
+
+/// Storage for the queued splices.
+#[cfg(not(feature = "smallvec"))]
+type SpliceVec<'a> = Vec<Splice<'a>>;
+/// Storage for the queued splices. Most splicers only ever hold a handful of splices, so they
+/// are stored inline.
+#[cfg(feature = "smallvec")]
+type SpliceVec<'a> = smallvec::SmallVec<[Splice<'a>; 4]>;
+
+/// A function that escapes replacement values.
+type Escaper = for<'s> fn(&'s str) -> Cow<'s, str>;
+
+/// A function that post-processes replacement values.
+type RenderHookFn<'a> = dyn Fn(SpliceId, &mut String) + Send + Sync + 'a;
+
+/// A function that is called with the range and value of every splice that is queued.
+type ObserverFn<'a> = dyn FnMut(Range<usize>, &str) + Send + Sync + 'a;
+
+/// A boxed render hook, so `Multisplice` can still derive `Debug`.
+struct RenderHook<'a>(Box<RenderHookFn<'a>>);
+
+impl fmt::Debug for RenderHook<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RenderHook")
+    }
+}
+
+/// How replacement values are rendered.
+#[derive(Debug, Default)]
+struct Rendering<'a> {
+    /// Escapes replacement values that are not raw.
+    escaper: Option<Escaper>,
+    /// Post-processes all replacement values.
+    hook: Option<RenderHook<'a>>,
+}
+
+impl Rendering<'_> {
+    /// Check if rendering may change the value of a splice.
+    #[inline]
+    fn applies_to(&self, splice: &Splice<'_>) -> bool {
+        (self.escaper.is_some() && !splice.raw)
+            || self.hook.is_some()
+            || splice.meta().lazy.is_some()
+    }
+
+    /// Give a splice the details that store its rendered value, if rendering may change it.
+    /// Splices must be prepared whenever they are added to a splicer, or the way they are
+    /// rendered changes.
+    #[inline]
+    fn prepare(&self, splice: &mut Splice<'_>) {
+        if self.applies_to(splice) {
+            splice.meta_mut();
+        }
+    }
+
+    /// Render the value of a splice, returning `None` if it is unchanged.
+    fn render(&self, splice: &Splice<'_>) -> Option<String> {
+        let computed = splice.meta().lazy.as_ref().map(|lazy| lazy.0.compute());
+        let value = computed.as_deref().unwrap_or(&splice.value);
+        let escaped = match self.escaper {
+            Some(escape) if !splice.raw => escape(value),
+            _ => Cow::Borrowed(value),
+        };
+        let rendered = match escaped {
+            Cow::Borrowed(escaped) if computed.is_none() && escaped == splice.value => None,
+            escaped => Some(escaped.into_owned()),
+        };
+        match &self.hook {
+            Some(hook) => {
+                let mut value = rendered.unwrap_or_else(|| splice.value.to_string());
+                (hook.0)(splice.id, &mut value);
+                Some(value)
+            }
+            None => rendered,
+        }
+    }
+}
+
+/// The size of the blocks of the original string that changes in length are summed up for.
+const OUTPUT_BLOCK_LEN: usize = 1024;
+
+/// The full output, and where the splices are in it.
+struct CachedOutput {
+    text: String,
+    /// The changes in length that the rendered splices make, per block of the original string.
+    /// `None` if some splices are hidden by or overlap others, so the output can not be updated in
+    /// place.
+    deltas: Option<BlockDeltas>,
+}
+
+/// A Fenwick tree of the changes in length that splices make, summed per block of
+/// [`OUTPUT_BLOCK_LEN`] bytes of the original string, by the start of the splices.
+struct BlockDeltas(Vec<isize>);
+
+impl BlockDeltas {
+    fn new(source_len: usize) -> Self {
+        BlockDeltas(vec![0; source_len / OUTPUT_BLOCK_LEN + 1])
+    }
+
+    /// Add the change in length of a splice starting at `start`.
+    fn add(&mut self, start: usize, delta: isize) {
+        let mut i = start / OUTPUT_BLOCK_LEN + 1;
+        while i <= self.0.len() {
+            self.0[i - 1] += delta;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// Sum the changes in length of the splices that start in the blocks before `block`.
+    fn before(&self, block: usize) -> isize {
+        let mut i = block;
+        let mut sum = 0;
+        while i > 0 {
+            sum += self.0[i - 1];
+            i &= i - 1;
+        }
+        sum
+    }
 }
 
 /// A multisplice operation.
-#[derive(Debug)]
 pub struct Multisplice<'a> {
     /// The original string.
     source: &'a str,
     /// Splice operations.
-    splices: Vec<Splice<'a>>,
+    splices: SpliceVec<'a>,
+    /// The identifier for the next splice.
+    next_id: usize,
+    /// Ranges that may not be spliced.
+    protected: Vec<Range<usize>>,
+    /// Offsets into the original string that are tracked through the splices.
+    anchors: Vec<usize>,
+    /// Names of the empty insertions that mark positions in the output.
+    markers: Vec<(Cow<'a, str>, SpliceId)>,
+    /// Keys of the splices reserved as slots, and whether they were filled.
+    slots: Vec<(Cow<'a, str>, SpliceId, bool)>,
+    /// How replacement values are rendered.
+    rendering: Rendering<'a>,
+    /// The group that new splices are added to.
+    group: Option<Cow<'a, str>>,
+    /// Groups whose splices are not rendered.
+    disabled_groups: Vec<Cow<'a, str>>,
+    /// Changes that can be undone, if history is enabled.
+    history: Option<History<'a>>,
+    /// Called whenever a splice is queued.
+    observer: Option<Box<ObserverFn<'a>>>,
+    /// Whether out-of-range and inverted ranges are clamped instead of rejected.
+    lenient: bool,
+    /// Counts the work the splicer does, with the `metrics` feature.
+    counters: Counters,
+    /// The full output, built when it is first rendered after a change.
+    output: OnceLock<CachedOutput>,
+    /// A byte order mark that is written before the output. See [`Multisplice::new_bom_aware`].
+    bom: Option<&'a str>,
 }
 
 impl<'a> Multisplice<'a> {
@@ -69,16 +908,153 @@ impl<'a> Multisplice<'a> {
     pub fn new(source: &'a str) -> Self {
         Multisplice {
             source,
-            splices: vec![],
+            splices: SpliceVec::new(),
+            next_id: 0,
+            protected: vec![],
+            anchors: vec![],
+            markers: vec![],
+            slots: vec![],
+            rendering: Rendering::default(),
+            group: None,
+            disabled_groups: vec![],
+            history: None,
+            observer: None,
+            lenient: false,
+            counters: Counters::default(),
+            output: OnceLock::new(),
+            bom: None,
         }
     }
 
+    /// Create a "multisplicer" for the given string, with space for at least `capacity` splices.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::with_capacity("a b c d e", 2);
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(6, 7, "boop");
+    /// assert_eq!(splicer.to_string(), "a beep c boop e");
+    /// ```
+    #[inline]
+    pub fn with_capacity(source: &'a str, capacity: usize) -> Self {
+        let mut splicer = Self::new(source);
+        splicer.splices.reserve(capacity);
+        splicer
+    }
+
+    /// Create a "multisplicer" for the given string, with all the splices from `edits` already
+    /// queued.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let fixes = vec![(2..3, "beep"), (6..7, "boop")];
+    /// let splicer = Multisplice::from_edits("a b c d e", fixes).unwrap();
+    /// assert_eq!(splicer.to_string(), "a beep c boop e");
+    /// ```
+    pub fn from_edits<V>(
+        source: &'a str,
+        edits: impl IntoIterator<Item = (Range<usize>, V)>,
+    ) -> Result<Self, Vec<SpliceError>>
+    where
+        V: Into<Cow<'a, str>>,
+    {
+        let mut splicer = Self::new(source);
+        splicer.splice_many(edits)?;
+        Ok(splicer)
+    }
+
+    /// Get the original string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// assert_eq!(splicer.source(), "a b c d e");
+    /// ```
+    #[inline]
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Get a part of the original string, ignoring any splices.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// assert_eq!(splicer.original(0..5), "a b c");
+    /// assert_eq!(splicer.slice(0, 5), "a beep c");
+    /// ```
+    #[inline]
+    pub fn original(&self, range: impl RangeBounds<usize>) -> &'a str {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        &self.source[start..end]
+    }
+
+    /// Get the queued splices that intersect a range of the original string, as their range and
+    /// replacement value.
+    ///
+    /// Splices intersect the range if they replace any part of it. Insertions intersect the range
+    /// if they are inside it or at its edges.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.insert_left(5, "!");
+    /// splicer.splice(6, 7, "boop");
+    /// assert_eq!(splicer.original(0..5), "a b c");
+    /// assert_eq!(
+    ///     splicer.splices_intersecting(0..5),
+    ///     vec![(2..3, "beep"), (5..5, "!")]
+    /// );
+    /// ```
+    pub fn splices_intersecting(
+        &self,
+        range: impl RangeBounds<usize>,
+    ) -> Vec<(Range<usize>, &str)> {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        self.splices
+            .iter()
+            .filter(|s| {
+                if s.is_insertion() {
+                    start <= s.start() && s.start() <= end
+                } else {
+                    s.start() < end && start < s.end()
+                }
+            })
+            .map(|s| (s.range(), &*s.value))
+            .collect()
+    }
+
     /// Replace the characters from index `start` up to (but not including) index `end` by the
     /// string `value`.
     ///
     /// If the replacement lifetime outlives the input string, you can pass in cheap &str references.
     /// Else, pass in an owned String using `replacement.to_string()`.
     ///
+    /// Splicing a range that was already spliced panics, unless it is the exact same range with
+    /// the exact same value: then the duplicate splice is ignored.
+    ///
+    /// An empty range, where `start == end`, inserts `value` at that index, like
+    /// [`Multisplice::insert_right`]. A range where `start > end` panics, unless lenient mode is
+    /// enabled with [`Multisplice::set_lenient`]. Use [`Multisplice::try_splice`] to get an error
+    /// instead.
+    ///
+    /// Returns a [`SpliceId`] that can be used to refer to the splice later.
+    ///
     /// # Example
     /// ```rust
     /// use multisplice::Multisplice;
@@ -90,12 +1066,67 @@ impl<'a> Multisplice<'a> {
     ///     splicer.splice(6, 7, replacement);
     /// }
     /// assert_eq!(splicer.to_string(), "a beep c boop e");
+    ///
+    /// // Making the same change twice is fine
+    /// splicer.splice(2, 3, "beep".to_string());
+    /// assert_eq!(splicer.to_string(), "a beep c boop e");
     /// ```
     #[inline]
-    pub fn splice(&mut self, start: usize, end: usize, value: impl Into<Cow<'a, str>>) {
+    pub fn splice(&mut self, start: usize, end: usize, value: impl Into<Cow<'a, str>>) -> SpliceId {
         self.splice_cow(start, end, value.into())
     }
 
+    /// Replace the characters from index `start` up to (but not including) index `end` by the
+    /// string `value`, returning an error instead of panicking if that is not possible.
+    ///
+    /// Returns [`SpliceError::Inverted`] if `start > end`, [`SpliceError::OutOfBounds`] if `end`
+    /// is past the end of the original string, [`SpliceError::NotCharBoundary`] if the range
+    /// starts or ends inside a UTF-8 character, [`SpliceError::Protected`] if the range touches a
+    /// protected range, and [`SpliceError::Overlap`] if it starts inside a range that was
+    /// already spliced. In lenient mode, the range is clamped first, so it can not be out of
+    /// bounds or inverted. Empty ranges are insertions, like with [`Multisplice::splice`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SpliceError};
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// assert!(splicer.try_splice(2, 3, "beep").is_ok());
+    /// assert!(splicer.try_splice(4, 4, "!").is_ok());
+    /// assert_eq!(splicer.try_splice(7, 6, "boop"), Err(SpliceError::Inverted { new: 7..6 }));
+    /// assert_eq!(
+    ///     splicer.try_splice(2, 5, "boop"),
+    ///     Err(SpliceError::Overlap { existing: 2..3, new: 2..5 }),
+    /// );
+    /// assert_eq!(splicer.to_string(), "a beep !c d e");
+    /// ```
+    pub fn try_splice(
+        &mut self,
+        start: usize,
+        end: usize,
+        value: impl Into<Cow<'a, str>>,
+    ) -> Result<SpliceId, SpliceError> {
+        let new = self.clamp(start..end);
+        check_range(self.source, &new)?;
+        let splice = Splice::new(SpliceId(self.next_id), new.clone(), value.into());
+        self.check_protected(&splice)?;
+        let existing = self.splices.iter().find(|s| {
+            let range = s.range();
+            let inside = range.start <= new.start && range.end > new.start;
+            inside
+                && !s.is_duplicate_of(&splice)
+                && !(splice.is_insertion() && range.start == new.start)
+        });
+        if let Some(existing) = existing {
+            return Err(SpliceError::Overlap {
+                existing: existing.range(),
+                new,
+            });
+        }
+        self.next_id += 1;
+        Ok(self.insert_splice(splice))
+    }
+
     /// Replace the characters in the range `range` by the string `value`.
     ///
     /// If the replacement lifetime outlives the input string, you can pass in cheap &str references.
@@ -114,99 +1145,2152 @@ impl<'a> Multisplice<'a> {
     /// assert_eq!(splicer.to_string(), "a beep c boop");
     /// ```
     #[inline]
-    pub fn splice_range(&mut self, range: impl RangeBounds<usize>, value: impl Into<Cow<'a, str>>) {
+    pub fn splice_range(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        value: impl Into<Cow<'a, str>>,
+    ) -> SpliceId {
         let start = get_start_bound(range.start_bound());
         let end = get_end_bound(range.end_bound(), self.source.len());
         self.splice_cow(start, end, value.into())
     }
 
-    fn splice_cow(&mut self, start: usize, end: usize, value: Cow<'a, str>) {
-        // Sorted insert
-        let mut insert_at = None;
-        for (i, s) in self.splices.iter().enumerate() {
-            let range = &s.range;
-            assert!(
-                !(range.start <= start && range.end > start),
-                "Trying to splice an already spliced range"
-            );
-            if range.start > start {
-                insert_at = Some(i);
-                break;
-            }
-        }
-
-        let splice = Splice {
-            range: Range { start, end },
-            value,
-        };
-        match insert_at {
-            Some(i) => self.splices.insert(i, splice),
-            None => self.splices.push(splice),
-        };
-    }
-
-    /// Get a part of the spliced string, using indices `start` to `end` (exclusive) from the
-    /// original string.
-    /// If the `start` or `end` indices are in the middle of a spliced range, the full value of the
-    /// splice is included in the return value. For example, when indices 1-10 were replaced with a
-    /// value "Hello World", requesting a slice of indices 7-20 will return the entire "Hello
-    /// World" string followed by indices 11-20.
+    /// Replace a range, resolving conflicts with overlapping splices by their priority.
     ///
-    /// # Example
+    /// If the range overlaps splices with a lower priority, those are removed. If it overlaps a
+    /// splice with the same or a higher priority, the new splice is discarded and `None` is
+    /// returned, so the splice that was queued first wins ties. Splices queued by other methods
+    /// have priority 0.
     ///
+    /// # Example
     /// ```rust
     /// use multisplice::Multisplice;
-    /// use std::borrow::Cow;
     ///
     /// let mut splicer = Multisplice::new("a b c d e");
-    /// splicer.splice(2, 3, "beep");
-    /// splicer.splice(6, 7, "boop");
+    /// splicer.splice_with_priority(2..3, "beep", 1);
+    /// splicer.splice_with_priority(6..7, "boop", 1);
+    /// // Displaces both lower-priority splices
+    /// assert!(splicer.splice_with_priority(2..7, "beep and boop", 2).is_some());
+    /// // Loses against an overlapping splice with a higher priority
+    /// assert!(splicer.splice_with_priority(4..5, "C", 1).is_none());
+    /// assert_eq!(splicer.to_string(), "a beep and boop e");
+    /// ```
+    ///
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice_with_priority(2..3, "beep", 1);
+    /// assert_eq!(splicer.to_string(), "a beep c d e");
+    /// splicer.splice_with_priority(2..7, "beep and boop", 2);
+    /// assert_eq!(splicer.to_string(), "a beep and boop e");
+    /// ```
+    pub fn splice_with_priority(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        value: impl Into<Cow<'a, str>>,
+        priority: i32,
+    ) -> Option<SpliceId> {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        let range = start..end;
+        let overlapping = |s: &Splice<'_>| s.overlaps(&range);
+        if self
+            .splices
+            .iter()
+            .any(|s| overlapping(s) && s.priority >= priority)
+        {
+            return None;
+        }
+        self.begin_step();
+        self.retain_splices(|s| !overlapping(s));
+        let splice = Splice {
+            priority,
+            ..Splice::new(self.next_id(), range.clone(), value.into())
+        };
+        let id = self.insert_splice(splice);
+        self.end_step();
+        Some(id)
+    }
+
+    /// Replace a range, clipping it around any overlapping splices instead of panicking.
+    ///
+    /// Only the parts of the range that were not spliced yet are replaced. `value` is put in the
+    /// first of those parts, and the other parts are removed. Returns `None` if the whole range was
+    /// already spliced.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(4, 5, "C");
+    /// // Split around the existing splice
+    /// splicer.splice_clipped(2..7, "beep");
+    /// assert_eq!(splicer.to_string(), "a beepC e");
+    /// // Entirely covered by existing splices
+    /// assert!(splicer.splice_clipped(3..5, "boop").is_none());
+    /// ```
+    pub fn splice_clipped(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        value: impl Into<Cow<'a, str>>,
+    ) -> Option<SpliceId> {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        let range = start..end;
+
+        let mut free = vec![];
+        let mut at = start;
+        for s in self.splices.iter().filter(|s| s.overlaps(&range)) {
+            if s.start() > at {
+                free.push(at..s.start());
+            }
+            at = at.max(s.end());
+        }
+        if at < end || (range.is_empty() && at == start) {
+            free.push(at..end);
+        }
+
+        let mut free = free.into_iter();
+        let first = free.next()?;
+        let id = self.splice_cow(first.start, first.end, value.into());
+        for range in free {
+            self.splice_cow(range.start, range.end, Cow::Borrowed(""));
+        }
+        Some(id)
+    }
+
+    /// Protect a range of the original string, so that any later attempt to splice it fails.
+    ///
+    /// Methods that queue a single splice panic when it touches a protected range, and methods
+    /// that queue many splices return [`SpliceError::Protected`]. Insertions at the edges of a
+    /// protected range are allowed. Splices that were queued before protecting the range are kept.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SpliceError};
+    ///
+    /// let mut splicer = Multisplice::new("a 'b' c d e");
+    /// splicer.protect(2..5);
+    /// splicer.insert_left(5, "!");
+    /// let errors = splicer.splice_many(vec![(0..3, "")]).unwrap_err();
+    /// assert_eq!(errors, vec![SpliceError::Protected { protected: 2..5, new: 0..3 }]);
+    /// assert_eq!(splicer.to_string(), "a 'b'! c d e");
+    /// ```
+    pub fn protect(&mut self, range: impl RangeBounds<usize>) {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        self.protected.push(start..end);
+    }
+
+    /// Replace every character in a range with `mask`, so the output has the same number of
+    /// characters as the original string.
+    ///
+    /// If both the range and `mask` are ASCII, the byte length is preserved as well.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("password=hunter2; user=me");
+    /// splicer.redact(9..16, '*');
+    /// assert_eq!(splicer.to_string(), "password=*******; user=me");
+    /// ```
+    pub fn redact(&mut self, range: impl RangeBounds<usize>, mask: char) -> SpliceId {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        let len = self.source[start..end].chars().count();
+        let value = std::iter::repeat_n(mask, len).collect::<String>();
+        self.splice_cow(start, end, Cow::Owned(value))
+    }
+
+    /// Replace a range with a value of exactly the same length in bytes, so the indices of the
+    /// output stay the same as those of the original string.
+    ///
+    /// Returns [`SpliceError::LengthMismatch`] without queueing anything if the lengths differ,
+    /// or the same errors as [`Multisplice::try_splice`] if the range does not fit the original
+    /// string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SpliceError};
+    ///
+    /// let mut splicer = Multisplice::new("id=0001;name=a   ;");
+    /// splicer.splice_same_len(3..7, "0042").unwrap();
+    /// let error = splicer.splice_same_len(13..17, "b").unwrap_err();
+    /// assert_eq!(error, SpliceError::LengthMismatch { new: 13..17, len: 1 });
+    /// assert_eq!(splicer.to_string(), "id=0042;name=a   ;");
+    /// assert_eq!(
+    ///     splicer.splice_same_len(30..32, "xx"),
+    ///     Err(SpliceError::OutOfBounds { new: 30..32, len: 18 }),
+    /// );
+    /// assert_eq!(
+    ///     splicer.splice_same_len(4..2, "xx"),
+    ///     Err(SpliceError::Inverted { new: 4..2 }),
+    /// );
+    /// ```
+    pub fn splice_same_len(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        value: impl Into<Cow<'a, str>>,
+    ) -> Result<SpliceId, SpliceError> {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        let value = value.into();
+        check_range(self.source, &(start..end))?;
+        if value.len() != end - start {
+            return Err(SpliceError::LengthMismatch {
+                new: start..end,
+                len: value.len(),
+            });
+        }
+        Ok(self.splice_cow(start, end, value))
+    }
+
+    /// Replace a range with a new name for an identifier, recording its original name in
+    /// generated source maps.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SourceMapOptions};
+    ///
+    /// let mut splicer = Multisplice::new("let count = 1");
+    /// splicer.splice_named(4..9, "a", "count");
+    /// assert_eq!(splicer.to_string(), "let a = 1");
+    /// let map = splicer.generate_map(&SourceMapOptions::new("input.js"));
+    /// assert_eq!(map.names, vec!["count".to_string()]);
+    /// assert_eq!(map.mappings, "AAAA,IAAIA,CAAK");
+    /// ```
+    pub fn splice_named(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        value: impl Into<Cow<'a, str>>,
+        original_name: impl Into<Cow<'a, str>>,
+    ) -> SpliceId {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        let mut splice = Splice::new(self.next_id(), start..end, value.into());
+        splice.meta_mut().name = Some(original_name.into());
+        self.insert_splice(splice)
+    }
+
+    /// Replace a range, attaching a human-readable note that explains why the change was made.
+    ///
+    /// The note is shown in [`Multisplice::preview_diff`] and in workspace conflict reports.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("let count = 1");
+    /// let id = splicer.splice_with_note(4..9, "total", "`count` is ambiguous");
+    /// assert_eq!(splicer.to_string(), "let total = 1");
+    /// assert_eq!(splicer.note(id), Some("`count` is ambiguous"));
+    /// ```
+    pub fn splice_with_note(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        value: impl Into<Cow<'a, str>>,
+        note: impl Into<Cow<'a, str>>,
+    ) -> SpliceId {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        let mut splice = Splice::new(self.next_id(), start..end, value.into());
+        splice.meta_mut().note = Some(note.into());
+        self.insert_splice(splice)
+    }
+
+    /// Get the note attached to a splice with [`Multisplice::splice_with_note`].
+    #[inline]
+    pub fn note(&self, id: SpliceId) -> Option<&str> {
+        self.splices
+            .iter()
+            .find(|s| s.id == id)
+            .and_then(|s| s.meta().note.as_deref())
+    }
+
+    /// Iterate over the ranges of the queued splices that have a note, and their notes.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c");
+    /// splicer.splice_with_note(4..5, "d", "c is deprecated");
+    /// splicer.splice(0, 1, "beep");
+    /// assert_eq!(splicer.notes().collect::<Vec<_>>(), vec![(4..5, "c is deprecated")]);
+    /// ```
+    pub fn notes(&self) -> impl Iterator<Item = (Range<usize>, &str)> {
+        self.splices
+            .iter()
+            .filter_map(|s| Some((s.range(), s.meta().note.as_deref()?)))
+    }
+
+    /// Escape replacement values with `escaper` when rendering them.
+    ///
+    /// The escaper applies to all replacement values, including ones that were queued before
+    /// calling this method, except the ones queued with [`Multisplice::splice_raw`]. Values are
+    /// escaped lazily, the first time they are rendered.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    /// use std::borrow::Cow;
+    ///
+    /// fn escape_html(value: &str) -> Cow<'_, str> {
+    ///     if value.contains(&['<', '>', '&'][..]) {
+    ///         let value = value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    ///         Cow::Owned(value)
+    ///     } else {
+    ///         Cow::Borrowed(value)
+    ///     }
+    /// }
+    ///
+    /// let mut splicer = Multisplice::new("<p>{name}</p>{footer}");
+    /// splicer.set_escaper(escape_html);
+    /// splicer.splice(3, 9, "<script>");
+    /// splicer.splice_raw(13, 21, "<hr>");
+    /// assert_eq!(splicer.to_string(), "<p>&lt;script&gt;</p><hr>");
+    /// ```
+    pub fn set_escaper(&mut self, escaper: fn(&str) -> Cow<'_, str>) {
+        self.rendering.escaper = Some(escaper);
+        self.clear_rendered();
+    }
+
+    /// Post-process replacement values with `hook` when rendering them.
+    ///
+    /// The hook receives the ID of each splice and its value, after escaping, and may change the
+    /// value in place. It runs lazily, the first time a value is rendered, so values that are
+    /// never rendered are not processed.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.set_render_hook(|_id, value| value.make_ascii_uppercase());
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(6, 7, "boop");
+    /// assert_eq!(splicer.to_string(), "a BEEP c BOOP e");
+    /// assert_eq!(splicer.slice(0, 5), "a BEEP c");
+    /// ```
+    pub fn set_render_hook(&mut self, hook: impl Fn(SpliceId, &mut String) + Send + Sync + 'a) {
+        self.rendering.hook = Some(RenderHook(Box::new(hook)));
+        self.clear_rendered();
+    }
+
+    /// Remove the splices for which `keep` returns false, along with the markers and slots that
+    /// refer to them.
+    fn retain_splices(&mut self, mut keep: impl FnMut(&Splice<'a>) -> bool) {
+        let removed = self
+            .splices
+            .iter()
+            .filter(|s| !keep(s))
+            .map(|s| s.id)
+            .collect::<HashSet<_>>();
+        if removed.is_empty() {
+            return;
+        }
+        let edit = self.take_splices(&removed);
+        self.record(|_| edit);
+        self.output = OnceLock::new();
+    }
+
+    /// Clamp out-of-range edits instead of rejecting them.
+    ///
+    /// In lenient mode, ranges that start or end after the end of the original string are
+    /// clamped to its end, and ranges that start after they end are treated as an empty range at
+    /// their start. This is useful when offsets come from slightly lossy tools, and a best effort
+    /// result is better than a failure. Lenient mode only affects splices queued after enabling
+    /// it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c");
+    /// splicer.set_lenient(true);
+    /// splicer.splice(4, 10, "beep");
+    /// splicer.splice(3, 1, "!");
+    /// assert_eq!(splicer.to_string(), "a b! beep");
+    /// ```
+    #[inline]
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
+
+    /// Clamp a range to the original string, if lenient mode is enabled.
+    #[inline]
+    fn clamp(&self, range: Range<usize>) -> Range<usize> {
+        if !self.lenient {
+            return range;
+        }
+        let len = self.source.len();
+        let start = range.start.min(len);
+        start..range.end.min(len).max(start)
+    }
+
+    /// Call `observer` with the range and value of every splice that is queued from now on, for
+    /// example for logging or for updating a live preview.
+    ///
+    /// Splices queued in bulk with [`Multisplice::splice_many`] are reported in order, after all
+    /// of them were queued. Changes to the values of queued splices are not reported.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let log = Arc::new(Mutex::new(vec![]));
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// let observer_log = Arc::clone(&log);
+    /// splicer.on_splice(move |range, value| {
+    ///     observer_log.lock().unwrap().push(format!("{:?} => {:?}", range, value));
+    /// });
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice_many(vec![(8..9, "!"), (6..7, "boop")]).unwrap();
+    /// assert_eq!(
+    ///     *log.lock().unwrap(),
+    ///     vec![r#"2..3 => "beep""#, r#"6..7 => "boop""#, r#"8..9 => "!""#],
+    /// );
+    /// ```
+    pub fn on_splice(&mut self, observer: impl FnMut(Range<usize>, &str) + Send + Sync + 'a) {
+        self.observer = Some(Box::new(observer));
+    }
+
+    /// Discard the rendered values of all splices, after the way they are rendered changed.
+    fn clear_rendered(&mut self) {
+        self.output = OnceLock::new();
+        for splice in self.splices.iter_mut() {
+            splice.clear_rendered();
+        }
+        self.prepare_rendering();
+    }
+
+    /// Prepare all splices for rendering, after they were added in bulk. See
+    /// [`Rendering::prepare`].
+    fn prepare_rendering(&mut self) {
+        for splice in self.splices.iter_mut() {
+            self.rendering.prepare(splice);
+        }
+    }
+
+    /// Replace the characters from index `start` up to (but not including) index `end` by the
+    /// string `value`, without escaping it.
+    ///
+    /// See [`Multisplice::set_escaper`].
+    pub fn splice_raw(
+        &mut self,
+        start: usize,
+        end: usize,
+        value: impl Into<Cow<'a, str>>,
+    ) -> SpliceId {
+        let splice = Splice {
+            raw: true,
+            ..Splice::new(self.next_id(), start..end, value.into())
+        };
+        self.insert_splice(splice)
+    }
+
+    /// Replace many ranges at once.
+    ///
+    /// This sorts the new splices once instead of doing a sorted insert for each of them, which is
+    /// much faster when queueing a large number of edits. All overlapping ranges are reported
+    /// together. If any conflicts are found, none of the new splices are applied. Splices that make
+    /// the exact same change as another splice are ignored. Ranges that start after they end, end
+    /// after the end of the original string or start or end inside a UTF-8 character are reported
+    /// as [`SpliceError::Inverted`], [`SpliceError::OutOfBounds`] and
+    /// [`SpliceError::NotCharBoundary`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SpliceError};
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice_many(vec![(6..7, "boop"), (2..3, "beep")]).unwrap();
+    /// assert_eq!(splicer.to_string(), "a beep c boop e");
+    ///
+    /// let errors = splicer.splice_many(vec![(0..3, "x"), (4..5, "y"), (6..9, "z")]).unwrap_err();
+    /// assert_eq!(errors, vec![
+    ///     SpliceError::Overlap { existing: 2..3, new: 0..3 },
+    ///     SpliceError::Overlap { existing: 6..7, new: 6..9 },
+    /// ]);
+    /// // Nothing was applied
+    /// assert_eq!(splicer.to_string(), "a beep c boop e");
+    ///
+    /// // Duplicates are not conflicts
+    /// splicer.splice_many(vec![(2..3, "beep"), (8..9, "!"), (8..9, "!")]).unwrap();
+    /// assert_eq!(splicer.to_string(), "a beep c boop !");
+    /// ```
+    ///
+    /// ```rust
+    /// use multisplice::{Multisplice, SpliceError};
+    ///
+    /// let mut splicer = Multisplice::new("aéb");
+    /// let errors = splicer.splice_many(vec![(2..3, "x"), (2..10, "y")]).unwrap_err();
+    /// assert_eq!(errors, vec![
+    ///     SpliceError::NotCharBoundary { new: 2..3, index: 2 },
+    ///     SpliceError::OutOfBounds { new: 2..10, len: 4 },
+    /// ]);
+    /// assert_eq!(splicer.to_string(), "aéb");
+    /// ```
+    pub fn splice_many<V>(
+        &mut self,
+        edits: impl IntoIterator<Item = (Range<usize>, V)>,
+    ) -> Result<(), Vec<SpliceError>>
+    where
+        V: Into<Cow<'a, str>>,
+    {
+        let new_splices = edits
+            .into_iter()
+            .map(|(range, value)| {
+                let range = self.clamp(range);
+                Splice::new(self.next_id(), range, value.into())
+            })
+            .collect::<Vec<_>>();
+        self.insert_many(new_splices)
+    }
+
+    /// Queue many splices at once, like [`Multisplice::splice_many`].
+    fn insert_many(&mut self, mut new_splices: Vec<Splice<'a>>) -> Result<(), Vec<SpliceError>> {
+        for splice in new_splices.iter_mut() {
+            if let (None, Some(group)) = (&splice.meta().group, &self.group) {
+                splice.meta_mut().group = Some(group.clone());
+            }
+            self.rendering.prepare(splice);
+        }
+        // Stable sort, so splices at the same position keep their order
+        new_splices.sort_by_key(Splice::order_key);
+        let invalid = new_splices
+            .iter()
+            .filter_map(|s| {
+                check_range(self.source, &s.range())
+                    .and_then(|()| self.check_protected(s))
+                    .err()
+            })
+            .collect::<Vec<_>>();
+        if !invalid.is_empty() {
+            return Err(invalid);
+        }
+
+        // Merge the existing splices with the new ones. On ties, existing splices go first, just
+        // like they would when inserting the new splices one by one.
+        let merged = merge_sorted(std::mem::take(&mut self.splices), new_splices);
+        let (duplicates, conflicts) = find_conflicts(&merged);
+        let errors = conflicts
+            .into_iter()
+            .map(|(existing, new)| SpliceError::Overlap {
+                existing: merged[existing].1.range(),
+                new: merged[new].1.range(),
+            })
+            .collect::<Vec<_>>();
+
+        let is_ok = errors.is_empty();
+        if is_ok {
+            self.output = OnceLock::new();
+        }
+        let mut added = vec![];
+        self.splices = remove_indices(merged, duplicates)
+            .filter(|(is_new, _)| is_ok || !is_new)
+            .map(|(is_new, s)| {
+                if is_new {
+                    added.push(s.id);
+                }
+                s
+            })
+            .collect();
+        if is_ok {
+            self.counters.queued(added.len());
+            self.begin_step();
+            for id in &added {
+                self.record(|_| Edit::Added(*id));
+            }
+            self.end_step();
+            if self.observer.is_some() {
+                added.sort();
+                for index in 0..self.splices.len() {
+                    if added.binary_search(&self.splices[index].id).is_ok() {
+                        self.notify(index);
+                    }
+                }
+            }
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Replace many ranges, applying every edit that is possible and reporting the others.
+    ///
+    /// Edits are tried in order, like with [`Multisplice::try_splice`], so an edit that overlaps
+    /// one that came before it in `edits` is rejected. The errors are returned together with the
+    /// position of the rejected edit in `edits`. Unlike [`Multisplice::splice_many`], the edits
+    /// that can be applied are kept even if others fail.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SpliceError};
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// let errors = splicer
+    ///     .splice_all(vec![(2..3, "beep"), (2..5, "boop"), (6..7, "boop"), (8..12, "!")])
+    ///     .unwrap_err();
+    /// assert_eq!(errors, vec![
+    ///     (1, SpliceError::Overlap { existing: 2..3, new: 2..5 }),
+    ///     (3, SpliceError::OutOfBounds { new: 8..12, len: 9 }),
+    /// ]);
+    /// assert_eq!(splicer.to_string(), "a beep c boop e");
+    /// ```
+    pub fn splice_all<V>(
+        &mut self,
+        edits: impl IntoIterator<Item = (Range<usize>, V)>,
+    ) -> Result<(), Vec<(usize, SpliceError)>>
+    where
+        V: Into<Cow<'a, str>>,
+    {
+        let mut errors = vec![];
+        self.begin_step();
+        for (index, (range, value)) in edits.into_iter().enumerate() {
+            if let Err(error) = self.try_splice(range.start, range.end, value) {
+                errors.push((index, error));
+            }
+        }
+        self.end_step();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Combine the splices from two splicers over the same source.
+    ///
+    /// If any splices from `self` and `other` overlap, all the conflicting pairs are returned,
+    /// including both sides' ranges and values. Splices that make the exact same change in both
+    /// splicers are only applied once.
+    ///
+    /// The [`SpliceId`]s of splices from `self` stay the same, but splices from `other` get new IDs.
+    /// Protected ranges from both splicers are kept, but only the anchors, markers and slots from
+    /// `self` are. The settings, history and [metrics](Multisplice::metrics) of `self` are kept
+    /// as well. The splices from `other` are not recorded in the history, so undoing does not
+    /// remove them.
+    ///
+    /// # Panics
+    /// Panics if `other` splices a different source string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let source = "a b c d e";
+    /// let mut first = Multisplice::new(source);
+    /// first.splice(2, 3, "beep");
+    /// let mut second = Multisplice::new(source);
+    /// second.splice(6, 7, "boop");
+    /// second.splice(2, 3, "beep");
+    /// let merged = first.merge(second).unwrap();
+    /// assert_eq!(merged.to_string(), "a beep c boop e");
+    ///
+    /// let mut first = Multisplice::new(source);
+    /// first.splice(2, 5, "beep");
+    /// let mut second = Multisplice::new(source);
+    /// second.splice(4, 7, "boop");
+    /// let error = first.merge(second).unwrap_err();
+    /// assert_eq!(error.conflicts[0].ours, (2..5, "beep".into()));
+    /// assert_eq!(error.conflicts[0].theirs, (4..7, "boop".into()));
+    /// ```
+    pub fn merge(self, other: Multisplice<'a>) -> Result<Multisplice<'a>, MergeConflict<'a>> {
+        assert!(
+            self.source == other.source,
+            "Trying to merge splicers with different sources"
+        );
+        let offset = self.next_id;
+        let mut protected = self.protected;
+        protected.extend(other.protected);
+        let mut disabled_groups = self.disabled_groups;
+        for group in other.disabled_groups {
+            if !disabled_groups.contains(&group) {
+                disabled_groups.push(group);
+            }
+        }
+        let theirs = other.splices.into_iter().map(|s| Splice {
+            id: SpliceId(offset + s.id.0),
+            ..s
+        });
+        let merged = merge_sorted(self.splices, theirs);
+        let (duplicates, conflicts) = find_conflicts(&merged);
+        if !conflicts.is_empty() {
+            let conflicts = conflicts
+                .into_iter()
+                .map(|(ours, theirs)| {
+                    let (ours, theirs) = (&merged[ours].1, &merged[theirs].1);
+                    Conflict {
+                        ours: (ours.range(), ours.value.clone()),
+                        theirs: (theirs.range(), theirs.value.clone()),
+                    }
+                })
+                .collect();
+            return Err(MergeConflict { conflicts });
+        }
+
+        let mut splicer = Multisplice {
+            source: self.source,
+            splices: remove_indices(merged, duplicates).map(|(_, s)| s).collect(),
+            next_id: offset + other.next_id,
+            protected,
+            anchors: self.anchors,
+            markers: self.markers,
+            slots: self.slots,
+            rendering: self.rendering,
+            group: self.group,
+            disabled_groups,
+            history: self.history,
+            observer: self.observer,
+            lenient: self.lenient,
+            counters: self.counters,
+            output: OnceLock::new(),
+            bom: self.bom,
+        };
+        splicer.prepare_rendering();
+        Ok(splicer)
+    }
+
+    /// Queue a second pass of splices on top of this one.
+    ///
+    /// The ranges in `edits` are indices into the output of `self`, instead of into the original
+    /// string. They are rebased onto the original string, so the output of the returned splicer
+    /// is the output of `self` with `edits` applied, without having to generate the intermediate
+    /// string. Where an edit touches a replacement value from `self`, that splice and the edit
+    /// are combined into a single splice, with a new [`SpliceId`].
+    ///
+    /// Where edits touch replacement values from `self`, the offsets count the rendered values,
+    /// after escaping, render hooks and lazy values. The combined splices are made from the
+    /// rendered text and the escaped edits, so they are not escaped again, but a render hook does
+    /// run on them again. A byte order mark kept by [`Multisplice::new_bom_aware`] is not part
+    /// of the output for the offsets.
+    ///
+    /// If any of the edits do not fit the output, or overlap each other, all the errors are
+    /// returned, in output coordinates. For [`SpliceError::OutOfBounds`], the length is the
+    /// length of the output.
+    ///
+    /// The returned splicer keeps the settings and [metrics](Multisplice::metrics) of `self`, but
+    /// starts without history, even if `self` has it enabled: the recorded changes refer to
+    /// splices that may have been combined. Call [`Multisplice::enable_history`] again to record
+    /// changes to the returned splicer.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(6, 7, "boop");
+    /// assert_eq!(splicer.to_string(), "a beep c boop e");
+    /// // Replace "p c b" and "e" in the output
+    /// let composed = splicer.compose(vec![(5..10, "-"), (14..15, "f")]).unwrap();
+    /// assert_eq!(composed.to_string(), "a bee-oop f");
+    /// assert_eq!(composed.slice(8, 9), "f");
+    /// ```
+    ///
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c");
+    /// splicer.enable_history();
+    /// splicer.splice(2, 3, "beep");
+    /// let mut composed = splicer.compose(vec![(0..1, "x")]).unwrap();
+    /// assert!(!composed.can_undo());
+    /// composed.enable_history();
+    /// composed.splice(4, 5, "!");
+    /// assert!(composed.undo());
+    /// assert_eq!(composed.to_string(), "x beep c");
+    /// ```
+    ///
+    /// ```rust
+    /// use multisplice::{Multisplice, SpliceError};
+    ///
+    /// let mut splicer = Multisplice::new("a b c");
+    /// splicer.splice_lazy(2, 3, || "beep".to_string());
+    /// assert_eq!(splicer.to_string(), "a beep c");
+    /// // Replace "ee" in the computed value
+    /// let composed = splicer.compose(vec![(3..5, "oo")]).unwrap();
+    /// assert_eq!(composed.to_string(), "a boop c");
+    ///
+    /// let mut splicer = Multisplice::new("a b c");
+    /// splicer.splice(2, 3, "beep");
+    /// let errors = splicer.compose(vec![(6..10, "!")]).unwrap_err();
+    /// assert_eq!(errors, vec![SpliceError::OutOfBounds { new: 6..10, len: 8 }]);
+    /// ```
+    pub fn compose<V>(
+        mut self,
+        edits: impl IntoIterator<Item = (Range<usize>, V)>,
+    ) -> Result<Multisplice<'a>, Vec<SpliceError>>
+    where
+        V: Into<Cow<'a, str>>,
+    {
+        let mut new_splices = edits
+            .into_iter()
+            .map(|(range, value)| Splice::new(self.next_id(), range, value.into()))
+            .collect::<Vec<_>>();
+        new_splices.sort_by_key(Splice::order_key);
+
+        // Edits must fit the output, and start and end on its character boundaries
+        let segments = self
+            .chunks(0, self.source.len())
+            .map(|chunk| self.chunk_str(&chunk))
+            .collect::<Vec<_>>();
+        let mut starts = Vec::with_capacity(segments.len());
+        let mut output_len = 0;
+        for segment in &segments {
+            starts.push(output_len);
+            output_len += segment.len();
+        }
+        let is_char_boundary = |index: usize| {
+            let segment = starts.partition_point(|start| *start <= index);
+            segment == 0 || segments[segment - 1].is_char_boundary(index - starts[segment - 1])
+        };
+        let invalid = new_splices
+            .iter()
+            .filter_map(|s| {
+                let new = s.range();
+                if new.start > new.end {
+                    Some(SpliceError::Inverted { new })
+                } else if new.end > output_len {
+                    Some(SpliceError::OutOfBounds {
+                        new,
+                        len: output_len,
+                    })
+                } else {
+                    let index = [new.start, new.end]
+                        .iter()
+                        .copied()
+                        .find(|index| !is_char_boundary(*index))?;
+                    Some(SpliceError::NotCharBoundary { new, index })
+                }
+            })
+            .collect::<Vec<_>>();
+        if !invalid.is_empty() {
+            return Err(invalid);
+        }
+
+        let merged = merge_sorted(None, new_splices);
+        let (duplicates, conflicts) = find_conflicts(&merged);
+        if !conflicts.is_empty() {
+            return Err(conflicts
+                .into_iter()
+                .map(|(existing, new)| SpliceError::Overlap {
+                    existing: merged[existing].1.range(),
+                    new: merged[new].1.range(),
+                })
+                .collect());
+        }
+        let edits = remove_indices(merged, duplicates).map(|(_, s)| s);
+
+        // Escape the edits now, because the splices they end up in are not escaped again
+        let escape = |value: Cow<'a, str>| match self.rendering.escaper {
+            Some(escape) => match escape(&value) {
+                Cow::Borrowed(escaped) if escaped == value => value,
+                escaped => Cow::Owned(escaped.into_owned()),
+            },
+            None => value,
+        };
+        let piece_len = |piece: &Piece<'a>| match piece {
+            Piece::Source(range) => range.len(),
+            Piece::Splice(index) => self.splices[*index].output(&self.rendering).len(),
+            Piece::Text(text) => text.len(),
+        };
+        let split_piece = |piece: Piece<'a>, at: usize| match piece {
+            Piece::Source(range) => (
+                Piece::Source(range.start..range.start + at),
+                Piece::Source(range.start + at..range.end),
+            ),
+            Piece::Splice(index) => {
+                let value = self.splices[index].output_cow(&self.rendering);
+                let len = value.len();
+                (
+                    Piece::Text(sub_cow(&value, 0..at)),
+                    Piece::Text(sub_cow(&value, at..len)),
+                )
+            }
+            Piece::Text(text) => (
+                Piece::Text(sub_cow(&text, 0..at)),
+                Piece::Text(sub_cow(&text, at..text.len())),
+            ),
+        };
+
+        // Apply the edits to the pieces of the current output
+        let mut pieces = self
+            .chunks(0, self.source.len())
+            .map(|chunk| match chunk {
+                Chunk::Source(range) => Piece::Source(range),
+                Chunk::Splice(index) => Piece::Splice(index),
+            })
+            .collect::<Vec<_>>()
+            .into_iter();
+        let mut output = vec![];
+        let mut current = pieces.next();
+        // Output index of the start of `current`
+        let mut pos = 0;
+        for edit in edits {
+            let Range { start, end } = edit.range();
+            // Keep everything before the edit
+            while let Some(piece) = current.take() {
+                let len = piece_len(&piece);
+                if pos + len <= start {
+                    pos += len;
+                    output.push(piece);
+                    current = pieces.next();
+                } else {
+                    if pos < start {
+                        let (left, right) = split_piece(piece, start - pos);
+                        output.push(left);
+                        pos = start;
+                        current = Some(right);
+                    } else {
+                        current = Some(piece);
+                    }
+                    break;
+                }
+            }
+            output.push(Piece::Text(escape(edit.value)));
+            // Remove everything inside the edit
+            while let Some(piece) = current.take() {
+                let len = piece_len(&piece);
+                let inside = if len == 0 {
+                    start < pos && pos < end
+                } else {
+                    pos + len <= end
+                };
+                if inside {
+                    pos += len;
+                    current = pieces.next();
+                } else {
+                    if pos < end && end < pos + len {
+                        let (_, right) = split_piece(piece, end - pos);
+                        pos = end;
+                        current = Some(right);
+                    } else {
+                        current = Some(piece);
+                    }
+                    break;
+                }
+            }
+        }
+        output.extend(current);
+        output.extend(pieces);
+
+        // Turn everything between the remaining original ranges back into splices
+        let mut next_id = self.next_id;
+        let mut originals = self.splices.into_iter().map(Some).collect::<Vec<_>>();
+        let mut splices = SpliceVec::new();
+        let mut gap = vec![];
+        let mut last = 0;
+        for piece in output {
+            match piece {
+                Piece::Source(range) if range.is_empty() => (),
+                Piece::Source(range) => {
+                    flush_gap(
+                        last..range.start,
+                        &mut gap,
+                        &mut originals,
+                        &self.rendering,
+                        &mut splices,
+                        &mut next_id,
+                    );
+                    last = range.end;
+                }
+                piece => gap.push(piece),
+            }
+        }
+        flush_gap(
+            last..self.source.len(),
+            &mut gap,
+            &mut originals,
+            &self.rendering,
+            &mut splices,
+            &mut next_id,
+        );
+
+        let mut splicer = Multisplice {
+            source: self.source,
+            splices,
+            next_id,
+            protected: self.protected,
+            anchors: self.anchors,
+            markers: self.markers,
+            slots: self.slots,
+            rendering: self.rendering,
+            group: self.group,
+            disabled_groups: self.disabled_groups,
+            history: None,
+            observer: self.observer,
+            lenient: self.lenient,
+            counters: self.counters,
+            output: OnceLock::new(),
+            bom: self.bom,
+        };
+        splicer.prepare_rendering();
+        Ok(splicer)
+    }
+
+    /// Insert `value` at index `index`, attached to the content to the left of it.
+    ///
+    /// All left insertions at the same index are output in the order they were queued, before
+    /// any right insertions at that index. A left insertion at the end of a slice is included in
+    /// the slice, while one at the start of a slice is not.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c");
+    /// splicer.insert_right(2, "[");
+    /// splicer.insert_left(3, "]");
+    /// splicer.insert_left(2, "1");
+    /// splicer.insert_left(2, "2");
+    /// assert_eq!(splicer.to_string(), "a 12[b] c");
+    /// assert_eq!(splicer.slice(0, 2), "a 12");
+    /// assert_eq!(splicer.slice(2, 3), "[b]");
+    /// ```
+    #[inline]
+    pub fn insert_left(&mut self, index: usize, value: impl Into<Cow<'a, str>>) -> SpliceId {
+        let splice = Splice {
+            side: Side::Left,
+            ..Splice::new(self.next_id(), index..index, value.into())
+        };
+        self.insert_splice(splice)
+    }
+    /// Insert `value` at index `index`, attached to the content to the right of it.
+    ///
+    /// All right insertions at the same index are output in the order they were queued, after
+    /// any left insertions at that index, and before the replacement of a splice starting at that
+    /// index. A right insertion at the start of a slice is included in the slice, while one at the
+    /// end of a slice is not. Empty splices queued through [`Multisplice::splice`] behave like
+    /// right insertions.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.insert_right(2, "1");
+    /// splicer.insert_right(2, "2");
+    /// splicer.insert_left(2, "0");
+    /// assert_eq!(splicer.to_string(), "a 012beep c");
+    /// assert_eq!(splicer.slice(0, 2), "a 0");
+    /// assert_eq!(splicer.slice(2, 3), "12beep");
+    /// ```
+    #[inline]
+    pub fn insert_right(&mut self, index: usize, value: impl Into<Cow<'a, str>>) -> SpliceId {
+        let splice = Splice::new(self.next_id(), index..index, value.into());
+        self.insert_splice(splice)
+    }
+
+    /// Replace the characters in the range `range` of the replacement value of a previously
+    /// queued splice by the string `value`.
+    ///
+    /// # Panics
+    /// Panics if there is no splice with the given ID. This can happen if the splice was combined
+    /// with other splices, for example by [`Multisplice::compose`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// let id = splicer.splice(2, 7, "beep and boop");
+    /// splicer.splice_in(id, 5..8, "or");
+    /// assert_eq!(splicer.to_string(), "a beep or boop e");
+    /// ```
+    pub fn splice_in(&mut self, id: SpliceId, range: impl RangeBounds<usize>, value: &str) {
+        self.output = OnceLock::new();
+        let index = self
+            .splices
+            .iter()
+            .position(|s| s.id == id)
+            .expect("Trying to splice a splice that does not exist");
+        self.record_value(index);
+        let splice = &mut self.splices[index];
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), splice.value.len());
+        splice.value_mut().to_mut().replace_range(start..end, value);
+    }
+
+    /// Move the characters in the range `range` of the original string to index `index`.
+    ///
+    /// The moved content is removed from its original position, and inserted at `index` as if by
+    /// [`Multisplice::insert_right`]. The inserted value borrows from the original string.
+    ///
+    /// # Panics
+    /// Panics if `range` was already spliced, or if `index` is inside a spliced range.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("let a = 1;\nuse b;\n");
+    /// splicer.move_range(11..18, 0);
+    /// assert_eq!(splicer.to_string(), "use b;\nlet a = 1;\n");
+    /// ```
+    pub fn move_range(&mut self, range: impl RangeBounds<usize>, index: usize) {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        self.splice_cow(start, end, Cow::Borrowed(""));
+        self.copy_range(start..end, index);
+    }
+
+    /// Insert a copy of the characters in the range `range` of the original string at index
+    /// `index`.
+    ///
+    /// The original content stays where it is. The copy is inserted as if by
+    /// [`Multisplice::insert_right`], and borrows from the original string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("/* MIT */\nfn a() {}\n");
+    /// splicer.copy_range(0..10, 20);
+    /// assert_eq!(splicer.to_string(), "/* MIT */\nfn a() {}\n/* MIT */\n");
+    /// ```
+    #[inline]
+    pub fn copy_range(&mut self, range: impl RangeBounds<usize>, index: usize) -> SpliceId {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        let mut splice = Splice::new(
+            self.next_id(),
+            index..index,
+            Cow::Borrowed(&self.source[start..end]),
+        );
+        splice.meta_mut().origin = Some(to_index(start));
+        self.insert_splice(splice)
+    }
+
+    /// Replace the characters in the range `range` of the original string by the characters in
+    /// the range `source_range` of the original string.
+    ///
+    /// Like [`Multisplice::copy_range`], the replacement borrows from the original string instead
+    /// of being copied, and source maps point it at `source_range`, so reordering and duplicating
+    /// code does not allocate or lose track of where it came from.
+    ///
+    /// # Panics
+    /// Panics if `range` was already spliced.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SourceMapOptions};
+    ///
+    /// let mut splicer = Multisplice::new("f(first, second)");
+    /// splicer.replace_with_source(2..7, 9..15);
+    /// splicer.replace_with_source(9..15, 2..7);
+    /// assert_eq!(splicer.to_string(), "f(second, first)");
+    /// let map = splicer.generate_map(&SourceMapOptions::new("input.txt"));
+    /// let columns = map
+    ///     .decoded_mappings()
+    ///     .unwrap()
+    ///     .into_iter()
+    ///     .map(|mapping| (mapping.generated_column, mapping.original.unwrap().column))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(columns, vec![(0, 0), (2, 9), (8, 7), (10, 2), (15, 15)]);
+    /// ```
+    pub fn replace_with_source(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        source_range: impl RangeBounds<usize>,
+    ) -> SpliceId {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        let source_start = get_start_bound(source_range.start_bound());
+        let source_end = get_end_bound(source_range.end_bound(), self.source.len());
+        let mut splice = Splice::new(
+            self.next_id(),
+            start..end,
+            Cow::Borrowed(&self.source[source_start..source_end]),
+        );
+        splice.meta_mut().origin = Some(to_index(source_start));
+        self.insert_splice(splice)
+    }
+
+    /// Add generated content to the start of the output.
+    ///
+    /// Banners go before everything else, in the order they were added. Unlike ordinary
+    /// insertions, they are not mapped to a position in the original string in source maps.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c");
+    /// splicer.insert_left(0, "(");
+    /// splicer.add_banner("/* generated */ ");
+    /// assert_eq!(splicer.to_string(), "/* generated */ (a b c");
+    /// ```
+    pub fn add_banner(&mut self, value: impl Into<Cow<'a, str>>) -> SpliceId {
+        let splice = Splice {
+            generated: true,
+            ..Splice::new(self.next_id(), 0..0, value.into())
+        };
+        self.insert_splice(splice)
+    }
+
+    /// Add generated content to the end of the output.
+    ///
+    /// Footers go after everything else, in the order they were added. Unlike ordinary
+    /// insertions, they are not mapped to a position in the original string in source maps.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c");
+    /// splicer.add_footer("\n//# sourceMappingURL=out.js.map");
+    /// splicer.insert_right(5, ")");
+    /// assert_eq!(splicer.to_string(), "a b c)\n//# sourceMappingURL=out.js.map");
+    /// ```
+    pub fn add_footer(&mut self, value: impl Into<Cow<'a, str>>) -> SpliceId {
+        let len = self.source.len();
+        let splice = Splice {
+            side: Side::Left,
+            generated: true,
+            ..Splice::new(self.next_id(), len..len, value.into())
+        };
+        self.insert_splice(splice)
+    }
+
+    /// Remove whitespace from the start and the end of the output.
+    ///
+    /// This works like [`str::trim`], but it modifies the splicer, taking both the original
+    /// string and replacement values into account.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("  a b c  ");
+    /// splicer.splice(0, 3, "\n");
+    /// splicer.insert_right(9, " ");
+    /// splicer.trim();
+    /// assert_eq!(splicer.to_string(), "b c");
+    /// ```
+    #[inline]
+    pub fn trim(&mut self) {
+        self.trim_start();
+        self.trim_end();
+    }
+
+    /// Remove whitespace from the start of the output.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("  a b c  ");
+    /// splicer.splice(0, 1, "\n");
+    /// splicer.trim_start();
+    /// assert_eq!(splicer.to_string(), "a b c  ");
+    /// ```
+    pub fn trim_start(&mut self) {
+        let mut trims = vec![];
+        for chunk in self.chunks(0, self.source.len()) {
+            let text = self.chunk_value(&chunk);
+            let trimmed = text.trim_start();
+            trims.push((chunk, text.len() - trimmed.len()));
+            if !trimmed.is_empty() {
+                break;
+            }
+        }
+        self.apply_trims(trims, false);
+    }
+
+    /// Remove whitespace from the end of the output.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("  a b c  ");
+    /// splicer.splice(8, 9, "\n");
+    /// splicer.trim_end();
+    /// assert_eq!(splicer.to_string(), "  a b c");
+    /// ```
+    pub fn trim_end(&mut self) {
+        let chunks = self.chunks(0, self.source.len()).collect::<Vec<_>>();
+        let mut trims = vec![];
+        for chunk in chunks.into_iter().rev() {
+            let text = self.chunk_value(&chunk);
+            let trimmed = text.trim_end();
+            trims.push((chunk, text.len() - trimmed.len()));
+            if !trimmed.is_empty() {
+                break;
+            }
+        }
+        self.apply_trims(trims, true);
+    }
+
+    /// Remove a number of bytes from the start or end of some pieces of the output.
+    fn apply_trims(&mut self, trims: Vec<(Chunk, usize)>, from_end: bool) {
+        self.output = OnceLock::new();
+        self.begin_step();
+        let mut removed = vec![];
+        // Trim replacement values first, so the indices in the chunks stay valid
+        for (chunk, len) in trims {
+            match chunk {
+                _ if len == 0 => (),
+                Chunk::Source(range) if from_end => removed.push(range.end - len..range.end),
+                Chunk::Source(range) => removed.push(range.start..range.start + len),
+                Chunk::Splice(index) => {
+                    self.record_value(index);
+                    let value = self.splices[index].value_mut();
+                    let keep = if from_end {
+                        0..value.len() - len
+                    } else {
+                        len..value.len()
+                    };
+                    *value = sub_cow(value, keep);
+                }
+            }
+        }
+        for range in removed {
+            self.splice_cow(range.start, range.end, Cow::Borrowed(""));
+        }
+        self.end_step();
+    }
+
+    /// Guess the indentation style of the original string.
+    ///
+    /// If more lines are indented with tabs than with spaces, this returns a tab. Otherwise, it
+    /// returns the smallest indentation using spaces. If no lines are indented, it returns a tab.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let splicer = Multisplice::new("fn a() {\n    if b {\n        c();\n    }\n}\n");
+    /// assert_eq!(splicer.guess_indent(), "    ");
+    /// ```
+    pub fn guess_indent(&self) -> &'a str {
+        let mut tabbed = 0;
+        let mut spaced: Option<&'a str> = None;
+        let mut spaced_count = 0;
+        for line in self.source.lines() {
+            if line.starts_with('\t') {
+                tabbed += 1;
+            } else if line.starts_with(' ') {
+                let spaces = &line[..line.len() - line.trim_start_matches(' ').len()];
+                if line[spaces.len()..].is_empty() {
+                    continue;
+                }
+                spaced_count += 1;
+                if spaced.is_none_or(|min| spaces.len() < min.len()) {
+                    spaced = Some(spaces);
+                }
+            }
+        }
+        match spaced {
+            Some(spaces) if spaced_count >= tabbed => spaces,
+            _ => "\t",
+        }
+    }
+
+    /// Prefix every line of the output with `prefix`. Empty lines are not indented.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a();\n\nb();\n");
+    /// splicer.splice(5, 5, "// comment\n");
+    /// splicer.indent(splicer.guess_indent());
+    /// assert_eq!(splicer.to_string(), "\ta();\n\t// comment\n\n\tb();\n");
+    /// ```
+    #[inline]
+    pub fn indent(&mut self, prefix: &'a str) {
+        self.indent_excluding(prefix, &[])
+    }
+
+    /// Prefix every line of the output with `prefix`, except for lines that start inside one of
+    /// the `exclude` ranges of the original string. Empty lines are not indented.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a(`\nb\n`);\nc();");
+    /// splicer.indent_excluding("  ", &[2..7]);
+    /// assert_eq!(splicer.to_string(), "  a(`\nb\n`);\n  c();");
+    /// ```
+    pub fn indent_excluding(&mut self, prefix: &'a str, exclude: &[Range<usize>]) {
+        let is_excluded = |index: usize| exclude.iter().any(|range| range.contains(&index));
+        let is_line_end = |c: u8| c == b'\n' || c == b'\r';
+
+        let mut insertions = vec![];
+        let mut values = vec![];
+        let mut at_line_start = true;
+        for chunk in self.chunks(0, self.source.len()) {
+            let text = self.chunk_value(&chunk);
+            let excluded = match chunk {
+                Chunk::Source(_) => false,
+                Chunk::Splice(index) => is_excluded(self.splices[index].start()),
+            };
+            let mut value = String::new();
+            let mut last = 0;
+            for (i, c) in text.bytes().enumerate() {
+                if at_line_start && !is_line_end(c) {
+                    match &chunk {
+                        Chunk::Source(range) if !is_excluded(range.start + i) => {
+                            insertions.push(range.start + i)
+                        }
+                        Chunk::Splice(_) if !excluded => {
+                            value.push_str(&text[last..i]);
+                            value.push_str(prefix);
+                            last = i;
+                        }
+                        _ => (),
+                    }
+                }
+                at_line_start = c == b'\n';
+            }
+            if let Chunk::Splice(index) = chunk {
+                if last > 0 || !value.is_empty() {
+                    value.push_str(&text[last..]);
+                    values.push((index, value));
+                }
+            }
+        }
+
+        // Update replacement values first, so the indices stay valid
+        self.output = OnceLock::new();
+        self.begin_step();
+        for (index, value) in values {
+            self.record_value(index);
+            *self.splices[index].value_mut() = Cow::Owned(value);
+        }
+        for index in insertions {
+            self.insert_right(index, prefix);
+        }
+        self.end_step();
+    }
+
+    /// Replace the characters in the range `range` by the string `value`, indenting the lines of
+    /// `value` to match the line of the original string that the range starts on.
+    ///
+    /// The first line of `value` is inserted as-is. The common indentation of the other lines is
+    /// replaced by the indentation of the line containing `range.start`. Lines that only contain
+    /// whitespace are left empty.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("fn a() {\n    b();\n}\n");
+    /// splicer.splice_indented(13..17, "if c {\n  b();\n\n}");
+    /// assert_eq!(splicer.to_string(), "fn a() {\n    if c {\n      b();\n\n    }\n}\n");
+    /// ```
+    pub fn splice_indented(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        value: impl Into<Cow<'a, str>>,
+    ) -> SpliceId {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        let value = value.into();
+        if !value.contains('\n') {
+            return self.splice_cow(start, end, value);
+        }
+
+        let line_start = self.source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line = &self.source[line_start..];
+        let indent = &line[..line.len() - line.trim_start_matches([' ', '\t']).len()];
+
+        let mut lines = value.split('\n');
+        let first = lines.next().unwrap_or_default();
+        let rest = lines.collect::<Vec<_>>();
+        let common = rest
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+            .min()
+            .unwrap_or(0);
+
+        let mut indented = first.to_string();
+        for line in rest {
+            indented.push('\n');
+            if !line.trim().is_empty() {
+                indented.push_str(indent);
+                indented.push_str(&line[common..]);
+            }
+        }
+        self.splice_cow(start, end, Cow::Owned(indented))
+    }
+
+    #[inline]
+    fn next_id(&mut self) -> SpliceId {
+        let id = SpliceId(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    fn splice_cow(&mut self, start: usize, end: usize, value: Cow<'a, str>) -> SpliceId {
+        let splice = Splice::new(self.next_id(), Range { start, end }, value);
+        self.insert_splice(splice)
+    }
+
+    /// Check that `splice` does not touch a protected range.
+    fn check_protected(&self, splice: &Splice<'_>) -> Result<(), SpliceError> {
+        match self.protected.iter().find(|range| splice.overlaps(range)) {
+            Some(range) => Err(SpliceError::Protected {
+                protected: range.clone(),
+                new: splice.range(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn insert_splice(&mut self, mut splice: Splice<'a>) -> SpliceId {
+        if let (None, Some(group)) = (&splice.meta().group, &self.group) {
+            splice.meta_mut().group = Some(group.clone());
+        }
+        if self.lenient {
+            let range = self.clamp(splice.range());
+            splice.start = to_index(range.start);
+            splice.end = to_index(range.end);
+        }
+        assert!(
+            splice.start <= splice.end,
+            "{}",
+            SpliceError::Inverted {
+                new: splice.range()
+            }
+        );
+        if let Err(err) = self.check_protected(&splice) {
+            panic!("{}", err);
+        }
+        self.rendering.prepare(&mut splice);
+        let start = splice.start();
+        let key = splice.order_key();
+
+        // Sorted insert
+        let mut insert_at = None;
+        for (i, s) in self.splices.iter().enumerate() {
+            if s.is_duplicate_of(&splice) {
+                return s.id;
+            }
+            let range = s.range();
+            // Insertions may go at the start of a spliced range, but not inside it
+            let inside = range.start <= start && range.end > start;
+            assert!(
+                !inside || (splice.is_insertion() && range.start == start),
+                "Trying to splice an already spliced range"
+            );
+            if s.order_key() > key {
+                insert_at = Some(i);
+                break;
+            }
+        }
+
+        let id = splice.id;
+        let index = insert_at.unwrap_or(self.splices.len());
+        self.splices.insert(index, splice);
+        self.counters.queued(1);
+        self.update_output(index);
+        self.record(|_| Edit::Added(id));
+        self.notify(index);
+        id
+    }
+
+    /// Call the observer for the newly queued splice at `index`.
+    #[inline]
+    fn notify(&mut self, index: usize) {
+        if let Some(observer) = &mut self.observer {
+            let splice = &self.splices[index];
+            observer(splice.range(), &splice.value);
+        }
+    }
+
+    /// Update the cached output after inserting the splice at `index`, instead of building it
+    /// again. The cache is discarded if the splice hides other splices, or is hidden itself.
+    ///
+    /// The position of the splice in the output is found from the changes in length of the
+    /// blocks before it and the splices in its own block, so this does not walk all the splices.
+    fn update_output(&mut self, index: usize) {
+        let mut output = match self.output.take() {
+            Some(output) => output,
+            None => return,
+        };
+        let splice = &self.splices[index];
+        if self.is_disabled(splice) {
+            self.output = OnceLock::from(output);
+            return;
+        }
+        let deltas = match &mut output.deltas {
+            Some(deltas) => deltas,
+            None => return,
+        };
+        let range = splice.range();
+        let next = self.splices.partition_point(|s| s.start() <= range.start);
+        let hides_others = self
+            .splices
+            .get(next)
+            .is_some_and(|s| s.start() < range.end);
+        let hidden = self.splices[..index]
+            .iter()
+            .rev()
+            .take_while(|s| s.start() == range.start)
+            .any(|s| !s.is_insertion() && !self.is_disabled(s));
+        if hides_others || hidden {
+            return;
+        }
+
+        let block = range.start / OUTPUT_BLOCK_LEN;
+        let first = self
+            .splices
+            .partition_point(|s| s.start() < block * OUTPUT_BLOCK_LEN);
+        let before = self.splices[first..index]
+            .iter()
+            .filter(|s| !self.is_disabled(s))
+            .map(|s| s.output(&self.rendering).len() as isize - s.range().len() as isize)
+            .sum::<isize>();
+        let position = (range.start as isize + deltas.before(block) + before) as usize;
+
+        let value = splice.output(&self.rendering);
+        self.counters.copied(value.len());
+        output
+            .text
+            .replace_range(position..position + range.len(), value);
+        deltas.add(range.start, value.len() as isize - range.len() as isize);
+        self.output = OnceLock::from(output);
+    }
+
+    /// Get a part of the spliced string, using indices `start` to `end` (exclusive) from the
+    /// original string.
+    /// If the `start` or `end` indices are in the middle of a spliced range, the full value of the
+    /// splice is included in the return value. For example, when indices 1-10 were replaced with a
+    /// value "Hello World", requesting a slice of indices 7-20 will return the entire "Hello
+    /// World" string followed by indices 11-20.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use multisplice::Multisplice;
+    /// use std::borrow::Cow;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(6, 7, "boop");
     /// assert_eq!(splicer.slice(2, 5), "beep c");
     /// // Does not allocate a new String if there were no changes
     /// assert_eq!(splicer.slice(3, 6), Cow::Borrowed(" c "));
+    /// // Or if the slice is entirely inside a splice with a borrowed value
+    /// assert!(matches!(splicer.slice(2, 3), Cow::Borrowed("beep")));
+    /// ```
+    ///
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 7, "beep and boop");
+    /// assert_eq!(splicer.to_string(), "a beep and boop e");
+    /// // Slicing in the middle of a spliced range:
+    /// assert_eq!(splicer.slice(0, 5), "a beep and boop");
+    /// assert_eq!(splicer.slice(6, 9), "beep and boop e");
+    /// ```
+    pub fn slice(&self, start: usize, end: usize) -> Cow<'a, str> {
+        assert!(end <= self.source.len());
+
+        let mut chunks = self.chunks(start, end);
+        let first = match chunks.next() {
+            Some(chunk) => chunk,
+            None => return Cow::Borrowed(""),
+        };
+        let second = chunks.next();
+        match (&first, &second) {
+            (Chunk::Source(range), None) => return Cow::Borrowed(&self.source[range.clone()]),
+            (Chunk::Splice(index), None) => {
+                return self.splices[*index].output_cow(&self.rendering)
+            }
+            _ => (),
+        }
+
+        if start == 0 && end == self.source.len() {
+            let output = self.cached_output();
+            self.counters.allocated();
+            self.counters.copied(output.len());
+            return Cow::Owned(output.to_string());
+        }
+        self.counters.timed(|| {
+            let mut result = String::with_capacity(self.output_len(start, end));
+            for chunk in std::iter::once(first).chain(second).chain(chunks) {
+                result.push_str(self.chunk_str(&chunk));
+            }
+            self.counters.allocated();
+            self.counters.copied(result.len());
+            result.into()
+        })
+    }
+
+    /// Get the full output. It is only built again if the splices changed since the last time.
+    fn cached_output(&self) -> &str {
+        &self
+            .output
+            .get_or_init(|| {
+                self.counters.timed(|| {
+                    let mut text = String::with_capacity(self.output_len(0, self.source.len()));
+                    let mut deltas = BlockDeltas::new(self.source.len());
+                    let mut rendered = 0;
+                    let mut last = 0;
+                    let mut overlapping = false;
+                    for chunk in self.chunks(0, self.source.len()) {
+                        let value = self.chunk_str(&chunk);
+                        if let Chunk::Splice(index) = chunk {
+                            let range = self.splices[index].range();
+                            deltas.add(range.start, value.len() as isize - range.len() as isize);
+                            overlapping |= range.start < last;
+                            last = range.end;
+                            rendered += 1;
+                        }
+                        text.push_str(value);
+                    }
+                    self.counters.allocated();
+                    self.counters.copied(text.len());
+                    let hidden =
+                        self.splices.iter().filter(|s| !self.is_disabled(s)).count() != rendered;
+                    CachedOutput {
+                        text,
+                        deltas: if hidden || overlapping {
+                            None
+                        } else {
+                            Some(deltas)
+                        },
+                    }
+                })
+            })
+            .text
+    }
+
+    /// Execute the splices, returning the new string.
+    ///
+    /// If no splices change the output, this borrows the original string instead of allocating.
+    /// An output that consists of a single borrowed replacement value is borrowed as well, so a
+    /// borrowed result does not mean that nothing changed: compare it to
+    /// [`source`](Multisplice::source) for that. The output is cached until the next change, so
+    /// rendering again without making changes does not walk the splices again. Queueing a single
+    /// splice updates the cached output in place, instead of building it again.
+    ///
+    /// Slots reserved with [`Multisplice::slot`] that were not filled render as empty strings.
+    /// Use [`Multisplice::try_render`] to get an error for them instead.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    /// use std::borrow::Cow;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// assert_eq!(splicer.render(), Cow::Borrowed("a b c d e"));
+    /// splicer.splice(2, 3, "beep");
+    /// assert_eq!(splicer.render(), "a beep c d e");
+    /// ```
+    ///
+    /// ```rust
+    /// use multisplice::Multisplice;
+    /// use std::borrow::Cow;
+    ///
+    /// let mut splicer = Multisplice::new("abc");
+    /// splicer.splice(0, 3, "xyz");
+    /// let output = splicer.render();
+    /// // The output borrows the replacement value, but it did change
+    /// assert!(matches!(output, Cow::Borrowed("xyz")));
+    /// assert_ne!(output, splicer.source());
+    /// ```
+    #[inline]
+    pub fn render(&self) -> Cow<'a, str> {
+        let output = self.slice(0, self.source.len());
+        match self.bom {
+            Some(bom) => Cow::Owned(format!("{}{}", bom, output)),
+            None => output,
+        }
+    }
+
+    /// Execute the splices, consuming the splicer and returning the new string.
+    ///
+    /// Unlike `to_string()`, this reuses the buffer of an owned replacement value at the start of
+    /// the output, and allocates the result at its final size otherwise.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(0, 1, String::from("beep"));
+    /// splicer.splice(6, 7, "boop");
+    /// assert_eq!(splicer.into_string(), "beep b c boop e");
+    /// ```
+    pub fn into_string(mut self) -> String {
+        let mut result = self.take_output();
+        if let Some(bom) = self.bom {
+            result.insert_str(0, bom);
+        }
+        result
+    }
+
+    /// Build the output without the byte order mark, reusing the cached output or the buffer of
+    /// an owned replacement value at the start of the output.
+    fn take_output(&mut self) -> String {
+        if let Some(output) = self.output.take() {
+            return output.text;
+        }
+        let len = self.rendered_len();
+
+        let mut result = match self.chunks(0, self.source.len()).next() {
+            Some(Chunk::Splice(index)) if !self.rendering.applies_to(&self.splices[index]) => {
+                match &mut self.splices[index].value {
+                    Cow::Owned(value) => std::mem::take(value),
+                    Cow::Borrowed(_) => String::new(),
+                }
+            }
+            _ => String::new(),
+        };
+        result.reserve_exact(len - result.len());
+        for chunk in self.chunks(0, self.source.len()) {
+            result.push_str(self.chunk_str(&chunk));
+        }
+        result
+    }
+
+    /// Execute the splices, appending the new string to `buffer`.
+    ///
+    /// The existing contents of `buffer` are kept. It only allocates if `buffer` does not have
+    /// enough spare capacity for the output, so a buffer can be reused for many renders.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut buffer = String::with_capacity(64);
+    /// for name in ["beep", "boop"].iter().copied() {
+    ///     let mut splicer = Multisplice::new("hello {name}!\n");
+    ///     splicer.splice(6, 12, name);
+    ///     splicer.render_into(&mut buffer);
+    /// }
+    /// assert_eq!(buffer, "hello beep!\nhello boop!\n");
+    /// assert_eq!(buffer.capacity(), 64);
+    /// ```
+    pub fn render_into(&self, buffer: &mut String) {
+        if let Some(bom) = self.bom {
+            buffer.push_str(bom);
+        }
+        self.counters.timed(|| {
+            let len = self.output_len(0, self.source.len());
+            if buffer.capacity() - buffer.len() < len {
+                self.counters.allocated();
+            }
+            self.counters.copied(len);
+            if let Some(output) = self.output.get() {
+                buffer.push_str(&output.text);
+                return;
+            }
+            buffer.reserve(len);
+            for chunk in self.chunks(0, self.source.len()) {
+                buffer.push_str(self.chunk_str(&chunk));
+            }
+        })
+    }
+
+    /// Execute the splices in place on `target`, a copy of the original string.
+    ///
+    /// The splices are applied back to front with [`String::replace_range`], so no second
+    /// buffer the size of the output is needed. This is slower than rendering a new string when
+    /// there are many splices, because the content after each splice is moved, but uses about
+    /// half the memory for very large strings with few changes.
+    ///
+    /// # Panics
+    /// Panics if `target` does not have the same length as the original string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut document = "a b c d e".to_string();
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.insert_left(5, "!");
+    /// splicer.splice(6, 9, "");
+    /// splicer.apply_to(&mut document);
+    /// assert_eq!(document, "a beep c! ");
+    /// ```
+    pub fn apply_to(&self, target: &mut String) {
+        assert_eq!(
+            target.len(),
+            self.source.len(),
+            "Trying to apply splices to a string that is not a copy of the original"
+        );
+        self.counters.timed(|| {
+            let splices = self
+                .chunks(0, self.source.len())
+                .filter_map(|chunk| match chunk {
+                    Chunk::Source(_) => None,
+                    Chunk::Splice(index) => Some(&self.splices[index]),
+                })
+                .collect::<Vec<_>>();
+            for splice in splices.into_iter().rev() {
+                let value = splice.output(&self.rendering);
+                self.counters.copied(value.len());
+                target.replace_range(splice.range(), value);
+            }
+        })
+    }
+
+    /// Get the changes in length made by the splices, in the order they are applied.
+    ///
+    /// Each item is the range of the original string that was replaced, the length of its
+    /// replacement, and the difference between the two. Positions into the original string can be
+    /// moved to the output by adding the deltas of all the splices before them. Splices that are
+    /// hidden by an earlier splice covering their range are not included.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(6, 9, "boop");
+    /// assert_eq!(splicer.deltas(), vec![(2..3, 4, 3), (6..9, 4, 1)]);
     /// ```
+    pub fn deltas(&self) -> Vec<(Range<usize>, usize, isize)> {
+        self.chunks(0, self.source.len())
+            .filter_map(|chunk| match chunk {
+                Chunk::Splice(index) => {
+                    let s = &self.splices[index];
+                    let new_len = s.output(&self.rendering).len();
+                    Some((
+                        s.range(),
+                        new_len,
+                        new_len as isize - s.range().len() as isize,
+                    ))
+                }
+                Chunk::Source(_) => None,
+            })
+            .collect()
+    }
+
+    /// Check if this splicer queues the exact same edits as `other`: the same ranges, replaced by
+    /// the same values, in the same order.
+    ///
+    /// Unlike comparing the output, this tells apart different edits that happen to produce the
+    /// same string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut a = Multisplice::new("a b c");
+    /// a.splice(2, 3, "beep");
+    /// let mut b = Multisplice::new("a b c");
+    /// b.splice(2, 3, "beep");
+    /// assert!(a.plan_eq(&b));
+    ///
+    /// let mut c = Multisplice::new("a b c");
+    /// c.splice(2, 3, "");
+    /// c.insert_left(2, "beep");
+    /// assert_eq!(a.to_string(), c.to_string());
+    /// assert!(!a.plan_eq(&c));
+    /// ```
+    pub fn plan_eq(&self, other: &Multisplice<'_>) -> bool {
+        self.splices.len() == other.splices.len()
+            && self
+                .splices
+                .iter()
+                .zip(other.splices.iter())
+                .all(|(a, b)| a.start == b.start && a.end == b.end && a.value == b.value)
+    }
+
+    /// Hash the queued edits: their ranges and replacement values, in order.
+    ///
+    /// Splicers that are equal according to [`Multisplice::plan_eq`] have the same hash.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::Hasher;
+    ///
+    /// fn plan_hash(splicer: &Multisplice<'_>) -> u64 {
+    ///     let mut hasher = DefaultHasher::new();
+    ///     splicer.plan_hash(&mut hasher);
+    ///     hasher.finish()
+    /// }
+    ///
+    /// let mut a = Multisplice::new("a b c");
+    /// a.splice(2, 3, "beep");
+    /// let mut b = Multisplice::new("a b c");
+    /// b.splice(2, 3, "beep");
+    /// assert_eq!(plan_hash(&a), plan_hash(&b));
+    /// ```
+    pub fn plan_hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.splices.len());
+        for splice in self.splices.iter() {
+            splice.range().hash(state);
+            splice.value.hash(state);
+        }
+    }
+
+    /// Iterate over the pieces of text that make up the output, without building the new string.
     ///
+    /// Each piece is either a part of the original string or a replacement value. Empty pieces are
+    /// skipped. A byte order mark kept by [`Multisplice::new_bom_aware`] is the first piece.
+    ///
+    /// # Example
     /// ```rust
     /// use multisplice::Multisplice;
     ///
     /// let mut splicer = Multisplice::new("a b c d e");
-    /// splicer.splice(2, 7, "beep and boop");
-    /// assert_eq!(splicer.to_string(), "a beep and boop e");
-    /// // Slicing in the middle of a spliced range:
-    /// assert_eq!(splicer.slice(0, 5), "a beep and boop");
-    /// assert_eq!(splicer.slice(6, 9), "beep and boop e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(6, 7, "boop");
+    /// let segments = splicer.segments().collect::<Vec<_>>();
+    /// assert_eq!(segments, vec!["a ", "beep", " c ", "boop", " e"]);
     /// ```
-    pub fn slice(&self, start: usize, end: usize) -> Cow<'a, str> {
+    #[inline]
+    pub fn segments(&self) -> Segments<'_, 'a> {
+        Segments {
+            bom: self.bom,
+            chunks: self.chunks(0, self.source.len()),
+            remaining: None,
+        }
+    }
+
+    /// Iterate over the pieces of text that make up the output for the range `range` of the
+    /// original string, without building the new string.
+    ///
+    /// The pieces are the ones that [`Multisplice::slice_range`] would join together, so splices
+    /// that overlap the range are included entirely. Empty pieces are skipped.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(6, 7, "boop");
+    /// let segments = splicer.segments_in(3..7).collect::<Vec<_>>();
+    /// assert_eq!(segments, vec![" c ", "boop"]);
+    /// let segments = splicer.segments_in(5..).collect::<Vec<_>>();
+    /// assert_eq!(segments, vec![" ", "boop", " e"]);
+    /// ```
+    #[inline]
+    pub fn segments_in(&self, range: impl RangeBounds<usize>) -> Segments<'_, 'a> {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
         assert!(end <= self.source.len());
+        Segments {
+            bom: None,
+            chunks: self.chunks(start, end),
+            remaining: None,
+        }
+    }
 
-        let mut result = String::new();
-        let mut last = start;
-        for s in &self.splices {
-            let range = &s.range;
-            // ignore splices that are entirely contained in an earlier spliced range
-            if range.end <= last {
-                continue;
-            }
-            // ignore splices after the end of the source
-            if range.start >= end {
-                break;
-            }
-            if range.start >= last {
-                result.push_str(&self.source[last..range.start]);
-            }
-            result.push_str(&s.value);
-            last = range.end;
+    /// Iterate over the pieces of text that make up the output, together with where they came
+    /// from. Empty pieces are skipped.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, Provenance};
+    ///
+    /// let mut splicer = Multisplice::new("a b c");
+    /// let id = splicer.splice(2, 3, "beep");
+    /// let segments = splicer.segments_with_spans().collect::<Vec<_>>();
+    /// assert_eq!(segments, vec![
+    ///     ("a ", Provenance::Source(0..2)),
+    ///     ("beep", Provenance::Replacement { id, original_range: 2..3 }),
+    ///     (" c", Provenance::Source(3..5)),
+    /// ]);
+    /// ```
+    #[inline]
+    pub fn segments_with_spans(&self) -> SegmentsWithSpans<'_, 'a> {
+        SegmentsWithSpans {
+            chunks: self.chunks(0, self.source.len()),
         }
-        // If our slice ends in the middle of a spliced range, we don't need to add any more of the
-        // original string because it's been spliced away
-        if end >= last {
-            if result.is_empty() {
-                return Cow::Borrowed(&self.source[last..end]);
-            }
-            result.push_str(&self.source[last..end]);
+    }
+
+    /// Iterate over the pieces that make up the output for indices `start` to `end` (exclusive)
+    /// from the original string.
+    #[inline]
+    fn chunks(&self, start: usize, end: usize) -> Chunks<'_, 'a> {
+        Chunks {
+            splicer: self,
+            start,
+            end,
+            last: start,
+            index: 0,
+            pending: None,
+            filter: None,
+        }
+    }
+
+    /// Like `chunks`, but only with the splices for which `filter` returns true.
+    fn chunks_where<'s>(
+        &'s self,
+        start: usize,
+        end: usize,
+        filter: &'s SpliceFilter<'s, 'a>,
+    ) -> Chunks<'s, 'a> {
+        Chunks {
+            filter: Some(filter),
+            ..self.chunks(start, end)
+        }
+    }
+
+    /// Build the output for indices `start` to `end` (exclusive) from the original string, with
+    /// only the splices for which `filter` returns true.
+    fn slice_where(&self, start: usize, end: usize, filter: &SpliceFilter<'_, 'a>) -> String {
+        assert!(end <= self.source.len());
+        self.chunks_where(start, end, filter)
+            .map(|chunk| self.chunk_str(&chunk))
+            .collect()
+    }
+
+    /// Get the length of the output for indices `start` to `end` (exclusive) from the original
+    /// string, without building it.
+    fn output_len(&self, start: usize, end: usize) -> usize {
+        self.chunks(start, end)
+            .map(|chunk| self.chunk_str(&chunk).len())
+            .sum()
+    }
+
+    /// Get the length of the full output, including the byte order mark.
+    #[inline]
+    fn rendered_len(&self) -> usize {
+        self.bom.map_or(0, str::len) + self.output_len(0, self.source.len())
+    }
+
+    /// Get the output text of a chunk.
+    #[inline]
+    fn chunk_str(&self, chunk: &Chunk) -> &str {
+        match chunk {
+            Chunk::Source(range) => &self.source[range.clone()],
+            Chunk::Splice(index) => self.splices[*index].output(&self.rendering),
         }
+    }
+
+    /// Check if any of the rendered splices change the text they replace. The byte order mark
+    /// kept by [`Multisplice::new_bom_aware`] does not count as a change.
+    pub(crate) fn has_changes(&self) -> bool {
+        self.chunks(0, self.source.len()).any(|chunk| match chunk {
+            Chunk::Source(_) => false,
+            Chunk::Splice(index) => {
+                let splice = &self.splices[index];
+                splice.output(&self.rendering) != &self.source[splice.range()]
+            }
+        })
+    }
 
-        result.into()
+    /// Get the text of a chunk before escaping.
+    #[inline]
+    fn chunk_value(&self, chunk: &Chunk) -> &str {
+        match chunk {
+            Chunk::Source(range) => &self.source[range.clone()],
+            Chunk::Splice(index) => &self.splices[*index].value,
+        }
     }
 
     /// Slice using range syntax.
@@ -231,10 +3315,573 @@ impl<'a> Multisplice<'a> {
     }
 }
 
-impl ToString for Multisplice<'_> {
-    /// Execute the splices, returning the new string.
+/// A piece of the output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Chunk {
+    /// An unchanged range of the original string.
+    Source(Range<usize>),
+    /// The value of the splice at this index.
+    Splice(usize),
+}
+
+/// A piece of the output while composing splicers.
+#[derive(Debug)]
+enum Piece<'a> {
+    /// An unchanged range of the original string.
+    Source(Range<usize>),
+    /// The unchanged value of the original splice at this index.
+    Splice(usize),
+    /// New or partial replacement text.
+    Text(Cow<'a, str>),
+}
+
+/// Get a part of a string, borrowing if possible.
+fn sub_cow<'a>(value: &Cow<'a, str>, range: Range<usize>) -> Cow<'a, str> {
+    match value {
+        Cow::Borrowed(value) => Cow::Borrowed(&value[range]),
+        Cow::Owned(value) => Cow::Owned(value[range].to_string()),
+    }
+}
+
+/// Turn the pieces between two unchanged ranges of the original string into splices.
+///
+/// If the pieces are exactly the original splices for that range, they are kept as they were.
+/// Otherwise, they are combined into a single splice, made from the rendered text of the pieces.
+fn flush_gap<'a>(
+    range: Range<usize>,
+    gap: &mut Vec<Piece<'a>>,
+    originals: &mut [Option<Splice<'a>>],
+    rendering: &Rendering<'a>,
+    splices: &mut SpliceVec<'a>,
+    next_id: &mut usize,
+) {
+    let mut new_splice = |range, value| {
+        let id = SpliceId(*next_id);
+        *next_id += 1;
+        Splice {
+            raw: true,
+            ..Splice::new(id, range, value)
+        }
+    };
+
+    if gap.is_empty() {
+        if !range.is_empty() {
+            splices.push(new_splice(range, Cow::Borrowed("")));
+        }
+        return;
+    }
+
+    let mut at = range.start;
+    let unchanged = gap.iter().all(|piece| match piece {
+        Piece::Splice(index) => {
+            let original = originals[*index].as_ref().map(|s| s.range());
+            match original {
+                Some(original) if original.start == at => {
+                    at = original.end;
+                    true
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }) && at == range.end;
+
+    let mut take_original = |index: usize| originals[index].take().expect("splice used twice");
+    if unchanged {
+        splices.extend(gap.drain(..).map(|piece| match piece {
+            Piece::Splice(index) => take_original(index),
+            _ => unreachable!("only original splices are unchanged"),
+        }));
+        return;
+    }
+
+    let mut values = gap.drain(..).map(|piece| match piece {
+        Piece::Splice(index) => take_original(index).output_cow(rendering),
+        Piece::Text(text) => text,
+        Piece::Source(_) => unreachable!("source ranges are not part of gaps"),
+    });
+    let first = values.next().unwrap_or_default();
+    let value = match values.next() {
+        None => first,
+        Some(second) => {
+            let mut value = first.into_owned();
+            value.push_str(&second);
+            values.for_each(|text| value.push_str(&text));
+            Cow::Owned(value)
+        }
+    };
+    splices.push(new_splice(range, value));
+}
+
+/// Iterator over the pieces that make up (a part of) the output.
+struct Chunks<'s, 'a> {
+    splicer: &'s Multisplice<'a>,
+    /// The start index of the slice.
+    start: usize,
+    /// The end index of the slice.
+    end: usize,
+    /// The index up to which the original string has been output.
+    last: usize,
+    /// The index of the next splice to look at.
+    index: usize,
+    /// A splice that directly follows the last returned chunk.
+    pending: Option<Chunk>,
+    /// If set, splices for which this returns false are skipped, as if they were not queued.
+    filter: Option<&'s SpliceFilter<'s, 'a>>,
+}
+
+/// Selects splices to include in the output.
+type SpliceFilter<'s, 'a> = dyn Fn(&Splice<'a>) -> bool + 's;
+
+impl Iterator for Chunks<'_, '_> {
+    type Item = Chunk;
+
+    fn next(&mut self) -> Option<Chunk> {
+        if let Some(chunk) = self.pending.take() {
+            return Some(chunk);
+        }
+
+        let splices = &self.splicer.splices;
+        while let Some(s) = splices.get(self.index) {
+            let index = self.index;
+            self.index += 1;
+            if self.splicer.is_disabled(s) || self.filter.is_some_and(|filter| !filter(s)) {
+                continue;
+            }
+
+            let range = s.range();
+            if s.is_insertion() {
+                // insertions at the edges of the slice only count if they are attached to the
+                // inside of the slice, unless they are at the edge of the source
+                if range.start < self.start
+                    || (range.start == self.start && s.side == Side::Left && self.start != 0)
+                {
+                    continue;
+                }
+                if range.start > self.end
+                    || (range.start == self.end
+                        && s.side == Side::Right
+                        && self.end != self.splicer.source.len())
+                {
+                    self.index = splices.len();
+                    break;
+                }
+                // ignore insertions inside an earlier spliced range
+                if range.start < self.last {
+                    continue;
+                }
+            } else {
+                // ignore splices that are entirely contained in an earlier spliced range
+                if range.end <= self.last {
+                    continue;
+                }
+                // ignore splices after the end of the source
+                if range.start >= self.end {
+                    self.index = splices.len();
+                    break;
+                }
+            }
+
+            let last = std::mem::replace(&mut self.last, range.end);
+            if range.start > last {
+                self.pending = Some(Chunk::Splice(index));
+                return Some(Chunk::Source(last..range.start));
+            }
+            return Some(Chunk::Splice(index));
+        }
+
+        // If our slice ends in the middle of a spliced range, we don't need to add any more of the
+        // original string because it's been spliced away
+        if self.end > self.last {
+            let last = std::mem::replace(&mut self.last, self.end);
+            return Some(Chunk::Source(last..self.end));
+        }
+        None
+    }
+}
+
+impl<'a> From<&'a str> for Multisplice<'a> {
+    /// Create a splicer for a string, like [`Multisplice::new`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// fn shout<'a>(source: impl Into<Multisplice<'a>>) -> String {
+    ///     let mut splicer = source.into();
+    ///     splicer.insert_left(splicer.source().len(), "!");
+    ///     splicer.to_string()
+    /// }
+    ///
+    /// assert_eq!(shout("a b c"), "a b c!");
+    /// assert_eq!(shout(&String::from("a b c")), "a b c!");
+    /// ```
+    #[inline]
+    fn from(source: &'a str) -> Self {
+        Multisplice::new(source)
+    }
+}
+
+impl<'a> From<&'a String> for Multisplice<'a> {
+    /// Create a splicer for a string, like [`Multisplice::new`].
+    ///
+    /// A `Multisplice` borrows its original string, so there is no conversion from an owned
+    /// `String`. [`SharedMultisplice`] is the splicer that owns its original string, and it does
+    /// implement `From<String>`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::SharedMultisplice;
+    ///
+    /// let mut splicer = SharedMultisplice::from(String::from("a b c"));
+    /// splicer.splice(2..3, "beep");
+    /// assert_eq!(splicer.render().unwrap(), "a beep c");
+    /// ```
+    #[inline]
+    fn from(source: &'a String) -> Self {
+        Multisplice::new(source)
+    }
+}
+
+impl<'a, V> Extend<(Range<usize>, V)> for Multisplice<'a>
+where
+    V: Into<Cow<'a, str>>,
+{
+    /// Queue all the splices from an iterator.
+    ///
+    /// # Panics
+    /// Panics if any of the ranges overlap a range that was already spliced. Use
+    /// [`Multisplice::splice_many`] to handle conflicts instead.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    /// use std::borrow::Cow;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// let pass_one = vec![(2..3, Cow::Borrowed("beep"))];
+    /// let pass_two = vec![(6..7, Cow::Owned("boop".to_string()))];
+    /// splicer.extend(pass_one.into_iter().chain(pass_two));
+    /// assert_eq!(splicer.to_string(), "a beep c boop e");
+    /// ```
+    fn extend<I: IntoIterator<Item = (Range<usize>, V)>>(&mut self, iter: I) {
+        if let Err(errors) = self.splice_many(iter) {
+            panic!("{}", errors[0]);
+        }
+    }
+}
+
+impl<'a> IntoIterator for Multisplice<'a> {
+    type Item = (Range<usize>, Cow<'a, str>);
+    type IntoIter = IntoIter<'a>;
+
+    /// Consume the splicer, returning the queued splices in order.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(6, 7, "boop".to_string());
+    /// splicer.splice(2, 3, "beep");
+    /// let edits = splicer.into_iter().collect::<Vec<_>>();
+    /// assert_eq!(edits, vec![(2..3, "beep".into()), (6..7, "boop".into())]);
+    /// ```
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.splices.into_iter(),
+        }
+    }
+}
+
+/// A consuming iterator over the splices in a [`Multisplice`].
+///
+/// Returned by [`Multisplice::into_iter`].
+#[derive(Debug)]
+pub struct IntoIter<'a> {
+    inner: <SpliceVec<'a> as IntoIterator>::IntoIter,
+}
+
+impl<'a> Iterator for IntoIter<'a> {
+    type Item = (Range<usize>, Cow<'a, str>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|s| {
+            let range = s.range();
+            let lazy = s.meta.and_then(|meta| meta.lazy);
+            let value = match lazy {
+                Some(lazy) => Cow::Owned(lazy.0.compute()),
+                None => s.value,
+            };
+            (range, value)
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl ExactSizeIterator for IntoIter<'_> {}
+
+/// An iterator over the pieces of text that make up the output of a [`Multisplice`].
+///
+/// Returned by [`Multisplice::segments`] and [`Multisplice::segments_in`].
+///
+/// Iterating from the back first walks the remaining splices to find where the segments are, but
+/// does not copy any text.
+pub struct Segments<'s, 'a> {
+    /// The byte order mark, until it is returned.
+    bom: Option<&'a str>,
+    chunks: Chunks<'s, 'a>,
+    /// The remaining chunks, once iteration from the back has started.
+    remaining: Option<VecDeque<Chunk>>,
+}
+
+impl<'s> Segments<'s, '_> {
+    #[inline]
+    fn text(&self, chunk: Chunk) -> &'s str {
+        let splicer = self.chunks.splicer;
+        match chunk {
+            Chunk::Source(range) => &splicer.source[range],
+            Chunk::Splice(index) => splicer.splices[index].output(&splicer.rendering),
+        }
+    }
+}
+
+impl<'s> Iterator for Segments<'s, '_> {
+    type Item = &'s str;
+
+    fn next(&mut self) -> Option<&'s str> {
+        if let Some(bom) = self.bom.take() {
+            return Some(bom);
+        }
+        loop {
+            let chunk = match &mut self.remaining {
+                Some(remaining) => remaining.pop_front()?,
+                None => self.chunks.next()?,
+            };
+            let text = self.text(chunk);
+            if !text.is_empty() {
+                return Some(text);
+            }
+        }
+    }
+}
+
+impl DoubleEndedIterator for Segments<'_, '_> {
+    /// Get the last remaining piece of the output.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(6, 7, "boop");
+    /// let mut segments = splicer.segments();
+    /// assert_eq!(segments.next_back(), Some(" e"));
+    /// assert_eq!(segments.next(), Some("a "));
+    /// assert_eq!(segments.rev().collect::<Vec<_>>(), vec!["boop", " c ", "beep"]);
+    /// ```
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining.is_none() {
+            self.remaining = Some(self.chunks.by_ref().collect());
+        }
+        while let Some(chunk) = self.remaining.as_mut()?.pop_back() {
+            let text = self.text(chunk);
+            if !text.is_empty() {
+                return Some(text);
+            }
+        }
+        self.bom.take()
+    }
+}
+
+impl fmt::Debug for Segments<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Segments").finish_non_exhaustive()
+    }
+}
+
+/// Where a piece of the output came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Provenance {
+    /// An unchanged range of the original string.
+    Source(Range<usize>),
+    /// The replacement value of a splice.
+    Replacement {
+        /// The splice that the value belongs to.
+        id: SpliceId,
+        /// The range of the original string that the splice replaced. Empty for insertions.
+        original_range: Range<usize>,
+    },
+}
+
+/// An iterator over the pieces of text that make up the output of a [`Multisplice`], together
+/// with where they came from.
+///
+/// Returned by [`Multisplice::segments_with_spans`].
+pub struct SegmentsWithSpans<'s, 'a> {
+    chunks: Chunks<'s, 'a>,
+}
+
+impl<'s> Iterator for SegmentsWithSpans<'s, '_> {
+    type Item = (&'s str, Provenance);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let splicer = self.chunks.splicer;
+        self.chunks
+            .by_ref()
+            .map(|chunk| match chunk {
+                Chunk::Source(range) => (&splicer.source[range.clone()], Provenance::Source(range)),
+                Chunk::Splice(index) => {
+                    let s = &splicer.splices[index];
+                    let provenance = Provenance::Replacement {
+                        id: s.id,
+                        original_range: s.range(),
+                    };
+                    (s.output(&splicer.rendering), provenance)
+                }
+            })
+            .find(|(text, _)| !text.is_empty())
+    }
+}
+
+impl fmt::Debug for SegmentsWithSpans<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SegmentsWithSpans").finish_non_exhaustive()
+    }
+}
+
+/// Compares the outputs, one segment at a time, without building the new strings.
+///
+/// # Example
+/// ```rust
+/// use multisplice::Multisplice;
+///
+/// let mut a = Multisplice::new("a b c d e");
+/// a.splice(2, 3, "beep");
+/// let mut b = Multisplice::new("a beep c d e");
+/// b.insert_left(0, "");
+/// assert_eq!(a, b);
+/// b.splice(0, 1, "b");
+/// assert_ne!(a, b);
+/// ```
+impl<'b> PartialEq<Multisplice<'b>> for Multisplice<'_> {
+    fn eq(&self, other: &Multisplice<'b>) -> bool {
+        let mut ours = self.segments().map(str::as_bytes);
+        let mut theirs = other.segments().map(str::as_bytes);
+        let (mut a, mut b): (&[u8], &[u8]) = (&[], &[]);
+        loop {
+            if a.is_empty() {
+                a = ours.next().unwrap_or_default();
+            }
+            if b.is_empty() {
+                b = theirs.next().unwrap_or_default();
+            }
+            // Segments are never empty, so an empty one means the output has ended
+            if a.is_empty() || b.is_empty() {
+                return a.is_empty() && b.is_empty();
+            }
+            let len = a.len().min(b.len());
+            if a[..len] != b[..len] {
+                return false;
+            }
+            a = &a[len..];
+            b = &b[len..];
+        }
+    }
+}
+
+impl Eq for Multisplice<'_> {}
+
+/// The number of bytes of the output that are hashed at a time.
+const HASH_BLOCK_LEN: usize = 64;
+
+/// Hashes the output, one segment at a time, without building the new string.
+///
+/// The output is passed to the hasher in blocks of a fixed size, so splicers with the same output
+/// hash the same no matter how it was produced, with any hasher. The hash is not the same as the
+/// hash of the rendered string.
+///
+/// # Example
+/// ```rust
+/// use multisplice::Multisplice;
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::collections::HashSet;
+/// use std::hash::{Hash, Hasher};
+///
+/// fn hash(value: impl Hash) -> u64 {
+///     let mut hasher = DefaultHasher::new();
+///     value.hash(&mut hasher);
+///     hasher.finish()
+/// }
+///
+/// let mut a = Multisplice::new("a b c d e");
+/// a.splice(2, 3, "beep");
+/// let mut b = Multisplice::new("a beep c d e");
+/// b.insert_left(0, "");
+/// assert_eq!(hash(&a), hash(&b));
+///
+/// let mut seen = HashSet::new();
+/// assert!(seen.insert(a));
+/// assert!(!seen.insert(b));
+/// ```
+impl Hash for Multisplice<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut block = [0; HASH_BLOCK_LEN];
+        let mut len = 0;
+        for segment in self.segments() {
+            let mut bytes = segment.as_bytes();
+            while !bytes.is_empty() {
+                if len == 0 && bytes.len() >= HASH_BLOCK_LEN {
+                    let (full, rest) = bytes.split_at(HASH_BLOCK_LEN);
+                    state.write(full);
+                    bytes = rest;
+                    continue;
+                }
+                let take = bytes.len().min(HASH_BLOCK_LEN - len);
+                block[len..len + take].copy_from_slice(&bytes[..take]);
+                len += take;
+                bytes = &bytes[take..];
+                if len == HASH_BLOCK_LEN {
+                    state.write(&block);
+                    len = 0;
+                }
+            }
+        }
+        state.write(&block[..len]);
+        // Like `str`, so the output of "ab" followed by "c" does not collide with "a" and "bc"
+        state.write_u8(0xff);
+    }
+}
+
+/// Writes the output, like [`Multisplice::render`]. Slots that were not filled are written as
+/// empty strings.
+///
+/// # Example
+/// ```rust
+/// use multisplice::Multisplice;
+///
+/// let mut splicer = Multisplice::new("a {b} c");
+/// splicer.slot(2..5, "b");
+/// assert_eq!(splicer.to_string(), "a  c");
+/// ```
+impl fmt::Display for Multisplice<'_> {
+    /// Execute the splices, writing the new string.
     #[inline]
-    fn to_string(&self) -> String {
-        self.slice_range(..).into()
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(bom) = self.bom {
+            f.write_str(bom)?;
+        }
+        if self.splices.is_empty() {
+            f.write_str(self.source)
+        } else {
+            f.write_str(self.cached_output())
+        }
     }
 }
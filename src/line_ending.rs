@@ -0,0 +1,78 @@
+//! Normalizing the line endings of the output.
+
+use crate::Multisplice;
+
+/// A line ending style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// `\n`, as used on Unix-like systems.
+    Lf,
+    /// `\r\n`, as used on Windows.
+    CrLf,
+}
+
+impl LineEnding {
+    /// The characters that end a line in this style.
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+impl Multisplice<'_> {
+    /// Execute the splices, returning the new string with all line endings converted to
+    /// `ending`.
+    ///
+    /// This applies to both the original string and the replacement values, including line
+    /// endings that are split between the two. Lone `\r` characters are treated as line endings
+    /// too.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{LineEnding, Multisplice};
+    ///
+    /// let mut splicer = Multisplice::new("a\r\nb\r\nc\r");
+    /// splicer.splice(3, 4, "beep\nboop");
+    /// splicer.insert_left(8, "\n");
+    /// assert_eq!(splicer.render_with_line_endings(LineEnding::Lf), "a\nbeep\nboop\nc\n");
+    /// assert_eq!(
+    ///     splicer.render_with_line_endings(LineEnding::CrLf),
+    ///     "a\r\nbeep\r\nboop\r\nc\r\n",
+    /// );
+    /// ```
+    pub fn render_with_line_endings(&self, ending: LineEnding) -> String {
+        let ending = ending.as_str();
+        let mut output = String::with_capacity(self.rendered_len());
+        let mut pending_cr = false;
+        for segment in self.segments() {
+            let mut last = 0;
+            for (i, c) in segment.char_indices() {
+                if c != '\r' && c != '\n' {
+                    if pending_cr {
+                        output.push_str(ending);
+                        pending_cr = false;
+                    }
+                    continue;
+                }
+                output.push_str(&segment[last..i]);
+                last = i + 1;
+                match c {
+                    '\r' if pending_cr => output.push_str(ending),
+                    '\r' => pending_cr = true,
+                    _ => {
+                        output.push_str(ending);
+                        pending_cr = false;
+                    }
+                }
+            }
+            output.push_str(&segment[last..]);
+        }
+        if pending_cr {
+            output.push_str(ending);
+        }
+        output
+    }
+}
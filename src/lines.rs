@@ -0,0 +1,325 @@
+//! Line and column computations.
+
+use crate::{Chunk, Chunks, Multisplice, SpliceId};
+use std::{borrow::Cow, fmt, ops::Range};
+
+/// Number of UTF-16 code units in a string.
+#[inline]
+pub(crate) fn utf16_len(text: &str) -> usize {
+    text.chars().map(char::len_utf16).sum()
+}
+
+/// Computes line and column numbers for indices into a string. Columns are counted in UTF-16
+/// code units, like source maps and LSP positions do.
+pub(crate) struct LineIndex<'a> {
+    source: &'a str,
+    /// The indices at which each line starts.
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub(crate) fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        LineIndex {
+            source,
+            line_starts,
+        }
+    }
+
+    /// Get the zero-based line and UTF-16 column of an index.
+    pub(crate) fn position(&self, index: usize) -> (usize, usize) {
+        let (line, column) = self.byte_position(index);
+        let start = index - column;
+        (line, utf16_len(&self.source[start..index]))
+    }
+
+    /// Get the zero-based line and byte column of an index.
+    pub(crate) fn byte_position(&self, index: usize) -> (usize, usize) {
+        let line = match self.line_starts.binary_search(&index) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+        (line, index - self.line_starts[line])
+    }
+
+    /// Get the index of a zero-based line and UTF-16 column.
+    ///
+    /// Lines past the end of the string resolve to the end of the string, and columns past the
+    /// end of a line resolve to the end of that line.
+    #[cfg_attr(not(feature = "lsp-types"), allow(dead_code))]
+    pub(crate) fn offset(&self, line: usize, column: usize) -> usize {
+        let start = match self.line_starts.get(line) {
+            Some(start) => *start,
+            None => return self.source.len(),
+        };
+        let text = &self.source[start..];
+        let text = &text[..text.find('\n').unwrap_or(text.len())];
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        let mut units = 0;
+        for (i, c) in text.char_indices() {
+            if units >= column {
+                return start + i;
+            }
+            units += c.len_utf16();
+        }
+        start + text.len()
+    }
+
+    /// Get the index of a one-based line and zero-based column counted in `char`s, like
+    /// `proc_macro2::LineColumn`.
+    ///
+    /// Lines past the end of the string resolve to the end of the string, and columns past the
+    /// end of a line resolve to the end of that line.
+    #[cfg_attr(not(feature = "syn"), allow(dead_code))]
+    pub(crate) fn char_offset(&self, line: usize, column: usize) -> usize {
+        let start = match line
+            .checked_sub(1)
+            .and_then(|line| self.line_starts.get(line))
+        {
+            Some(start) => *start,
+            None => return self.source.len(),
+        };
+        let text = &self.source[start..];
+        let text = &text[..text.find('\n').unwrap_or(text.len())];
+        match text.char_indices().nth(column) {
+            Some((i, _)) => start + i,
+            None => start + text.len(),
+        }
+    }
+}
+
+impl<'a> Multisplice<'a> {
+    /// Count the lines in the output, without building the new string.
+    ///
+    /// Lines are counted like [`str::lines`] does: a line ending at the very end does not start
+    /// another line, and an empty output has no lines.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a\nb\nc\n");
+    /// assert_eq!(splicer.output_line_count(), 3);
+    /// splicer.splice(2, 3, "beep\nboop");
+    /// assert_eq!(splicer.output_line_count(), 4);
+    /// ```
+    pub fn output_line_count(&self) -> usize {
+        let mut count = 0;
+        let mut ends_with_newline = true;
+        for segment in self.segments() {
+            count += segment.matches('\n').count();
+            ends_with_newline = segment.ends_with('\n');
+        }
+        if ends_with_newline {
+            count
+        } else {
+            count + 1
+        }
+    }
+
+    /// Get the byte range of each line in the output, without building the new string.
+    ///
+    /// The ranges do not include the line endings, which may be `\n` or `\r\n`. Lines are split
+    /// like [`Multisplice::output_line_count`] counts them.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a\r\nb\nc");
+    /// splicer.splice(3, 4, "beep\nboop");
+    /// assert_eq!(splicer.to_string(), "a\r\nbeep\nboop\nc");
+    /// assert_eq!(splicer.line_spans_output(), vec![0..1, 3..7, 8..12, 13..14]);
+    /// ```
+    pub fn line_spans_output(&self) -> Vec<Range<usize>> {
+        let mut spans = vec![];
+        let mut start = 0;
+        let mut position = 0;
+        let mut after_cr = false;
+        for segment in self.segments() {
+            for (i, _) in segment.match_indices('\n') {
+                let end = position + i;
+                let cr = match i.checked_sub(1) {
+                    Some(before) => segment.as_bytes()[before] == b'\r',
+                    None => after_cr,
+                };
+                let cr = cr && end > start;
+                spans.push(start..end - usize::from(cr));
+                start = end + 1;
+            }
+            position += segment.len();
+            after_cr = segment.ends_with('\r');
+        }
+        if start < position {
+            spans.push(start..position);
+        }
+        spans
+    }
+
+    /// Iterate over the lines of the output, together with the lines of the original string and
+    /// the splices that they came from, without building the new string.
+    ///
+    /// Lines are split like [`Multisplice::output_line_count`] counts them, and do not include
+    /// their line endings.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a\nb c\nd\n");
+    /// let id = splicer.splice(4, 7, "beep\nboop");
+    /// let lines = splicer.output_lines().collect::<Vec<_>>();
+    /// assert_eq!(lines.len(), 3);
+    /// assert_eq!(lines[0].text, "a");
+    /// assert_eq!(lines[0].original_lines, vec![0]);
+    /// assert!(lines[0].splices.is_empty());
+    /// assert_eq!(lines[1].text, "b beep");
+    /// assert_eq!(lines[1].original_lines, vec![1]);
+    /// assert_eq!(lines[1].splices, vec![id]);
+    /// assert_eq!(lines[2].text, "boop");
+    /// assert!(lines[2].original_lines.is_empty());
+    /// assert_eq!(lines[2].splices, vec![id]);
+    /// ```
+    pub fn output_lines(&self) -> OutputLines<'_, 'a> {
+        OutputLines {
+            chunks: self.chunks(0, self.source.len()),
+            lines: LineIndex::new(self.source),
+            current: None,
+            pending: vec![],
+        }
+    }
+}
+
+/// A line of the output of a [`Multisplice`], and where its content came from.
+///
+/// Yielded by [`Multisplice::output_lines`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputLine<'s> {
+    /// The text of the line, without its line ending.
+    pub text: Cow<'s, str>,
+    /// The zero-based lines of the original string that were copied into this line, in the
+    /// order they appear in it. A line ending only counts if it is all that was copied into an
+    /// otherwise empty line.
+    pub original_lines: Vec<usize>,
+    /// The splices whose replacement values make up parts of this line, in the order they appear
+    /// in it.
+    pub splices: Vec<SpliceId>,
+}
+
+/// An iterator over the lines of the output of a [`Multisplice`], and where they came from.
+///
+/// Returned by [`Multisplice::output_lines`].
+pub struct OutputLines<'s, 'a> {
+    chunks: Chunks<'s, 'a>,
+    lines: LineIndex<'a>,
+    /// The rest of the current chunk, the chunk, and the offset of the rest in the chunk.
+    current: Option<(&'s str, Chunk, usize)>,
+    /// Splices with empty values that come before the next line.
+    pending: Vec<SpliceId>,
+}
+
+impl<'s> OutputLines<'s, '_> {
+    /// Add `piece` of the current chunk, starting at `offset` in the chunk, to `line`.
+    fn add(
+        &mut self,
+        line: &mut Option<OutputLine<'s>>,
+        piece: &'s str,
+        chunk: &Chunk,
+        offset: usize,
+    ) {
+        if piece.is_empty() && line.is_none() {
+            // Attribute deletions between lines to the next line
+            match chunk {
+                Chunk::Splice(index) if offset == 0 => {
+                    self.pending.push(self.chunks.splicer.splices[*index].id);
+                }
+                _ => (),
+            }
+            return;
+        }
+        let pending = &mut self.pending;
+        let line = line.get_or_insert_with(|| OutputLine {
+            text: Cow::Borrowed(""),
+            original_lines: vec![],
+            splices: std::mem::take(pending),
+        });
+        match chunk {
+            Chunk::Source(range) => {
+                // Only count a copied line ending if the line has nothing else in it
+                let content = piece.trim_end_matches(&['\r', '\n'][..]);
+                let empty_line = line.text.is_empty() && line.splices.is_empty();
+                let (original, _) = self.lines.byte_position(range.start + offset);
+                if (!content.is_empty() || (!piece.is_empty() && empty_line))
+                    && line.original_lines.last() != Some(&original)
+                {
+                    line.original_lines.push(original);
+                }
+            }
+            Chunk::Splice(index) => {
+                let id = self.chunks.splicer.splices[*index].id;
+                if line.splices.last() != Some(&id) {
+                    line.splices.push(id);
+                }
+            }
+        }
+        if line.text.is_empty() {
+            line.text = Cow::Borrowed(piece);
+        } else if !piece.is_empty() {
+            line.text.to_mut().push_str(piece);
+        }
+    }
+}
+
+/// Remove the line ending from the end of `text`.
+fn strip_line_ending(text: &mut Cow<'_, str>) {
+    let len = text.strip_suffix('\n').unwrap_or(text).len();
+    let len = text[..len].strip_suffix('\r').unwrap_or(&text[..len]).len();
+    match text {
+        Cow::Borrowed(text) => *text = &text[..len],
+        Cow::Owned(text) => text.truncate(len),
+    }
+}
+
+impl<'s> Iterator for OutputLines<'s, '_> {
+    type Item = OutputLine<'s>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = None;
+        loop {
+            let (rest, chunk, offset) = match self.current.take() {
+                Some(current) => current,
+                None => match self.chunks.next() {
+                    Some(chunk) => {
+                        let splicer = self.chunks.splicer;
+                        let text: &'s str = match &chunk {
+                            Chunk::Source(range) => &splicer.source[range.clone()],
+                            Chunk::Splice(index) => {
+                                splicer.splices[*index].output(&splicer.rendering)
+                            }
+                        };
+                        (text, chunk, 0)
+                    }
+                    None => return line,
+                },
+            };
+            match rest.find('\n') {
+                Some(i) => {
+                    self.add(&mut line, &rest[..=i], &chunk, offset);
+                    self.current = Some((&rest[i + 1..], chunk, offset + i + 1));
+                    let mut line = line.expect("a piece was added");
+                    strip_line_ending(&mut line.text);
+                    return Some(line);
+                }
+                None => self.add(&mut line, rest, &chunk, offset),
+            }
+        }
+    }
+}
+
+impl fmt::Debug for OutputLines<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OutputLines").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,69 @@
+//! Conversions between splices and Language Server Protocol text edits.
+
+use crate::{lines::LineIndex, Multisplice, SpliceError};
+use lsp_types::{Position, Range as LspRange, TextEdit};
+use std::borrow::Cow;
+
+impl<'a> Multisplice<'a> {
+    /// Create a "multisplicer" for the given string, with all the LSP `TextEdit`s in `edits`
+    /// already queued.
+    ///
+    /// Positions are resolved against `source`, with columns counted in UTF-16 code units.
+    ///
+    /// # Example
+    /// ```rust
+    /// use lsp_types::{Position, Range, TextEdit};
+    /// use multisplice::Multisplice;
+    ///
+    /// let edits = vec![TextEdit {
+    ///     range: Range::new(Position::new(1, 2), Position::new(1, 4)),
+    ///     new_text: "beep".to_string(),
+    /// }];
+    /// let splicer = Multisplice::from_text_edits("a 😀 b\nc 😀 d", &edits).unwrap();
+    /// assert_eq!(splicer.to_string(), "a 😀 b\nc beep d");
+    /// ```
+    pub fn from_text_edits(
+        source: &'a str,
+        edits: &'a [TextEdit],
+    ) -> Result<Self, Vec<SpliceError>> {
+        let lines = LineIndex::new(source);
+        let offset =
+            |position: &Position| lines.offset(position.line as usize, position.character as usize);
+        Self::from_edits(
+            source,
+            edits.iter().map(|edit| {
+                let range = offset(&edit.range.start)..offset(&edit.range.end);
+                (range, Cow::Borrowed(edit.new_text.as_str()))
+            }),
+        )
+    }
+
+    /// Get the queued splices as LSP `TextEdit`s, in order.
+    ///
+    /// # Example
+    /// ```rust
+    /// use lsp_types::{Position, Range, TextEdit};
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b\nc d");
+    /// splicer.splice(6, 7, "boop");
+    /// assert_eq!(splicer.to_text_edits(), vec![TextEdit {
+    ///     range: Range::new(Position::new(1, 2), Position::new(1, 3)),
+    ///     new_text: "boop".to_string(),
+    /// }]);
+    /// ```
+    pub fn to_text_edits(&self) -> Vec<TextEdit> {
+        let lines = LineIndex::new(self.source);
+        let position = |index| {
+            let (line, column) = lines.position(index);
+            Position::new(line as u32, column as u32)
+        };
+        self.splices
+            .iter()
+            .map(|s| TextEdit {
+                range: LspRange::new(position(s.start()), position(s.end())),
+                new_text: s.output(&self.rendering).to_string(),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,50 @@
+//! Building a splicer from a list of splices.
+
+/// Create a [`Multisplice`](crate::Multisplice) for a source string and queue a list of splices
+/// on it.
+///
+/// Each splice is written as `range => value`, where the range can be any range accepted by
+/// [`Multisplice::splice_range`](crate::Multisplice::splice_range). Ranges written as two integer
+/// literals, like `2..3`, are checked at compile time: a range that starts after it ends does not
+/// compile.
+///
+/// # Example
+/// ```rust
+/// use multisplice::multisplice;
+///
+/// let owned = String::from("boop");
+/// let splicer = multisplice!("a b c d e"; 2..3 => "beep", 6..7 => owned);
+/// assert_eq!(splicer.to_string(), "a beep c boop e");
+///
+/// let end = 9;
+/// let splicer = multisplice!("a b c d e"; 0..1 => "A", 8..end => "E",);
+/// assert_eq!(splicer.to_string(), "A b c d E");
+/// ```
+///
+/// ```rust,compile_fail
+/// use multisplice::multisplice;
+///
+/// let splicer = multisplice!("a b c d e"; 3..2 => "beep");
+/// ```
+#[macro_export]
+macro_rules! multisplice {
+    ($source:expr; $($splices:tt)*) => {{
+        #[allow(unused_mut)]
+        let mut splicer = $crate::Multisplice::new($source);
+        $crate::multisplice!(@splice splicer; $($splices)*);
+        splicer
+    }};
+    (@splice $splicer:ident;) => {};
+    (@splice $splicer:ident; $start:literal .. $end:literal => $value:expr $(, $($rest:tt)*)?) => {
+        const _: () = assert!(
+            $start <= $end,
+            concat!("range ", stringify!($start..$end), " starts after it ends"),
+        );
+        $splicer.splice_range($start..$end, $value);
+        $crate::multisplice!(@splice $splicer; $($($rest)*)?);
+    };
+    (@splice $splicer:ident; $range:expr => $value:expr $(, $($rest:tt)*)?) => {
+        $splicer.splice_range($range, $value);
+        $crate::multisplice!(@splice $splicer; $($($rest)*)?);
+    };
+}
@@ -0,0 +1,254 @@
+//! Rewriting Markdown documents using the offsets reported by `pulldown-cmark`.
+
+use crate::{Multisplice, SpliceError};
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, HeadingLevel, LinkType, Parser, Tag, TagEnd};
+use std::{borrow::Cow, ops::Range};
+
+/// Find the end of the link destination that starts at `index`.
+fn destination_end(source: &str, index: usize) -> usize {
+    let bytes = source.as_bytes();
+    let mut i = index;
+    if bytes.get(i) == Some(&b'<') {
+        while i < bytes.len() && bytes[i] != b'>' && bytes[i] != b'\n' {
+            i += if bytes[i] == b'\\' { 2 } else { 1 };
+        }
+        return (i + 1).min(bytes.len());
+    }
+    let mut depth = 0;
+    while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !bytes[i].is_ascii_control() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'(' => depth += 1,
+            b')' if depth == 0 => break,
+            b')' => depth -= 1,
+            _ => (),
+        }
+        i += 1;
+    }
+    i.min(bytes.len())
+}
+
+/// Find the range of the link destination starting at or after `index`, skipping whitespace and
+/// angle brackets.
+fn destination_range(source: &str, index: usize) -> Option<Range<usize>> {
+    let start = index + source[index..].len() - source[index..].trim_start().len();
+    let end = destination_end(source, start);
+    if source[start..].starts_with('<') {
+        (end > start + 1 && source[..end].ends_with('>')).then(|| start + 1..end - 1)
+    } else {
+        (end > start).then_some(start..end)
+    }
+}
+
+/// Find the range of the destination of the reference definition in `span`, like `[a]: b`.
+fn definition_destination(source: &str, span: Range<usize>) -> Option<Range<usize>> {
+    let bytes = source.as_bytes();
+    let mut i = span.start + source[span.clone()].find('[')? + 1;
+    while i < span.end && bytes[i] != b']' {
+        i += if bytes[i] == b'\\' { 2 } else { 1 };
+    }
+    if source.get(i..i + 2) != Some("]:") {
+        return None;
+    }
+    destination_range(source, i + 2).filter(|range| range.end <= span.end)
+}
+
+/// An open link or image, and the end of its text so far.
+struct OpenLink {
+    link_type: LinkType,
+    range: Range<usize>,
+    text_end: usize,
+}
+
+impl<'a> Multisplice<'a> {
+    /// Parse the original string as CommonMark and replace the destinations of links and images
+    /// for which `rewrite` returns a value.
+    ///
+    /// `rewrite` receives each destination as it is written in the original string, so escapes
+    /// are retained and angle brackets around it are not included. Reference links are rewritten
+    /// by rewriting their definition, once per definition. The new splices are queued like
+    /// [`Multisplice::splice_many`] does, so if any of them conflict with splices that were
+    /// already queued, none are applied.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let source = "See [the docs](./docs.md), ![logo](logo.png) and [the source].\n\n\
+    ///               [the source]: ./src/lib.rs\n";
+    /// let mut splicer = Multisplice::new(source);
+    /// splicer
+    ///     .rewrite_links(|url| url.strip_prefix("./").map(|path| format!("https://example.com/{}", path)))
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     splicer.to_string(),
+    ///     "See [the docs](https://example.com/docs.md), ![logo](logo.png) and [the source].\n\n\
+    ///      [the source]: https://example.com/src/lib.rs\n",
+    /// );
+    /// ```
+    pub fn rewrite_links<V>(
+        &mut self,
+        mut rewrite: impl FnMut(&str) -> Option<V>,
+    ) -> Result<(), Vec<SpliceError>>
+    where
+        V: Into<Cow<'a, str>>,
+    {
+        let source = self.source;
+        let mut destinations = vec![];
+        let mut open: Vec<OpenLink> = vec![];
+        let events = Parser::new(source).into_offset_iter();
+        for (_, definition) in events.reference_definitions().iter() {
+            destinations.extend(definition_destination(source, definition.span.clone()));
+        }
+        for (event, range) in events {
+            match event {
+                Event::Start(Tag::Link { link_type, .. })
+                | Event::Start(Tag::Image { link_type, .. }) => {
+                    let text_start = if source[range.start..].starts_with('!') {
+                        2
+                    } else {
+                        1
+                    };
+                    open.push(OpenLink {
+                        link_type,
+                        text_end: range.start + text_start,
+                        range,
+                    });
+                    continue;
+                }
+                Event::End(TagEnd::Link) | Event::End(TagEnd::Image) => {
+                    let link = open.pop().expect("link end without a start");
+                    match link.link_type {
+                        LinkType::Inline if source[link.text_end..].starts_with("](") => {
+                            destinations.extend(
+                                destination_range(source, link.text_end + 2)
+                                    .filter(|dest| dest.end <= link.range.end),
+                            );
+                        }
+                        LinkType::Autolink | LinkType::Email => {
+                            destinations.push(link.range.start + 1..link.range.end - 1);
+                        }
+                        _ => (),
+                    }
+                }
+                _ => (),
+            }
+            if let Some(link) = open.last_mut() {
+                link.text_end = link.text_end.max(range.end);
+            }
+        }
+
+        destinations.sort_by_key(|range| range.start);
+        let edits = destinations
+            .into_iter()
+            .filter_map(|range| rewrite(&source[range.clone()]).map(|value| (range, value)))
+            .collect::<Vec<_>>();
+        self.splice_many(edits)
+    }
+
+    /// Parse the original string as CommonMark and replace the text of headings for which
+    /// `rewrite` returns a value.
+    ///
+    /// `rewrite` receives the level and the source of the text of each heading, without the `#`
+    /// markers or setext underline. The new splices are queued like [`Multisplice::splice_many`]
+    /// does, so if any of them conflict with splices that were already queued, none are applied.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    /// use pulldown_cmark::HeadingLevel;
+    ///
+    /// let mut splicer = Multisplice::new("# Intro\n\ntext\n\n## Usage *notes*\n");
+    /// splicer
+    ///     .rewrite_headings(|level, text| (level == HeadingLevel::H2).then(|| text.to_uppercase()))
+    ///     .unwrap();
+    /// assert_eq!(splicer.to_string(), "# Intro\n\ntext\n\n## USAGE *NOTES*\n");
+    /// ```
+    pub fn rewrite_headings<V>(
+        &mut self,
+        mut rewrite: impl FnMut(HeadingLevel, &str) -> Option<V>,
+    ) -> Result<(), Vec<SpliceError>>
+    where
+        V: Into<Cow<'a, str>>,
+    {
+        let source = self.source;
+        let mut edits = vec![];
+        let mut heading: Option<(HeadingLevel, Option<Range<usize>>)> = None;
+        for (event, range) in Parser::new(source).into_offset_iter() {
+            match event {
+                Event::Start(Tag::Heading { level, .. }) => heading = Some((level, None)),
+                Event::End(TagEnd::Heading(_)) => {
+                    if let Some((level, Some(text))) = heading.take() {
+                        if let Some(value) = rewrite(level, &source[text.clone()]) {
+                            edits.push((text, value));
+                        }
+                    }
+                }
+                _ => {
+                    if let Some((_, text)) = &mut heading {
+                        let start = text.as_ref().map_or(range.start, |text| text.start);
+                        let end = text
+                            .as_ref()
+                            .map_or(range.end, |text| text.end.max(range.end));
+                        *text = Some(start..end);
+                    }
+                }
+            }
+        }
+        self.splice_many(edits)
+    }
+
+    /// Parse the original string as CommonMark and replace the code of fenced code blocks for
+    /// which `rewrite` returns a value.
+    ///
+    /// `rewrite` receives the info string after the opening fence and the source of the code,
+    /// including its final newline but not the fences. Empty code blocks are not passed to
+    /// `rewrite`. For code blocks inside block quotes or list items, the source of the code also
+    /// contains the container markers and indentation of every line but the first. The new
+    /// splices are queued like [`Multisplice::splice_many`] does, so if any of them conflict with
+    /// splices that were already queued, none are applied.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("```sh\n$ cargo build\n```\n\n```rust\nfn main() {}\n```\n");
+    /// splicer
+    ///     .rewrite_code_blocks(|info, code| (info == "sh").then(|| code.replace("$ ", "")))
+    ///     .unwrap();
+    /// assert_eq!(splicer.to_string(), "```sh\ncargo build\n```\n\n```rust\nfn main() {}\n```\n");
+    /// ```
+    pub fn rewrite_code_blocks<V>(
+        &mut self,
+        mut rewrite: impl FnMut(&str, &str) -> Option<V>,
+    ) -> Result<(), Vec<SpliceError>>
+    where
+        V: Into<Cow<'a, str>>,
+    {
+        let source = self.source;
+        let mut edits = vec![];
+        let mut block: Option<(CowStr<'_>, Option<Range<usize>>)> = None;
+        for (event, range) in Parser::new(source).into_offset_iter() {
+            match event {
+                Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
+                    block = Some((info, None));
+                }
+                Event::Text(_) => {
+                    if let Some((_, code)) = &mut block {
+                        let start = code.as_ref().map_or(range.start, |code| code.start);
+                        *code = Some(start..range.end);
+                    }
+                }
+                Event::End(TagEnd::CodeBlock) => {
+                    if let Some((info, Some(code))) = block.take() {
+                        if let Some(value) = rewrite(&info, &source[code.clone()]) {
+                            edits.push((code, value));
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        self.splice_many(edits)
+    }
+}
@@ -0,0 +1,116 @@
+//! Counting the work a splicer does.
+
+#[cfg(feature = "metrics")]
+use crate::Multisplice;
+#[cfg(feature = "metrics")]
+use std::{
+    convert::TryFrom,
+    sync::atomic::{AtomicU64, Ordering::Relaxed},
+    time::{Duration, Instant},
+};
+
+/// A snapshot of the work a [`Multisplice`] has done.
+///
+/// Returned by [`Multisplice::metrics`].
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Metrics {
+    /// The number of splices that were queued.
+    pub splices_queued: u64,
+    /// The number of bytes copied into output strings.
+    pub bytes_copied: u64,
+    /// The number of output strings allocated.
+    pub allocations: u64,
+    /// The time spent assembling output strings.
+    pub render_time: Duration,
+}
+
+/// Counters for the work a splicer does. Without the `metrics` feature, this is empty and does
+/// nothing.
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    #[cfg(feature = "metrics")]
+    splices_queued: AtomicU64,
+    #[cfg(feature = "metrics")]
+    bytes_copied: AtomicU64,
+    #[cfg(feature = "metrics")]
+    allocations: AtomicU64,
+    /// The render time in nanoseconds.
+    #[cfg(feature = "metrics")]
+    render_time: AtomicU64,
+}
+
+#[cfg(feature = "metrics")]
+impl Counters {
+    #[inline]
+    pub(crate) fn queued(&self, count: usize) {
+        self.splices_queued.fetch_add(count as u64, Relaxed);
+    }
+
+    #[inline]
+    pub(crate) fn copied(&self, bytes: usize) {
+        self.bytes_copied.fetch_add(bytes as u64, Relaxed);
+    }
+
+    #[inline]
+    pub(crate) fn allocated(&self) {
+        self.allocations.fetch_add(1, Relaxed);
+    }
+
+    /// Run `render`, adding the time it takes to the render time.
+    #[inline]
+    pub(crate) fn timed<R>(&self, render: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let result = render();
+        let elapsed = u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX);
+        self.render_time.fetch_add(elapsed, Relaxed);
+        result
+    }
+}
+
+#[cfg(not(feature = "metrics"))]
+impl Counters {
+    #[inline]
+    pub(crate) fn queued(&self, _count: usize) {}
+
+    #[inline]
+    pub(crate) fn copied(&self, _bytes: usize) {}
+
+    #[inline]
+    pub(crate) fn allocated(&self) {}
+
+    #[inline]
+    pub(crate) fn timed<R>(&self, render: impl FnOnce() -> R) -> R {
+        render()
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl Multisplice<'_> {
+    /// Get a snapshot of the work this splicer has done so far: how many splices were queued,
+    /// and how many output strings were allocated, how many bytes were copied into them and how
+    /// long that took.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(6, 7, "boop");
+    /// assert_eq!(splicer.to_string(), "a beep c boop e");
+    /// let metrics = splicer.metrics();
+    /// assert_eq!(metrics.splices_queued, 2);
+    /// assert_eq!(metrics.allocations, 1);
+    /// assert_eq!(metrics.bytes_copied, 15);
+    /// ```
+    pub fn metrics(&self) -> Metrics {
+        let counters = &self.counters;
+        Metrics {
+            splices_queued: counters.splices_queued.load(Relaxed),
+            bytes_copied: counters.bytes_copied.load(Relaxed),
+            allocations: counters.allocations.load(Relaxed),
+            render_time: Duration::from_nanos(counters.render_time.load(Relaxed)),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Memory-mapped original strings.
+
+use memmap2::Mmap;
+use std::{fmt, fs::File, io, ops::Deref, path::Path};
+
+/// A memory-mapped file that can be used as the original string for a
+/// [`Multisplice`](crate::Multisplice).
+///
+/// The file is not read into memory all at once, so huge files can be patched by combining this
+/// with [`Multisplice::write_to`](crate::Multisplice::write_to).
+///
+/// # Example
+/// ```rust
+/// use multisplice::{MappedFile, Multisplice};
+///
+/// let path = std::env::temp_dir().join("multisplice-mmap-example.txt");
+/// std::fs::write(&path, "a b c d e").unwrap();
+///
+/// // Safety: the file is not modified while it is mapped.
+/// let file = unsafe { MappedFile::open(&path) }.unwrap();
+/// let mut splicer = Multisplice::new(&file);
+/// splicer.splice(2, 3, "beep");
+/// let mut output = vec![];
+/// splicer.write_to(&mut output).unwrap();
+/// assert_eq!(output, b"a beep c d e");
+/// # drop(splicer);
+/// # drop(file);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct MappedFile {
+    map: Mmap,
+}
+
+impl MappedFile {
+    /// Memory-map the file at `path`.
+    ///
+    /// Returns an error with kind [`io::ErrorKind::InvalidData`] if the file is not valid UTF-8.
+    ///
+    /// # Safety
+    /// The file must not be modified, by this process or any other, while it is mapped. See
+    /// [`Mmap::map`].
+    pub unsafe fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let map = Mmap::map(&file)?;
+        std::str::from_utf8(&map).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(MappedFile { map })
+    }
+
+    /// Get the contents of the file.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // Safety: the contents were validated in `open`, and may not be modified after that.
+        unsafe { std::str::from_utf8_unchecked(&self.map) }
+    }
+}
+
+impl Deref for MappedFile {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for MappedFile {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for MappedFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MappedFile")
+            .field("len", &self.map.len())
+            .finish()
+    }
+}
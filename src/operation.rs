@@ -0,0 +1,75 @@
+//! Converting splices to operational transform operations.
+
+use crate::{Chunk, Multisplice};
+use std::borrow::Cow;
+
+/// An operation in an operational transform style sequence of edits, as used by collaborative
+/// editing libraries like `ot.js`.
+///
+/// Lengths are in bytes of the UTF-8 original string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation<'s> {
+    /// Keep the next `n` bytes of the original string.
+    Retain(usize),
+    /// Insert a string at the current position.
+    Insert(Cow<'s, str>),
+    /// Remove the next `n` bytes of the original string.
+    Delete(usize),
+}
+
+/// Add `op` to the end of `ops`, merging it with the previous operation if they are of the same
+/// kind.
+fn push<'s>(ops: &mut Vec<Operation<'s>>, op: Operation<'s>) {
+    match (ops.last_mut(), op) {
+        (_, Operation::Retain(0)) | (_, Operation::Delete(0)) => (),
+        (_, Operation::Insert(value)) if value.is_empty() => (),
+        (Some(Operation::Retain(last)), Operation::Retain(n)) => *last += n,
+        (Some(Operation::Delete(last)), Operation::Delete(n)) => *last += n,
+        (Some(Operation::Insert(last)), Operation::Insert(value)) => last.to_mut().push_str(&value),
+        (_, op) => ops.push(op),
+    }
+}
+
+impl Multisplice<'_> {
+    /// Describe the queued splices as a sequence of operational transform operations, which turns
+    /// the original string into the output when applied from the start of the string.
+    ///
+    /// The operations span the whole original string, so their retained and deleted lengths add
+    /// up to its length. Adjacent operations of the same kind are merged, and replacements are
+    /// described as an insertion followed by a deletion.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, Operation};
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(3, 6, "");
+    /// splicer.insert_left(9, "!");
+    /// assert_eq!(
+    ///     splicer.operations(),
+    ///     vec![
+    ///         Operation::Retain(2),
+    ///         Operation::Insert("beep".into()),
+    ///         Operation::Delete(4),
+    ///         Operation::Retain(3),
+    ///         Operation::Insert("!".into()),
+    ///     ],
+    /// );
+    /// ```
+    pub fn operations(&self) -> Vec<Operation<'_>> {
+        let mut ops = vec![];
+        for chunk in self.chunks(0, self.source.len()) {
+            match chunk {
+                Chunk::Source(range) => push(&mut ops, Operation::Retain(range.len())),
+                Chunk::Splice(index) => {
+                    let splice = &self.splices[index];
+                    let value = splice.output(&self.rendering);
+                    push(&mut ops, Operation::Insert(Cow::Borrowed(value)));
+                    push(&mut ops, Operation::Delete(splice.end() - splice.start()));
+                }
+            }
+        }
+        ops
+    }
+}
@@ -0,0 +1,84 @@
+//! Attributing each character of the output to where it came from.
+
+use crate::{Chunk, Chunks, Multisplice, SpliceId};
+use std::{fmt, str::CharIndices};
+
+/// Where a character of the output came from.
+///
+/// Yielded by [`Multisplice::chars_with_origin`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Origin {
+    /// The character at this byte offset in the original string.
+    Source(usize),
+    /// The replacement value of this splice.
+    Splice(SpliceId),
+}
+
+impl<'a> Multisplice<'a> {
+    /// Iterate over the characters of the output, together with where each of them came from,
+    /// without building the new string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, Origin};
+    ///
+    /// let mut splicer = Multisplice::new("a b");
+    /// let id = splicer.splice(2, 3, "be");
+    /// let chars = splicer.chars_with_origin().collect::<Vec<_>>();
+    /// assert_eq!(chars, vec![
+    ///     ('a', Origin::Source(0)),
+    ///     (' ', Origin::Source(1)),
+    ///     ('b', Origin::Splice(id)),
+    ///     ('e', Origin::Splice(id)),
+    /// ]);
+    /// ```
+    #[inline]
+    pub fn chars_with_origin(&self) -> CharsWithOrigin<'_, 'a> {
+        CharsWithOrigin {
+            chunks: self.chunks(0, self.source.len()),
+            current: None,
+        }
+    }
+}
+
+/// An iterator over the characters of the output of a [`Multisplice`], together with where they
+/// came from.
+///
+/// Returned by [`Multisplice::chars_with_origin`].
+pub struct CharsWithOrigin<'s, 'a> {
+    chunks: Chunks<'s, 'a>,
+    /// The characters of the current chunk, and the chunk they belong to.
+    current: Option<(CharIndices<'s>, Chunk)>,
+}
+
+impl Iterator for CharsWithOrigin<'_, '_> {
+    type Item = (char, Origin);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((chars, chunk)) = &mut self.current {
+                if let Some((offset, c)) = chars.next() {
+                    let splicer = self.chunks.splicer;
+                    let origin = match chunk {
+                        Chunk::Source(range) => Origin::Source(range.start + offset),
+                        Chunk::Splice(index) => Origin::Splice(splicer.splices[*index].id),
+                    };
+                    return Some((c, origin));
+                }
+            }
+            let chunk = self.chunks.next()?;
+            let splicer = self.chunks.splicer;
+            let text: &str = match &chunk {
+                Chunk::Source(range) => &splicer.source[range.clone()],
+                Chunk::Splice(index) => splicer.splices[*index].output(&splicer.rendering),
+            };
+            self.current = Some((text.char_indices(), chunk));
+        }
+    }
+}
+
+impl fmt::Debug for CharsWithOrigin<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CharsWithOrigin").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,136 @@
+//! Splicing an owned string, for bindings to other languages that can not lend out strings.
+
+use crate::{check_range, Multisplice, SpliceError};
+use std::ops::Range;
+
+/// A queued edit.
+#[derive(Debug)]
+enum Edit {
+    Splice(Range<usize>, String),
+    #[cfg_attr(not(feature = "wasm"), allow(dead_code))]
+    InsertLeft(usize, String),
+}
+
+/// Owns the original string and the replacement values, and builds a [`Multisplice`] on demand.
+///
+/// A `Multisplice` can not be stored next to the string it borrows, so new edits are checked
+/// against sorted lists of the queued ranges instead. That only looks at the neighbours of the
+/// new edit, so queueing many edits stays fast.
+#[derive(Debug)]
+pub(crate) struct OwnedSplicer {
+    source: String,
+    edits: Vec<Edit>,
+    /// The non-empty ranges that are replaced, with the index of their edit, sorted by start.
+    /// They never overlap.
+    replaced: Vec<(Range<usize>, usize)>,
+    /// The indices of the insertions, sorted.
+    insertions: Vec<usize>,
+}
+
+impl OwnedSplicer {
+    pub(crate) fn new(source: String) -> Self {
+        OwnedSplicer {
+            source,
+            edits: vec![],
+            replaced: vec![],
+            insertions: vec![],
+        }
+    }
+
+    /// Build a splicer from the queued edits.
+    pub(crate) fn splicer(&self) -> Multisplice<'_> {
+        let mut splicer = Multisplice::with_capacity(&self.source, self.edits.len());
+        let splices = self.edits.iter().filter_map(|edit| match edit {
+            Edit::Splice(range, value) => Some((range.clone(), value.as_str())),
+            Edit::InsertLeft(..) => None,
+        });
+        splicer
+            .splice_many(splices)
+            .expect("edits are checked when they are queued");
+        for edit in &self.edits {
+            if let Edit::InsertLeft(index, value) = edit {
+                splicer.insert_left(*index, value.as_str());
+            }
+        }
+        splicer
+    }
+
+    /// Queue a splice, or return an error if the range does not fit the original string or
+    /// overlaps a range that was already spliced.
+    pub(crate) fn splice(&mut self, range: Range<usize>, value: String) -> Result<(), SpliceError> {
+        check_range(&self.source, &range)?;
+        if range.is_empty() {
+            self.check_insertion(range.start)?;
+            self.add_insertion(range.start);
+            self.edits.push(Edit::Splice(range, value));
+            return Ok(());
+        }
+
+        let index = self
+            .replaced
+            .partition_point(|(r, _)| r.start < range.start);
+        if let Some((existing, _)) = index.checked_sub(1).map(|i| &self.replaced[i]) {
+            if existing.end > range.start {
+                return Err(SpliceError::Overlap {
+                    existing: existing.clone(),
+                    new: range,
+                });
+            }
+        }
+        if let Some((existing, edit)) = self.replaced.get(index) {
+            if existing.start < range.end {
+                let duplicate = *existing == range
+                    && matches!(&self.edits[*edit], Edit::Splice(_, v) if *v == value);
+                if duplicate {
+                    return Ok(());
+                }
+                return Err(SpliceError::Overlap {
+                    existing: existing.clone(),
+                    new: range,
+                });
+            }
+        }
+        let inside = self.insertions.partition_point(|&i| i <= range.start);
+        if let Some(&insertion) = self.insertions.get(inside) {
+            if insertion < range.end {
+                return Err(SpliceError::Overlap {
+                    existing: insertion..insertion,
+                    new: range,
+                });
+            }
+        }
+
+        self.replaced
+            .insert(index, (range.clone(), self.edits.len()));
+        self.edits.push(Edit::Splice(range, value));
+        Ok(())
+    }
+
+    /// Queue a left insertion, or return an error if the index does not fit the original string
+    /// or is inside a range that was already spliced.
+    #[cfg_attr(not(feature = "wasm"), allow(dead_code))]
+    pub(crate) fn insert_left(&mut self, index: usize, value: String) -> Result<(), SpliceError> {
+        check_range(&self.source, &(index..index))?;
+        self.check_insertion(index)?;
+        self.add_insertion(index);
+        self.edits.push(Edit::InsertLeft(index, value));
+        Ok(())
+    }
+
+    /// Check that an insertion at `index` is not inside a replaced range.
+    fn check_insertion(&self, index: usize) -> Result<(), SpliceError> {
+        let before = self.replaced.partition_point(|(r, _)| r.start < index);
+        match before.checked_sub(1).map(|i| &self.replaced[i].0) {
+            Some(existing) if existing.end > index => Err(SpliceError::Overlap {
+                existing: existing.clone(),
+                new: index..index,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn add_insertion(&mut self, index: usize) {
+        let position = self.insertions.partition_point(|&i| i <= index);
+        self.insertions.insert(position, index);
+    }
+}
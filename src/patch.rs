@@ -0,0 +1,211 @@
+//! A compact text format for storing splices.
+//!
+//! The format starts with a header line containing a hash and the length of the original string.
+//! Each splice is then stored as a line with the start and end indices of the range to replace
+//! and the length of the replacement value in bytes, followed by the replacement value and a
+//! newline:
+//!
+//! ```text
+//! multisplice 1 e22588b27bd8c58c 9
+//! 2 3 4
+//! beep
+//! 6 7 4
+//! boop
+//! ```
+
+use crate::{Multisplice, SpliceError};
+use std::{borrow::Cow, error::Error, fmt, fs, io, path::Path};
+
+const HEADER: &str = "multisplice 1";
+
+/// FNV-1a hash of a string. It is used to check that a patch is applied to the string it was
+/// created for, so it must be stable across platforms and Rust versions.
+fn hash(source: &str) -> u64 {
+    source.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// An error that occurred while loading a patch.
+#[derive(Debug)]
+pub enum PatchError {
+    /// The patch file could not be read.
+    Io(io::Error),
+    /// The patch is not in the expected format.
+    InvalidFormat,
+    /// The patch was created for a different original string.
+    SourceMismatch,
+    /// Some of the splices overlap, or have ranges that do not fit the original string.
+    Splice(Vec<SpliceError>),
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::Io(err) => write!(f, "Could not read patch: {}", err),
+            PatchError::InvalidFormat => write!(f, "Invalid patch format"),
+            PatchError::SourceMismatch => {
+                write!(f, "The patch was created for a different source string")
+            }
+            PatchError::Splice(errors) => write!(f, "{}", errors[0]),
+        }
+    }
+}
+
+impl Error for PatchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PatchError::Io(err) => Some(err),
+            PatchError::Splice(errors) => Some(&errors[0]),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PatchError {
+    fn from(err: io::Error) -> Self {
+        PatchError::Io(err)
+    }
+}
+
+/// Split off the first line of `text`.
+fn next_line(text: &str) -> Result<(&str, &str), PatchError> {
+    let end = text.find('\n').ok_or(PatchError::InvalidFormat)?;
+    Ok((&text[..end], &text[end + 1..]))
+}
+
+/// Parse whitespace-separated numbers from a line.
+fn numbers<const N: usize>(line: &str) -> Result<[usize; N], PatchError> {
+    let mut result = [0; N];
+    let mut parts = line.split(' ');
+    for number in result.iter_mut() {
+        let part = parts.next().ok_or(PatchError::InvalidFormat)?;
+        *number = part.parse().map_err(|_| PatchError::InvalidFormat)?;
+    }
+    match parts.next() {
+        Some(_) => Err(PatchError::InvalidFormat),
+        None => Ok(result),
+    }
+}
+
+impl<'a> Multisplice<'a> {
+    /// Export the queued splices in the patch format, including a hash of the original string.
+    ///
+    /// Lazy values are computed, so loading the patch gives the same output.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(6, 7, "boop");
+    /// let patch = splicer.to_patch();
+    /// assert_eq!(patch, "multisplice 1 e22588b27bd8c58c 9\n2 3 4\nbeep\n6 7 4\nboop\n");
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice_lazy(2, 3, || "beep".to_string());
+    /// let patch = splicer.to_patch();
+    /// let loaded = Multisplice::from_patch("a b c d e", &patch).unwrap();
+    /// assert_eq!(loaded.to_string(), "a beep c d e");
+    /// ```
+    pub fn to_patch(&self) -> String {
+        let mut patch = format!(
+            "{} {:016x} {}\n",
+            HEADER,
+            hash(self.source),
+            self.source.len()
+        );
+        for s in &self.splices {
+            let value = match &s.meta().lazy {
+                Some(lazy) => Cow::Owned(lazy.0.compute()),
+                None => Cow::Borrowed(&*s.value),
+            };
+            patch.push_str(&format!("{} {} {}\n", s.start(), s.end(), value.len()));
+            patch.push_str(&value);
+            patch.push('\n');
+        }
+        patch
+    }
+
+    /// Create a "multisplicer" for the given string, with the splices from `patch` already
+    /// queued. Replacement values borrow from `patch`.
+    ///
+    /// Returns an error if the patch was created for a different original string, or if its
+    /// ranges do not fit the original string: [`PatchError::Splice`] reports ranges that end
+    /// after the end of the string or start or end inside a UTF-8 character, which can happen if
+    /// the patch was edited by hand.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, PatchError, SpliceError};
+    ///
+    /// let patch = "multisplice 1 e22588b27bd8c58c 9\n2 3 4\nbeep\n6 7 4\nboop\n";
+    /// let splicer = Multisplice::from_patch("a b c d e", patch).unwrap();
+    /// assert_eq!(splicer.to_string(), "a beep c boop e");
+    ///
+    /// let error = Multisplice::from_patch("a b c d f", patch).unwrap_err();
+    /// assert!(matches!(error, PatchError::SourceMismatch));
+    ///
+    /// let patch = "multisplice 1 e22588b27bd8c58c 9\n6 12 4\nboop\n";
+    /// let error = Multisplice::from_patch("a b c d e", patch).unwrap_err();
+    /// assert!(matches!(
+    ///     error,
+    ///     PatchError::Splice(errors) if errors == [SpliceError::OutOfBounds { new: 6..12, len: 9 }]
+    /// ));
+    /// ```
+    pub fn from_patch(source: &'a str, patch: &'a str) -> Result<Self, PatchError> {
+        let (header, mut rest) = next_line(patch)?;
+        let expected = format!("{} {:016x} {}", HEADER, hash(source), source.len());
+        if header != expected {
+            return match header.strip_prefix(HEADER) {
+                Some(_) => Err(PatchError::SourceMismatch),
+                None => Err(PatchError::InvalidFormat),
+            };
+        }
+
+        let mut edits = vec![];
+        while !rest.is_empty() {
+            let (line, after) = next_line(rest)?;
+            let [start, end, len] = numbers(line)?;
+            let value = after.get(..len).ok_or(PatchError::InvalidFormat)?;
+            rest = after[len..]
+                .strip_prefix('\n')
+                .ok_or(PatchError::InvalidFormat)?;
+            edits.push((start..end, Cow::Borrowed(value)));
+        }
+        Self::from_edits(source, edits).map_err(PatchError::Splice)
+    }
+
+    /// Save the queued splices to a file in the patch format.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let path = std::env::temp_dir().join("multisplice-save-example.patch");
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.save(&path).unwrap();
+    ///
+    /// let loaded = Multisplice::load(&path, "a b c d e").unwrap();
+    /// assert_eq!(loaded.to_string(), "a beep c d e");
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_patch())
+    }
+
+    /// Load splices for the given string from a file in the patch format.
+    ///
+    /// Returns an error if the patch was created for a different original string.
+    pub fn load(path: impl AsRef<Path>, source: &'a str) -> Result<Self, PatchError> {
+        let patch = fs::read_to_string(path)?;
+        let splicer = Multisplice::from_patch(source, &patch)?;
+        let edits = splicer
+            .into_iter()
+            .map(|(range, value)| (range, Cow::Owned(value.into_owned())))
+            .collect::<Vec<_>>();
+        Self::from_edits(source, edits).map_err(PatchError::Splice)
+    }
+}
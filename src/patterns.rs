@@ -0,0 +1,49 @@
+//! Splicing the matches of many patterns at once.
+
+use crate::{Multisplice, SpliceError};
+use aho_corasick::{AhoCorasick, Match};
+use std::borrow::Cow;
+
+impl<'a> Multisplice<'a> {
+    /// Find all matches of `patterns` in the original string in a single scan, and replace the
+    /// ones for which `replace` returns a value.
+    ///
+    /// Matches do not overlap; which of several overlapping matches is found depends on the
+    /// automaton's [`MatchKind`](aho_corasick::MatchKind). The new splices are queued like
+    /// [`Multisplice::splice_many`] does, so if any of them conflict with splices that were
+    /// already queued, none are applied.
+    ///
+    /// # Panics
+    /// Panics in the same cases as [`AhoCorasick::find_iter`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use aho_corasick::AhoCorasick;
+    /// use multisplice::Multisplice;
+    ///
+    /// let patterns = AhoCorasick::new(["apple", "banana", "cherry"]).unwrap();
+    /// let mut splicer = Multisplice::new("apple, banana and cherry");
+    /// splicer
+    ///     .splice_matches(&patterns, |m| match m.pattern().as_usize() {
+    ///         0 => Some("pear"),
+    ///         2 => Some("plum"),
+    ///         _ => None,
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(splicer.to_string(), "pear, banana and plum");
+    /// ```
+    pub fn splice_matches<V>(
+        &mut self,
+        patterns: &AhoCorasick,
+        mut replace: impl FnMut(&Match) -> Option<V>,
+    ) -> Result<(), Vec<SpliceError>>
+    where
+        V: Into<Cow<'a, str>>,
+    {
+        let edits = patterns
+            .find_iter(self.source)
+            .filter_map(|m| replace(&m).map(|value| (m.range(), value)))
+            .collect::<Vec<_>>();
+        self.splice_many(edits)
+    }
+}
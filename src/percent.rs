@@ -0,0 +1,123 @@
+//! Percent-encoding replacement values that are spliced into URLs.
+
+use crate::{Multisplice, SpliceId};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use std::borrow::Cow;
+
+/// Characters that are percent-encoded in URL fragments.
+const FRAGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'`');
+/// Characters that are percent-encoded in URL paths.
+const PATH: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+/// Characters that are percent-encoded in URL query values.
+const QUERY: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'&')
+    .add(b'\'')
+    .add(b'+')
+    .add(b'<')
+    .add(b'=')
+    .add(b'>');
+
+/// A part of a URL, which determines the characters that are percent-encoded in it.
+///
+/// The character sets are based on the ones from the [WHATWG URL standard](https://url.spec.whatwg.org/#percent-encoded-bytes),
+/// using the query set for special schemes like `http`. They also encode `%`, so values are
+/// always taken literally. The query set also encodes `&`, `=` and `+`, so a spliced value can
+/// not start a new query parameter: it is meant for a single key or value, not a whole query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UrlComponent {
+    /// The path, after the host.
+    Path,
+    /// A key or value in the query, after `?`.
+    Query,
+    /// The fragment, after `#`.
+    Fragment,
+}
+
+impl UrlComponent {
+    /// The characters that are percent-encoded in this part of a URL, in addition to all
+    /// non-ASCII characters.
+    #[inline]
+    pub fn ascii_set(self) -> &'static AsciiSet {
+        match self {
+            UrlComponent::Path => PATH,
+            UrlComponent::Query => QUERY,
+            UrlComponent::Fragment => FRAGMENT,
+        }
+    }
+
+    /// Percent-encode `value` for this part of a URL.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::UrlComponent;
+    ///
+    /// assert_eq!(UrlComponent::Path.encode("a b?c"), "a%20b%3Fc");
+    /// assert_eq!(UrlComponent::Query.encode("a b?c"), "a%20b?c");
+    /// assert_eq!(UrlComponent::Query.encode("100%25 a&b=c"), "100%2525%20a%26b%3Dc");
+    /// ```
+    pub fn encode<'v>(self, value: &'v str) -> Cow<'v, str> {
+        utf8_percent_encode(value, self.ascii_set()).into()
+    }
+}
+
+impl<'a> Multisplice<'a> {
+    /// Replace the characters from index `start` up to (but not including) index `end` by the
+    /// string `value`, percent-encoded for the `component` part of a URL.
+    ///
+    /// `value` is taken literally: a `%` in it is encoded as well, so values that are already
+    /// percent-encoded are encoded again. A query value can not add query parameters.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, UrlComponent};
+    ///
+    /// let mut splicer = Multisplice::new("https://example.com/PATH?q=QUERY#FRAGMENT");
+    /// splicer.splice_percent_encoded(20, 24, "docs/über uns", UrlComponent::Path);
+    /// splicer.splice_percent_encoded(27, 32, "\"a\" & <b>", UrlComponent::Query);
+    /// splicer.splice_percent_encoded(33, 41, "see `x`", UrlComponent::Fragment);
+    /// assert_eq!(
+    ///     splicer.to_string(),
+    ///     "https://example.com/docs/%C3%BCber%20uns?q=%22a%22%20%26%20%3Cb%3E#see%20%60x%60",
+    /// );
+    ///
+    /// let mut splicer = Multisplice::new("https://example.com/search?q=QUERY");
+    /// splicer.splice_percent_encoded(29, 34, "a&admin=1", UrlComponent::Query);
+    /// assert_eq!(splicer.to_string(), "https://example.com/search?q=a%26admin%3D1");
+    /// ```
+    pub fn splice_percent_encoded(
+        &mut self,
+        start: usize,
+        end: usize,
+        value: impl Into<Cow<'a, str>>,
+        component: UrlComponent,
+    ) -> SpliceId {
+        let value = match value.into() {
+            Cow::Borrowed(value) => component.encode(value),
+            Cow::Owned(value) => match component.encode(&value) {
+                Cow::Borrowed(_) => Cow::Owned(value),
+                Cow::Owned(encoded) => Cow::Owned(encoded),
+            },
+        };
+        self.splice_cow(start, end, value)
+    }
+}
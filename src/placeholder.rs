@@ -0,0 +1,140 @@
+//! Filling in `{{key}}`-style placeholders.
+
+use crate::{Multisplice, SpliceError};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+/// An error that occurred while splicing placeholders.
+///
+/// Returned by [`Multisplice::splice_placeholders`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlaceholderError {
+    /// A placeholder names a key that has no value.
+    UnknownKey {
+        /// The key, without surrounding whitespace.
+        key: String,
+        /// The range of the placeholder, including its delimiters.
+        range: Range<usize>,
+    },
+    /// A placeholder was opened but never closed.
+    Unclosed {
+        /// The index of the opening delimiter.
+        start: usize,
+    },
+    /// The opening or closing delimiter is empty.
+    EmptyDelimiter,
+    /// The placeholders could not be spliced.
+    Splice(Vec<SpliceError>),
+}
+
+impl fmt::Display for PlaceholderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlaceholderError::UnknownKey { key, range } => {
+                write!(f, "Unknown placeholder {:?} at {:?}", key, range)
+            }
+            PlaceholderError::Unclosed { start } => {
+                write!(f, "Unclosed placeholder at index {}", start)
+            }
+            PlaceholderError::EmptyDelimiter => {
+                f.write_str("Placeholder delimiters can not be empty")
+            }
+            PlaceholderError::Splice(errors) => match errors.first() {
+                Some(error) => write!(f, "Could not splice placeholders: {}", error),
+                None => f.write_str("Could not splice placeholders"),
+            },
+        }
+    }
+}
+
+impl Error for PlaceholderError {}
+
+impl<'a> Multisplice<'a> {
+    /// Replace every `{{key}}` placeholder in the original string by the value for `key` in
+    /// `values`. Whitespace around the key is ignored.
+    ///
+    /// If any placeholder is unclosed or has no value, none of them are spliced.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, PlaceholderError};
+    /// use std::collections::HashMap;
+    ///
+    /// let values = HashMap::from([("name", "world"), ("greeting", "Hello")]);
+    /// let mut splicer = Multisplice::new("{{greeting}}, {{ name }}!");
+    /// splicer.splice_placeholders(&values).unwrap();
+    /// assert_eq!(splicer.to_string(), "Hello, world!");
+    ///
+    /// let mut splicer = Multisplice::new("{{greeting}}, {{ you }}!");
+    /// assert_eq!(splicer.splice_placeholders(&values), Err(PlaceholderError::UnknownKey {
+    ///     key: "you".to_string(),
+    ///     range: 14..23,
+    /// }));
+    /// assert_eq!(splicer.to_string(), "{{greeting}}, {{ you }}!");
+    /// ```
+    pub fn splice_placeholders(
+        &mut self,
+        values: &HashMap<&str, &'a str>,
+    ) -> Result<(), PlaceholderError> {
+        self.splice_placeholders_with("{{", "}}", values)
+    }
+
+    /// Replace every placeholder in the original string that is delimited by `open` and `close`
+    /// by its value in `values`. Whitespace around the key is ignored.
+    ///
+    /// If any placeholder is unclosed or has no value, none of them are spliced. Returns
+    /// [`PlaceholderError::EmptyDelimiter`] if `open` or `close` is empty.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, PlaceholderError};
+    /// use std::collections::HashMap;
+    ///
+    /// let values = HashMap::from([("name", "world")]);
+    /// let mut splicer = Multisplice::new("Hello, ${name}! {{name}}");
+    /// splicer.splice_placeholders_with("${", "}", &values).unwrap();
+    /// assert_eq!(splicer.to_string(), "Hello, world! {{name}}");
+    ///
+    /// let values = HashMap::from([("", "x")]);
+    /// assert_eq!(
+    ///     splicer.splice_placeholders_with("", "", &values),
+    ///     Err(PlaceholderError::EmptyDelimiter),
+    /// );
+    /// ```
+    pub fn splice_placeholders_with(
+        &mut self,
+        open: &str,
+        close: &str,
+        values: &HashMap<&str, &'a str>,
+    ) -> Result<(), PlaceholderError> {
+        if open.is_empty() || close.is_empty() {
+            return Err(PlaceholderError::EmptyDelimiter);
+        }
+        let source = self.source;
+        let mut edits = vec![];
+        let mut last = 0;
+        while let Some(offset) = source[last..].find(open) {
+            let start = last + offset;
+            let key_start = start + open.len();
+            let key_end = match source[key_start..].find(close) {
+                Some(len) => key_start + len,
+                None => return Err(PlaceholderError::Unclosed { start }),
+            };
+            let end = key_end + close.len();
+            let key = source[key_start..key_end].trim();
+            match values.get(key) {
+                Some(value) => edits.push((start..end, *value)),
+                None => {
+                    return Err(PlaceholderError::UnknownKey {
+                        key: key.to_string(),
+                        range: start..end,
+                    })
+                }
+            }
+            last = end;
+        }
+        self.splice_many(edits).map_err(PlaceholderError::Splice)
+    }
+}
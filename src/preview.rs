@@ -0,0 +1,95 @@
+//! Colored terminal previews of the queued splices.
+
+use crate::{Chunk, Multisplice};
+use std::fmt::Write;
+
+/// Number of unchanged lines to show around each change.
+const CONTEXT_LINES: usize = 2;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+impl Multisplice<'_> {
+    /// Render an ANSI-colored before and after view of each queued splice, with a few lines of
+    /// context around it.
+    ///
+    /// Each change starts with a header containing its line number in the original string.
+    /// Removed lines are red and prefixed with `-`, added lines are green and prefixed with `+`.
+    /// Notes attached with [`Multisplice::splice_with_note`] are shown below the header,
+    /// prefixed with `#`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a\nb\nc\nd");
+    /// splicer.splice(4, 5, "beep");
+    /// assert_eq!(
+    ///     splicer.preview_diff(),
+    ///     "\x1b[36m@@ line 3 @@\x1b[0m\n  a\n  b\n\x1b[31m- c\x1b[0m\n\x1b[32m+ beep\x1b[0m\n  d\n",
+    /// );
+    ///
+    /// let mut splicer = Multisplice::new("a\nb");
+    /// splicer.splice_with_note(2..3, "beep", "b is deprecated");
+    /// assert_eq!(
+    ///     splicer.preview_diff(),
+    ///     "\x1b[36m@@ line 2 @@\x1b[0m\n\x1b[36m# b is deprecated\x1b[0m\n  a\n\x1b[31m- b\x1b[0m\n\x1b[32m+ beep\x1b[0m\n",
+    /// );
+    /// ```
+    pub fn preview_diff(&self) -> String {
+        let source = self.source;
+        let mut output = String::new();
+        for chunk in self.chunks(0, source.len()) {
+            let index = match chunk {
+                Chunk::Splice(index) => index,
+                Chunk::Source(_) => continue,
+            };
+            let splice = &self.splices[index];
+            let line_start = source[..splice.start()]
+                .rfind('\n')
+                .map_or(0, |newline| newline + 1);
+            let line_end = source[splice.end()..]
+                .find('\n')
+                .map_or(source.len(), |newline| splice.end() + newline);
+            let before = &source[line_start..line_end];
+            let after = format!(
+                "{}{}{}",
+                &source[line_start..splice.start()],
+                self.chunk_str(&chunk),
+                &source[splice.end()..line_end]
+            );
+            if before == after {
+                continue;
+            }
+
+            let line = source[..line_start].matches('\n').count() + 1;
+            let _ = writeln!(output, "{}@@ line {} @@{}", CYAN, line, RESET);
+            if let Some(note) = &splice.meta().note {
+                for note in note.split('\n') {
+                    let _ = writeln!(output, "{}# {}{}", CYAN, note, RESET);
+                }
+            }
+            let context_before = source[..line_start.saturating_sub(1)]
+                .rsplit('\n')
+                .take(if line_start == 0 { 0 } else { CONTEXT_LINES })
+                .collect::<Vec<_>>();
+            for context in context_before.into_iter().rev() {
+                let _ = writeln!(output, "  {}", context);
+            }
+            for removed in before.split('\n') {
+                let _ = writeln!(output, "{}- {}{}", RED, removed, RESET);
+            }
+            for added in after.split('\n') {
+                let _ = writeln!(output, "{}+ {}{}", GREEN, added, RESET);
+            }
+            if line_end < source.len() {
+                for context in source[line_end + 1..].split('\n').take(CONTEXT_LINES) {
+                    let _ = writeln!(output, "  {}", context);
+                }
+            }
+        }
+        output
+    }
+}
@@ -0,0 +1,83 @@
+//! Streaming the output through `std::io`.
+
+use crate::{Multisplice, Segments};
+use std::io::{self, BufRead, Read, Write};
+
+/// Reads the output of a [`Multisplice`] without building the new string.
+///
+/// Returned by [`Multisplice::reader`].
+#[derive(Debug)]
+pub struct Reader<'s, 'a> {
+    segments: Segments<'s, 'a>,
+    /// The unread part of the current segment.
+    current: &'s [u8],
+}
+
+impl<'a> Multisplice<'a> {
+    /// Get a reader that streams the output, one segment at a time.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    /// use std::io::Read;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(6, 7, "boop");
+    /// let mut output = String::new();
+    /// splicer.reader().read_to_string(&mut output).unwrap();
+    /// assert_eq!(output, "a beep c boop e");
+    /// ```
+    #[inline]
+    pub fn reader(&self) -> Reader<'_, 'a> {
+        Reader {
+            segments: self.segments(),
+            current: &[],
+        }
+    }
+
+    /// Write the output to a writer, one segment at a time, without building the new string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(6, 7, "boop");
+    /// let mut output = vec![];
+    /// splicer.write_to(&mut output).unwrap();
+    /// assert_eq!(output, b"a beep c boop e");
+    /// ```
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        for segment in self.segments() {
+            writer.write_all(segment.as_bytes())?;
+        }
+        writer.flush()
+    }
+}
+
+impl Read for Reader<'_, '_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl BufRead for Reader<'_, '_> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.current.is_empty() {
+            if let Some(segment) = self.segments.next() {
+                self.current = segment.as_bytes();
+            }
+        }
+        Ok(self.current)
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.current = &self.current[amount..];
+    }
+}
@@ -0,0 +1,307 @@
+//! Moving queued splices onto a changed version of the original string.
+
+use crate::{Multisplice, Splice, SpliceId};
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// Options for moving splices onto a changed string with [`Multisplice::rebase_with`].
+#[derive(Debug, Clone)]
+pub struct RebaseOptions {
+    /// Number of bytes of context before and after each splice that must match.
+    context: usize,
+    /// How far a splice may move from its old position.
+    max_drift: Option<usize>,
+    /// Whether runs of whitespace match any other run of whitespace.
+    ignore_whitespace: bool,
+}
+
+impl Default for RebaseOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RebaseOptions {
+    /// Create the default options: 16 bytes of context, no limit on how far splices may move,
+    /// and exact whitespace matching.
+    #[inline]
+    pub fn new() -> Self {
+        RebaseOptions {
+            context: 16,
+            max_drift: None,
+            ignore_whitespace: false,
+        }
+    }
+
+    /// Set the number of bytes of context before and after each splice that must match in the
+    /// new string. Less context recovers more splices, but is more likely to put them in the
+    /// wrong place.
+    #[inline]
+    pub fn context(mut self, context: usize) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Set how many bytes a splice may move from its old position. Splices that would move
+    /// further fail.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, RebaseOptions};
+    ///
+    /// let mut splicer = Multisplice::new("a b c");
+    /// splicer.splice(2, 3, "beep");
+    /// let options = RebaseOptions::new().max_drift(4);
+    /// let rebased = splicer.rebase_with("// a long header\na b c", &options);
+    /// assert_eq!(rebased.failed.len(), 1);
+    /// ```
+    #[inline]
+    pub fn max_drift(mut self, max_drift: usize) -> Self {
+        self.max_drift = Some(max_drift);
+        self
+    }
+
+    /// Let any run of whitespace in the context match any other run of whitespace, so changes
+    /// in indentation and line breaks do not prevent splices from being moved.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, RebaseOptions};
+    ///
+    /// let mut splicer = Multisplice::new("if (a) { b(); }");
+    /// splicer.splice(9, 10, "beep");
+    /// let options = RebaseOptions::new().ignore_whitespace(true);
+    /// let rebased = splicer.rebase_with("if (a) {\n    b();\n}", &options);
+    /// assert_eq!(rebased.splicer.to_string(), "if (a) {\n    beep();\n}");
+    /// ```
+    #[inline]
+    pub fn ignore_whitespace(mut self, ignore_whitespace: bool) -> Self {
+        self.ignore_whitespace = ignore_whitespace;
+        self
+    }
+}
+
+/// The result of [`Multisplice::rebase`].
+#[derive(Debug)]
+pub struct Rebased<'a> {
+    /// A splicer for the new string, with all the splices that could be moved.
+    pub splicer: Multisplice<'a>,
+    /// The IDs, ranges in the old string, and replacement values of the splices that could not
+    /// be moved, because their context was not found or they would overlap another splice.
+    pub failed: Vec<(SpliceId, Range<usize>, Cow<'a, str>)>,
+}
+
+/// Find the nearest character boundary at or before `index`.
+pub(crate) fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Find the nearest character boundary at or after `index`.
+fn ceil_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+/// Collapse every run of whitespace in `text` to a single space. Also returns the index in
+/// `text` of every byte of the result, plus the length of `text`.
+fn collapse_whitespace(text: &str) -> (String, Vec<usize>) {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut indices = Vec::with_capacity(text.len() + 1);
+    let mut in_whitespace = false;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() {
+            if !in_whitespace {
+                collapsed.push(' ');
+                indices.push(i);
+            }
+            in_whitespace = true;
+            continue;
+        }
+        in_whitespace = false;
+        collapsed.push(c);
+        indices.extend(i..i + c.len_utf8());
+    }
+    indices.push(text.len());
+    (collapsed, indices)
+}
+
+/// The new string, prepared for searching.
+struct Haystack<'s> {
+    /// The text to search in.
+    text: Cow<'s, str>,
+    /// For whitespace-insensitive matching, the index in the new string of every byte of `text`.
+    indices: Option<Vec<usize>>,
+}
+
+impl<'s> Haystack<'s> {
+    fn new(new: &'s str, options: &RebaseOptions) -> Self {
+        if options.ignore_whitespace {
+            let (text, indices) = collapse_whitespace(new);
+            Haystack {
+                text: Cow::Owned(text),
+                indices: Some(indices),
+            }
+        } else {
+            Haystack {
+                text: Cow::Borrowed(new),
+                indices: None,
+            }
+        }
+    }
+
+    /// Get the index in the new string of an index into `text`.
+    #[inline]
+    fn original_index(&self, index: usize) -> usize {
+        match &self.indices {
+            Some(indices) => indices[index],
+            None => index,
+        }
+    }
+
+    /// Find where the range `range` of `old` is in the new string, by searching for it with some
+    /// context around it. If the context appears several times, the occurrence closest to the old
+    /// position wins.
+    fn locate(
+        &self,
+        old: &str,
+        range: &Range<usize>,
+        options: &RebaseOptions,
+    ) -> Option<Range<usize>> {
+        let context_start = floor_char_boundary(old, range.start.saturating_sub(options.context));
+        let context_end = ceil_char_boundary(old, (range.end + options.context).min(old.len()));
+        let needle = &old[context_start..context_end];
+        let (mut start_offset, mut end_offset) =
+            (range.start - context_start, range.end - context_start);
+        let needle = if options.ignore_whitespace {
+            let (needle, indices) = collapse_whitespace(needle);
+            start_offset = indices.partition_point(|i| *i < start_offset);
+            end_offset = indices.partition_point(|i| *i < end_offset);
+            Cow::Owned(needle)
+        } else {
+            Cow::Borrowed(needle)
+        };
+
+        let mut best: Option<usize> = None;
+        let mut end = 0;
+        let mut search_from = 0;
+        while let Some(found) = self.text[search_from..].find(&*needle) {
+            let found = search_from + found;
+            let start = self.original_index(found + start_offset);
+            let drift = start.abs_diff(range.start);
+            let within_limit = options.max_drift.is_none_or(|max_drift| drift <= max_drift);
+            if within_limit && best.is_none_or(|best| drift < best.abs_diff(range.start)) {
+                best = Some(start);
+                end = self.original_index(found + end_offset);
+            }
+            match self.text[found..].chars().next() {
+                Some(c) => search_from = found + c.len_utf8(),
+                None => break,
+            }
+        }
+        best.map(|start| start..end)
+    }
+}
+
+impl<'a> Multisplice<'a> {
+    /// Move the queued splices onto a changed version of the original string.
+    ///
+    /// Each splice is located in `new_source` by searching for the text it replaces together with
+    /// some context around it, like `patch` does. If the context appears several times, the
+    /// occurrence closest to the old position is used. Splices whose context is not found, or that
+    /// would overlap another splice in the new string, are returned in [`Rebased::failed`].
+    ///
+    /// This uses the default [`RebaseOptions`]. Splice IDs stay the same. Anchors and protected ranges are moved the same way; protected
+    /// ranges that are not found are dropped, and anchors that are not found keep their old
+    /// offset, limited to the length of the new string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let old = "fn alpha() { return 1; }\nfn beta() { return 2; }\n";
+    /// let new = "// header\nfn alpha() { return 1; }\nfn gamma() { return 3; }\n";
+    /// let mut splicer = Multisplice::new(old);
+    /// splicer.splice(3, 8, "first");
+    /// splicer.splice(28, 32, "second");
+    /// let rebased = splicer.rebase(new);
+    /// assert_eq!(
+    ///     rebased.splicer.to_string(),
+    ///     "// header\nfn first() { return 1; }\nfn gamma() { return 3; }\n",
+    /// );
+    /// assert_eq!(rebased.failed.len(), 1);
+    /// assert_eq!(rebased.failed[0].1, 28..32);
+    /// ```
+    pub fn rebase(self, new_source: &'a str) -> Rebased<'a> {
+        self.rebase_with(new_source, &RebaseOptions::new())
+    }
+
+    /// Move the queued splices onto a changed version of the original string, with options for
+    /// how splices are located. See [`Multisplice::rebase`].
+    pub fn rebase_with(self, new_source: &'a str, options: &RebaseOptions) -> Rebased<'a> {
+        let old_source = self.source;
+        let haystack = Haystack::new(new_source, options);
+        let locate = |range: &Range<usize>| haystack.locate(old_source, range, options);
+        let mut splicer = Multisplice::new(new_source);
+        splicer.next_id = self.next_id;
+        splicer.rendering = self.rendering;
+        splicer.disabled_groups = self.disabled_groups;
+        splicer.protected = self.protected.iter().filter_map(&locate).collect();
+        splicer.anchors = self
+            .anchors
+            .iter()
+            .map(|offset| match locate(&(*offset..*offset)) {
+                Some(range) => range.start,
+                None => floor_char_boundary(new_source, (*offset).min(new_source.len())),
+            })
+            .collect();
+
+        let mut failed = vec![];
+        for splice in self.splices {
+            let range = splice.range();
+            let new_range = if splice.generated && splice.start() == 0 {
+                Some(0..0)
+            } else if splice.generated && splice.start() == old_source.len() {
+                Some(new_source.len()..new_source.len())
+            } else {
+                locate(&range)
+            };
+            let new_range = new_range.filter(|new_range| {
+                let conflicts = splicer.splices.iter().any(|s| s.overlaps(new_range));
+                let probe = Splice::new(splice.id, new_range.clone(), Cow::Borrowed(""));
+                !conflicts && splicer.check_protected(&probe).is_ok()
+            });
+            match new_range {
+                Some(new_range) => {
+                    let start = crate::to_index(new_range.start);
+                    let end = crate::to_index(new_range.end);
+                    let mut splice = Splice {
+                        start,
+                        end,
+                        ..splice
+                    };
+                    if let Some(meta) = &mut splice.meta {
+                        meta.origin = None;
+                    }
+                    splicer.insert_splice(splice);
+                }
+                None => failed.push((splice.id, range, splice.value)),
+            }
+        }
+
+        let kept = |id: &SpliceId| failed.iter().all(|(failed, _, _)| failed != id);
+        let markers = self.markers.into_iter().filter(|(_, id)| kept(id));
+        let slots = self.slots.into_iter().filter(|(_, id, _)| kept(id));
+        splicer.markers = markers.collect();
+        splicer.slots = slots.collect();
+        splicer.group = self.group;
+        splicer.observer = self.observer;
+        splicer.lenient = self.lenient;
+        Rebased { splicer, failed }
+    }
+}
@@ -0,0 +1,30 @@
+//! Building the output as a rope.
+
+use crate::Multisplice;
+use ropey::{Rope, RopeBuilder};
+
+impl Multisplice<'_> {
+    /// Execute the splices, building the new string as a [`Rope`].
+    ///
+    /// The output is assembled one segment at a time, so it never has to exist as a single flat
+    /// string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c\nd e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.splice(6, 7, "boop");
+    /// let rope = splicer.to_rope();
+    /// assert_eq!(rope, "a beep c\nboop e");
+    /// assert_eq!(rope.len_lines(), 2);
+    /// ```
+    pub fn to_rope(&self) -> Rope {
+        let mut builder = RopeBuilder::new();
+        for segment in self.segments() {
+            builder.append(segment);
+        }
+        builder.finish()
+    }
+}
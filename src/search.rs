@@ -0,0 +1,186 @@
+//! Searching the output without building it.
+
+use crate::{Chunk, Chunks, Multisplice};
+use std::{collections::VecDeque, fmt, ops::Range};
+
+/// A match of a pattern in the output of a [`Multisplice`].
+///
+/// Returned by [`Multisplice::find`] and [`Multisplice::match_indices`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OutputMatch {
+    /// Where the match is in the output.
+    pub output: Range<usize>,
+    /// Where the match is in the original string, if all of it was copied from there unchanged.
+    pub original: Option<Range<usize>>,
+}
+
+impl<'a> Multisplice<'a> {
+    /// Find the first match of `pattern` in the output, without building the new string.
+    ///
+    /// An empty pattern matches nowhere.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, OutputMatch};
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// assert_eq!(splicer.find("c"), Some(OutputMatch { output: 7..8, original: Some(4..5) }));
+    /// assert_eq!(splicer.find("ee"), Some(OutputMatch { output: 3..5, original: None }));
+    /// assert_eq!(splicer.find("b"), Some(OutputMatch { output: 2..3, original: None }));
+    /// assert_eq!(splicer.find("x"), None);
+    /// ```
+    #[inline]
+    pub fn find(&self, pattern: &str) -> Option<OutputMatch> {
+        self.match_indices(pattern).next()
+    }
+
+    /// Iterate over the non-overlapping matches of `pattern` in the output, without building the
+    /// new string.
+    ///
+    /// Matches may span several pieces of the output. Their range in the original string is only
+    /// known if all of the matched text was copied from one place in the original string. An
+    /// empty pattern matches nowhere.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, OutputMatch};
+    ///
+    /// let mut splicer = Multisplice::new("ab ab ab");
+    /// splicer.insert_left(3, "x");
+    /// splicer.splice(6, 8, "a");
+    /// assert_eq!(splicer.to_string(), "ab xab a");
+    /// let matches = splicer.match_indices("a").collect::<Vec<_>>();
+    /// assert_eq!(matches, vec![
+    ///     OutputMatch { output: 0..1, original: Some(0..1) },
+    ///     OutputMatch { output: 4..5, original: Some(3..4) },
+    ///     OutputMatch { output: 7..8, original: None },
+    /// ]);
+    ///
+    /// let matches = splicer.match_indices("ab ").collect::<Vec<_>>();
+    /// assert_eq!(matches, vec![
+    ///     OutputMatch { output: 0..3, original: Some(0..3) },
+    ///     OutputMatch { output: 4..7, original: Some(3..6) },
+    /// ]);
+    /// assert_eq!(splicer.find("xab a"), Some(OutputMatch { output: 3..8, original: None }));
+    /// ```
+    pub fn match_indices<'s, 'p>(&'s self, pattern: &'p str) -> MatchIndices<'s, 'a, 'p> {
+        MatchIndices {
+            chunks: self.chunks(0, self.source.len()),
+            pattern,
+            buffer: String::new(),
+            offset: 0,
+            pieces: VecDeque::new(),
+            search: 0,
+        }
+    }
+}
+
+/// An iterator over the matches of a pattern in the output of a [`Multisplice`].
+///
+/// Returned by [`Multisplice::match_indices`].
+pub struct MatchIndices<'s, 'a, 'p> {
+    chunks: Chunks<'s, 'a>,
+    pattern: &'p str,
+    /// The part of the output that may still contain matches.
+    buffer: String,
+    /// The index in the output where `buffer` starts.
+    offset: usize,
+    /// Where each piece of `buffer` starts in the output, and for unchanged parts of the original
+    /// string, where it starts in the original string.
+    pieces: VecDeque<(usize, Option<usize>)>,
+    /// The index in `buffer` to continue searching from.
+    search: usize,
+}
+
+impl MatchIndices<'_, '_, '_> {
+    /// Discard the part of the buffer that can no longer be part of a match, and add the text of
+    /// the next chunk to it. Returns false at the end of the output.
+    fn refill(&mut self) -> bool {
+        let splicer = self.chunks.splicer;
+        let chunk = loop {
+            let chunk = match self.chunks.next() {
+                Some(chunk) => chunk,
+                None => return false,
+            };
+            if !splicer.chunk_str(&chunk).is_empty() {
+                break chunk;
+            }
+        };
+
+        let mut keep = self.buffer.len() + 1 - self.pattern.len().min(self.buffer.len() + 1);
+        while !self.buffer.is_char_boundary(keep) {
+            keep += 1;
+        }
+        let drop = keep.max(self.search);
+        self.buffer.drain(..drop);
+        self.offset += drop;
+        self.search = 0;
+        while self.pieces.len() > 1 && self.pieces[1].0 <= self.offset {
+            self.pieces.pop_front();
+        }
+
+        let original = match &chunk {
+            Chunk::Source(range) => Some(range.start),
+            Chunk::Splice(_) => None,
+        };
+        self.pieces
+            .push_back((self.offset + self.buffer.len(), original));
+        self.buffer.push_str(splicer.chunk_str(&chunk));
+        true
+    }
+
+    /// Find where a match in the output is in the original string.
+    fn original(&self, output: &Range<usize>) -> Option<Range<usize>> {
+        let buffer_end = self.offset + self.buffer.len();
+        let mut start = None;
+        let mut expected = None;
+        for (i, &(piece_start, original)) in self.pieces.iter().enumerate() {
+            let piece_end = self.pieces.get(i + 1).map_or(buffer_end, |piece| piece.0);
+            if piece_end <= output.start {
+                continue;
+            }
+            if piece_start >= output.end {
+                break;
+            }
+            let original = original?;
+            match expected {
+                None => start = Some(original + output.start.saturating_sub(piece_start)),
+                Some(expected) if expected != original => return None,
+                Some(_) => (),
+            }
+            expected = Some(original + piece_end - piece_start);
+        }
+        start.map(|start| start..start + output.len())
+    }
+}
+
+impl Iterator for MatchIndices<'_, '_, '_> {
+    type Item = OutputMatch;
+
+    fn next(&mut self) -> Option<OutputMatch> {
+        if self.pattern.is_empty() {
+            return None;
+        }
+        loop {
+            if let Some(position) = self.buffer[self.search..].find(self.pattern) {
+                let start = self.search + position;
+                self.search = start + self.pattern.len();
+                let output = self.offset + start..self.offset + self.search;
+                let original = self.original(&output);
+                return Some(OutputMatch { output, original });
+            }
+            if !self.refill() {
+                return None;
+            }
+        }
+    }
+}
+
+impl fmt::Debug for MatchIndices<'_, '_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MatchIndices")
+            .field("pattern", &self.pattern)
+            .finish_non_exhaustive()
+    }
+}
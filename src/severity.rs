@@ -0,0 +1,97 @@
+//! Confidence levels of splices, for applying only the safe ones.
+
+use crate::{get_end_bound, get_start_bound, Multisplice, Splice, SpliceId};
+use std::borrow::Cow;
+use std::ops::RangeBounds;
+
+/// How confident the author of a splice is that it is correct, like the applicability of rustc
+/// suggestions.
+///
+/// Severities are ordered from least to most confident, so `severity >= Severity::MaybeIncorrect`
+/// selects everything but splices with placeholders and unspecified splices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Nothing is known about whether the splice is correct.
+    Unspecified,
+    /// The replacement value contains placeholders that must be filled in by someone.
+    HasPlaceholders,
+    /// The splice may be wrong, and should be reviewed before it is applied.
+    MaybeIncorrect,
+    /// The splice is definitely correct, and can be applied automatically. Splices queued
+    /// without a severity have this one.
+    MachineApplicable,
+}
+
+impl Default for Severity {
+    #[inline]
+    fn default() -> Self {
+        Severity::MachineApplicable
+    }
+}
+
+impl<'a> Multisplice<'a> {
+    /// Replace a range, recording how confident you are that the change is correct.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, Severity};
+    ///
+    /// let mut splicer = Multisplice::new("let x = 1;");
+    /// let id = splicer.splice_with_severity(4..5, "_x", Severity::MaybeIncorrect);
+    /// assert_eq!(splicer.severity(id), Some(Severity::MaybeIncorrect));
+    /// ```
+    pub fn splice_with_severity(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        value: impl Into<Cow<'a, str>>,
+        severity: Severity,
+    ) -> SpliceId {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        let splice = Splice {
+            severity,
+            ..Splice::new(self.next_id(), start..end, value.into())
+        };
+        self.insert_splice(splice)
+    }
+
+    /// Get the severity of a splice.
+    #[inline]
+    pub fn severity(&self, id: SpliceId) -> Option<Severity> {
+        self.splices.iter().find(|s| s.id == id).map(|s| s.severity)
+    }
+
+    /// Execute only the splices that are at least as confident as `min_severity`, returning the
+    /// new string. The other splices are left queued.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, Severity};
+    ///
+    /// let mut splicer = Multisplice::new("let x = 1 ;");
+    /// splicer.splice(9, 10, "");
+    /// splicer.splice_with_severity(4..5, "_x", Severity::MaybeIncorrect);
+    /// assert_eq!(splicer.render_filtered(Severity::MachineApplicable), "let x = 1;");
+    /// assert_eq!(splicer.render_filtered(Severity::MaybeIncorrect), "let _x = 1;");
+    /// ```
+    pub fn render_filtered(&self, min_severity: Severity) -> String {
+        self.slice_where(0, self.source.len(), &|s| s.severity >= min_severity)
+    }
+
+    /// Remove the queued splices whose severity does not match `predicate`, so only the rest
+    /// are applied.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, Severity};
+    ///
+    /// let mut splicer = Multisplice::new("let x = 1 ;");
+    /// splicer.splice(9, 10, "");
+    /// splicer.splice_with_severity(4..5, "_x", Severity::MaybeIncorrect);
+    /// splicer.apply_filtered(|severity| severity == Severity::MachineApplicable);
+    /// assert_eq!(splicer.to_string(), "let x = 1;");
+    /// ```
+    pub fn apply_filtered(&mut self, mut predicate: impl FnMut(Severity) -> bool) {
+        self.retain_splices(|s| predicate(s.severity));
+    }
+}
@@ -0,0 +1,109 @@
+//! Splicing a reference-counted string, for splicers that must be `'static`.
+
+use crate::{Multisplice, SpliceError};
+use std::{borrow::Cow, ops::Range, sync::Arc};
+
+/// A splicer that shares ownership of its original string.
+///
+/// Unlike [`Multisplice`], which borrows the original string, this is `Send + Sync + 'static`,
+/// so it can be moved into threads and async tasks, and cloned cheaply. It only queues splices;
+/// [`SharedMultisplice::splicer`] builds a `Multisplice` for everything else.
+///
+/// # Example
+/// ```rust
+/// use multisplice::SharedMultisplice;
+/// use std::sync::Arc;
+///
+/// let source: Arc<str> = Arc::from("a b c d e");
+/// let mut splicer = SharedMultisplice::new(source);
+/// splicer.splice(2..3, "beep");
+/// let output = std::thread::spawn(move || {
+///     splicer.splice(6..7, String::from("boop"));
+///     splicer.render()
+/// });
+/// assert_eq!(output.join().unwrap().unwrap(), "a beep c boop e");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedMultisplice {
+    source: Arc<str>,
+    edits: Vec<(Range<usize>, Cow<'static, str>)>,
+}
+
+impl SharedMultisplice {
+    /// Create a splicer for the given string.
+    pub fn new(source: impl Into<Arc<str>>) -> Self {
+        SharedMultisplice {
+            source: source.into(),
+            edits: vec![],
+        }
+    }
+
+    /// Get the original string.
+    #[inline]
+    pub fn source(&self) -> &Arc<str> {
+        &self.source
+    }
+
+    /// Queue a replacement of the characters in `range` by `value`. Empty ranges are insertions,
+    /// like with [`Multisplice::splice`].
+    ///
+    /// Conflicts are not checked until the splices are applied by [`SharedMultisplice::splicer`].
+    pub fn splice(&mut self, range: Range<usize>, value: impl Into<Cow<'static, str>>) {
+        self.edits.push((range, value.into()));
+    }
+
+    /// Build a [`Multisplice`] with the queued splices, borrowing the original string.
+    ///
+    /// Returns the conflicts between the splices instead if there are any, like
+    /// [`Multisplice::splice_many`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{SharedMultisplice, SpliceError};
+    ///
+    /// let mut splicer = SharedMultisplice::new("a b c d e");
+    /// splicer.splice(2..3, "beep");
+    /// assert_eq!(splicer.splicer().unwrap().map_offset(4), 7);
+    ///
+    /// splicer.splice(2..5, "boop");
+    /// assert_eq!(
+    ///     splicer.splicer().unwrap_err(),
+    ///     vec![SpliceError::Overlap { existing: 2..3, new: 2..5 }],
+    /// );
+    /// ```
+    pub fn splicer(&self) -> Result<Multisplice<'_>, Vec<SpliceError>> {
+        let mut splicer = Multisplice::with_capacity(&self.source, self.edits.len());
+        splicer.splice_many(
+            self.edits
+                .iter()
+                .map(|(range, value)| (range.clone(), &**value)),
+        )?;
+        Ok(splicer)
+    }
+
+    /// Execute the splices, returning the new string, or the conflicts between the splices.
+    pub fn render(&self) -> Result<String, Vec<SpliceError>> {
+        Ok(self.splicer()?.into_string())
+    }
+}
+
+impl From<Arc<str>> for SharedMultisplice {
+    #[inline]
+    fn from(source: Arc<str>) -> Self {
+        SharedMultisplice::new(source)
+    }
+}
+
+impl From<String> for SharedMultisplice {
+    #[inline]
+    fn from(source: String) -> Self {
+        SharedMultisplice::new(source)
+    }
+}
+
+impl From<&str> for SharedMultisplice {
+    #[inline]
+    fn from(source: &str) -> Self {
+        SharedMultisplice::new(source)
+    }
+}
@@ -0,0 +1,111 @@
+//! Regions whose replacement is supplied later.
+
+use crate::{history::Edit, Multisplice};
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeBounds;
+use std::sync::OnceLock;
+
+/// An error returned when rendering a splicer with slots that were not filled.
+///
+/// Returned by [`Multisplice::try_render`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnfilledSlots {
+    /// The keys of the slots that were not filled, in the order they were first reserved. Keys
+    /// used for several slots are only listed once.
+    pub keys: Vec<String>,
+}
+
+impl fmt::Display for UnfilledSlots {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Slots were not filled: {}", self.keys.join(", "))
+    }
+}
+
+impl Error for UnfilledSlots {}
+
+impl<'a> Multisplice<'a> {
+    /// Reserve the range `range` of the original string, to be replaced by the value given to
+    /// [`Multisplice::fill`] for `key`.
+    ///
+    /// Until the slot is filled, it renders as an empty string, and
+    /// [`Multisplice::try_render`] fails. The same key can be used for several slots.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("/* imports */\nmain();");
+    /// splicer.slot(0..13, "imports");
+    /// splicer.splice(14, 18, "start");
+    /// assert!(splicer.try_render().is_err());
+    /// splicer.fill("imports", "import start from './start.js';");
+    /// assert_eq!(splicer.try_render().unwrap(), "import start from './start.js';\nstart();");
+    /// ```
+    pub fn slot(&mut self, range: impl RangeBounds<usize>, key: impl Into<Cow<'a, str>>) {
+        let id = self.splice_range(range, "");
+        self.slots.push((key.into(), id, false));
+    }
+
+    /// Fill the slots reserved for `key` with the string `value`.
+    ///
+    /// # Panics
+    /// Panics if there is no slot for `key`.
+    pub fn fill(&mut self, key: &str, value: impl Into<Cow<'a, str>>) {
+        let value = value.into();
+        let mut found = false;
+        self.output = OnceLock::new();
+        self.begin_step();
+        for slot in 0..self.slots.len() {
+            let (slot_key, id, _) = &self.slots[slot];
+            if slot_key != key {
+                continue;
+            }
+            let index = self
+                .splices
+                .iter()
+                .position(|s| s.id == *id)
+                .expect("Trying to fill a slot whose splice does not exist");
+            let (id, filled) = (*id, self.slots[slot].2);
+            self.record_value(index);
+            self.record(|_| Edit::Filled(id, filled));
+            *self.splices[index].value_mut() = value.clone();
+            self.slots[slot].2 = true;
+            found = true;
+        }
+        self.end_step();
+        assert!(
+            found,
+            "Trying to fill a slot that does not exist: {:?}",
+            key
+        );
+    }
+
+    /// Execute the splices, returning the new string, or an error if any slots reserved with
+    /// [`Multisplice::slot`] were not filled.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("{a} {b} {a}");
+    /// splicer.slot(0..3, "a");
+    /// splicer.slot(4..7, "b");
+    /// splicer.slot(8..11, "a");
+    /// let error = splicer.try_render().unwrap_err();
+    /// assert_eq!(error.keys, vec!["a", "b"]);
+    /// ```
+    pub fn try_render(&self) -> Result<Cow<'a, str>, UnfilledSlots> {
+        let mut keys: Vec<String> = vec![];
+        for (key, _, filled) in &self.slots {
+            if !filled && !keys.iter().any(|k| k == key) {
+                keys.push(key.to_string());
+            }
+        }
+        if !keys.is_empty() {
+            return Err(UnfilledSlots { keys });
+        }
+        Ok(self.render())
+    }
+}
@@ -0,0 +1,65 @@
+//! Extracting a part of the original string into a splicer of its own.
+
+use crate::{to_index, Chunk, Multisplice, Splice};
+
+impl<'a> Multisplice<'a> {
+    /// Create a splicer for the part of the original string from index `start` up to (but not
+    /// including) index `end`, with copies of the splices that are rendered in that part.
+    ///
+    /// The splices keep their IDs and are moved to the corresponding indices in the new original
+    /// string, so the snippet can be edited further on its own. Splices that extend past the edges
+    /// of the part are cut off at the edges, so rendering the snippet gives the same result as
+    /// [`Multisplice::slice`]. Replacement values are copied as they are rendered; the escaper,
+    /// render hook, protected ranges and anchors of the splicer are not copied.
+    ///
+    /// # Panics
+    /// Panics if the part is out of bounds or does not lie on character boundaries.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let source = "fn a() { 1 }\nfn b() { 2 }\n";
+    /// let mut splicer = Multisplice::new(source);
+    /// splicer.splice(9, 10, "one");
+    /// splicer.splice(22, 23, "two");
+    ///
+    /// let mut snippet = splicer.snip(13, 26);
+    /// assert_eq!(snippet.original(..), "fn b() { 2 }\n");
+    /// assert_eq!(snippet.to_string(), "fn b() { two }\n");
+    /// snippet.splice(3, 4, "c");
+    /// assert_eq!(snippet.to_string(), "fn c() { two }\n");
+    /// assert_eq!(splicer.to_string(), "fn a() { one }\nfn b() { two }\n");
+    /// ```
+    pub fn snip(&self, start: usize, end: usize) -> Multisplice<'a> {
+        let mut snippet = Multisplice::new(&self.source[start..end]);
+        snippet.next_id = self.next_id;
+        for chunk in self.chunks(start, end) {
+            let splice = match chunk {
+                Chunk::Source(_) => continue,
+                Chunk::Splice(index) => &self.splices[index],
+            };
+            let value = splice.output_cow(&self.rendering);
+            let origin = splice.origin().and_then(|origin| {
+                let copied = origin..origin + value.len();
+                (copied.start >= start && copied.end <= end).then(|| to_index(origin - start))
+            });
+            let mut copy = Splice {
+                id: splice.id,
+                start: to_index(splice.start().clamp(start, end) - start),
+                end: to_index(splice.end().clamp(start, end) - start),
+                side: splice.side,
+                generated: splice.generated,
+                priority: splice.priority,
+                severity: splice.severity,
+                ..Splice::new(splice.id, 0..0, value)
+            };
+            if splice.meta.is_some() {
+                *copy.meta_mut() = splice.meta().copy_details();
+                copy.meta_mut().origin = origin;
+            }
+            snippet.splices.push(copy);
+        }
+        snippet
+    }
+}
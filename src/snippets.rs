@@ -0,0 +1,57 @@
+//! Rendering splices as diagnostics using `annotate-snippets`.
+
+use crate::Multisplice;
+use annotate_snippets::{AnnotationKind, Group, Level, Patch, Renderer, Snippet};
+
+impl Multisplice<'_> {
+    /// Describe each queued splice as an `annotate-snippets` diagnostic, showing the replaced
+    /// range in the original string and the proposed replacement.
+    ///
+    /// `path` is the file name that is shown in the diagnostics.
+    pub fn snippet_groups<'s>(&'s self, path: &'s str) -> Vec<Group<'s>> {
+        self.splices
+            .iter()
+            .map(|s| {
+                let original = &self.source[s.range()];
+                let (title, label) = match (original.is_empty(), s.value.is_empty()) {
+                    (true, _) => (format!("insert {:?}", s.value), "insert here".to_string()),
+                    (false, true) => (format!("remove {:?}", original), "remove this".to_string()),
+                    (false, false) => (
+                        format!("replace {:?} with {:?}", original, s.value),
+                        format!("replace with {:?}", s.value),
+                    ),
+                };
+                Level::HELP
+                    .primary_title(title)
+                    .element(
+                        Snippet::source(self.source)
+                            .path(path)
+                            .annotation(AnnotationKind::Primary.span(s.range()).label(label)),
+                    )
+                    .element(
+                        Snippet::source(self.source)
+                            .path(path)
+                            .patch(Patch::new(s.range(), &*s.value)),
+                    )
+            })
+            .collect()
+    }
+
+    /// Render each queued splice as a compiler-style diagnostic, using the given `renderer`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use annotate_snippets::Renderer;
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("let a = 1;\nlet b = 2;\n");
+    /// splicer.splice(15, 16, "beep");
+    /// let preview = splicer.render_snippets("src/main.rs", &Renderer::plain());
+    /// assert!(preview.contains(r#"replace "b" with "beep""#));
+    /// assert!(preview.contains("src/main.rs:2:5"));
+    /// assert!(preview.contains("let beep = 2;"));
+    /// ```
+    pub fn render_snippets(&self, path: &str, renderer: &Renderer) -> String {
+        renderer.render(&self.snippet_groups(path))
+    }
+}
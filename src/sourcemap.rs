@@ -0,0 +1,603 @@
+//! Source map generation.
+
+use crate::{
+    lines::{utf16_len, LineIndex},
+    vlq::{self, DecodeError},
+    Chunk, Multisplice,
+};
+use std::fmt::Write;
+
+/// Options for generating a source map.
+#[derive(Debug, Clone)]
+pub struct SourceMapOptions {
+    /// The name of the original file.
+    source: String,
+    /// Whether to add a segment for every character of unchanged content.
+    hires: bool,
+    /// Whether to embed the original string in the source map.
+    include_content: bool,
+    /// The name of the generated file.
+    file: Option<String>,
+    /// The path that the source names are relative to.
+    source_root: Option<String>,
+}
+
+impl SourceMapOptions {
+    /// Create options for a source map of the original file named `source`.
+    #[inline]
+    pub fn new(source: impl Into<String>) -> Self {
+        SourceMapOptions {
+            source: source.into(),
+            hires: false,
+            include_content: false,
+            file: None,
+            source_root: None,
+        }
+    }
+
+    /// Embed the original string in the source map, as `sourcesContent`, so tools can show it
+    /// without fetching the original file.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SourceMapOptions};
+    ///
+    /// let splicer = Multisplice::new("a b c");
+    /// let options = SourceMapOptions::new("input.txt").include_content(true);
+    /// let map = splicer.generate_map(&options);
+    /// assert_eq!(map.sources_content, Some(vec!["a b c".to_string()]));
+    /// ```
+    #[inline]
+    pub fn include_content(mut self, include_content: bool) -> Self {
+        self.include_content = include_content;
+        self
+    }
+
+    /// Set the name of the generated file that the source map belongs to.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SourceMapOptions};
+    ///
+    /// let splicer = Multisplice::new("a b c");
+    /// let options = SourceMapOptions::new("input.txt")
+    ///     .file("output.txt")
+    ///     .source_root("src/");
+    /// let map = splicer.generate_map(&options);
+    /// assert_eq!(map.file.as_deref(), Some("output.txt"));
+    /// assert_eq!(map.source_root.as_deref(), Some("src/"));
+    /// ```
+    #[inline]
+    pub fn file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    /// Set the path that the name of the original file is relative to.
+    #[inline]
+    pub fn source_root(mut self, source_root: impl Into<String>) -> Self {
+        self.source_root = Some(source_root.into());
+        self
+    }
+
+    /// Add a segment for every character of unchanged content, instead of only for every line.
+    /// This makes the source map much larger, but lets tools map every position in the output
+    /// exactly.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SourceMapOptions};
+    ///
+    /// let mut splicer = Multisplice::new("ab c");
+    /// splicer.splice(3, 4, "beep");
+    /// let map = splicer.generate_map(&SourceMapOptions::new("input.txt").hires(true));
+    /// assert_eq!(map.mappings, "AAAA,CAAC,CAAC,CAAC");
+    /// ```
+    #[inline]
+    pub fn hires(mut self, hires: bool) -> Self {
+        self.hires = hires;
+        self
+    }
+}
+
+/// A version 3 source map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceMap {
+    /// The name of the generated file.
+    pub file: Option<String>,
+    /// The path that the names of the original files are relative to.
+    pub source_root: Option<String>,
+    /// The names of the original files.
+    pub sources: Vec<String>,
+    /// The contents of the original files, if they are embedded.
+    pub sources_content: Option<Vec<String>>,
+    /// Identifier names used by the mappings.
+    pub names: Vec<String>,
+    /// The encoded mappings.
+    pub mappings: String,
+}
+
+impl SourceMap {
+    /// Decode the mappings into a list of segments, ordered by their position in the generated
+    /// file, so they can be inspected without parsing the base64 VLQ encoding.
+    ///
+    /// # Errors
+    /// Returns an error if the mappings are not valid base64 VLQ.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SourceMapOptions};
+    ///
+    /// let mut splicer = Multisplice::new("a\nb c");
+    /// splicer.splice(4, 5, "beep");
+    /// let map = splicer.generate_map(&SourceMapOptions::new("input.txt"));
+    /// let starts = map
+    ///     .decoded_mappings()
+    ///     .unwrap()
+    ///     .into_iter()
+    ///     .map(|mapping| {
+    ///         let original = mapping.original.unwrap();
+    ///         (mapping.generated_line, mapping.generated_column, original.line, original.column)
+    ///     })
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(starts, vec![(0, 0, 0, 0), (1, 0, 1, 0), (1, 2, 1, 2)]);
+    /// ```
+    pub fn decoded_mappings(&self) -> Result<Vec<Mapping>, DecodeError> {
+        let lines = decode_mappings(&self.mappings)?;
+        let mappings = lines
+            .into_iter()
+            .enumerate()
+            .flat_map(|(line, segments)| {
+                segments.into_iter().map(move |segment| Mapping {
+                    generated_line: line,
+                    generated_column: segment.column,
+                    original: segment.original,
+                })
+            })
+            .collect();
+        Ok(mappings)
+    }
+
+    /// Serialize the source map to JSON.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SourceMapOptions};
+    ///
+    /// let mut splicer = Multisplice::new("a b c");
+    /// splicer.splice(2, 3, "beep");
+    /// let map = splicer.generate_map(&SourceMapOptions::new("input.txt"));
+    /// assert_eq!(
+    ///     map.to_json(),
+    ///     r#"{"version":3,"sources":["input.txt"],"names":[],"mappings":"AAAA,EAAE,IAAC"}"#
+    /// );
+    /// ```
+    ///
+    /// ```rust
+    /// use multisplice::{Multisplice, SourceMapOptions};
+    ///
+    /// let splicer = Multisplice::new("a\nb");
+    /// let options = SourceMapOptions::new("input.txt")
+    ///     .file("output.txt")
+    ///     .include_content(true);
+    /// assert_eq!(
+    ///     splicer.generate_map(&options).to_json(),
+    ///     r#"{"version":3,"file":"output.txt","sources":["input.txt"],"sourcesContent":["a\nb"],"names":[],"mappings":"AAAA;AACA"}"#
+    /// );
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut json = String::from(r#"{"version":3,"#);
+        if let Some(file) = &self.file {
+            json.push_str(r#""file":"#);
+            write_json_string(&mut json, file);
+            json.push(',');
+        }
+        if let Some(source_root) = &self.source_root {
+            json.push_str(r#""sourceRoot":"#);
+            write_json_string(&mut json, source_root);
+            json.push(',');
+        }
+        json.push_str(r#""sources":"#);
+        write_json_array(&mut json, &self.sources);
+        if let Some(sources_content) = &self.sources_content {
+            json.push_str(r#","sourcesContent":"#);
+            write_json_array(&mut json, sources_content);
+        }
+        json.push_str(r#","names":"#);
+        write_json_array(&mut json, &self.names);
+        json.push_str(r#","mappings":"#);
+        write_json_string(&mut json, &self.mappings);
+        json.push('}');
+        json
+    }
+}
+
+fn write_json_array(json: &mut String, items: &[String]) {
+    json.push('[');
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write_json_string(json, item);
+    }
+    json.push(']');
+}
+
+fn write_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+/// A decoded segment of source map mappings, with absolute positions.
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    /// The column in the generated line.
+    column: usize,
+    /// The position in an original file that the segment maps to.
+    original: Option<OriginalPosition>,
+}
+
+/// A position in an original file that a mapping points to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OriginalPosition {
+    /// The index of the original file in [`SourceMap::sources`].
+    pub source: usize,
+    /// The zero-based line in the original file.
+    pub line: usize,
+    /// The zero-based column in the original file, in UTF-16 code units.
+    pub column: usize,
+    /// The index of the original name in [`SourceMap::names`], if the mapping has one.
+    pub name: Option<usize>,
+}
+
+/// A decoded segment of source map mappings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mapping {
+    /// The zero-based line in the generated file.
+    pub generated_line: usize,
+    /// The zero-based column in the generated file, in UTF-16 code units.
+    pub generated_column: usize,
+    /// The position in an original file that the generated position maps to, or `None` if it
+    /// is unmapped.
+    pub original: Option<OriginalPosition>,
+}
+
+/// Decode the mappings of a source map into the segments of every generated line.
+///
+/// Segments with fewer than four values are treated as unmapped.
+fn decode_mappings(mappings: &str) -> Result<Vec<Vec<Segment>>, DecodeError> {
+    let to_index = |value: i64| value.max(0) as usize;
+    let mut prev = [0i64; 4];
+    let mut lines = vec![];
+    for encoded_line in mappings.split(';') {
+        let mut segments = vec![];
+        let mut column = 0;
+        for encoded in encoded_line
+            .split(',')
+            .filter(|encoded| !encoded.is_empty())
+        {
+            let values = vlq::decode(encoded)?;
+            column += values[0];
+            let original = if values.len() >= 4 {
+                for (prev, delta) in prev.iter_mut().zip(&values[1..]) {
+                    *prev += delta;
+                }
+                Some(OriginalPosition {
+                    source: to_index(prev[0]),
+                    line: to_index(prev[1]),
+                    column: to_index(prev[2]),
+                    name: values.get(4).map(|_| to_index(prev[3])),
+                })
+            } else {
+                None
+            };
+            segments.push(Segment {
+                column: to_index(column),
+                original,
+            });
+        }
+        lines.push(segments);
+    }
+    Ok(lines)
+}
+
+/// Encode the segments of every generated line as source map mappings.
+fn encode_mappings(lines: &[Vec<Segment>]) -> String {
+    let mut mappings = String::new();
+    let mut prev = [0i64; 4];
+    for (i, segments) in lines.iter().enumerate() {
+        if i > 0 {
+            mappings.push(';');
+        }
+        let mut prev_column = 0;
+        for (j, segment) in segments.iter().enumerate() {
+            if j > 0 {
+                mappings.push(',');
+            }
+            vlq::encode(segment.column as i64 - prev_column, &mut mappings);
+            prev_column = segment.column as i64;
+            if let Some(original) = segment.original {
+                let values = [original.source, original.line, original.column];
+                for (prev, value) in prev.iter_mut().zip(values.iter().copied()) {
+                    vlq::encode(value as i64 - *prev, &mut mappings);
+                    *prev = value as i64;
+                }
+                if let Some(name) = original.name {
+                    vlq::encode(name as i64 - prev[3], &mut mappings);
+                    prev[3] = name as i64;
+                }
+            }
+        }
+    }
+    mappings
+}
+
+/// Find the segment of `lines` that covers a line and column.
+fn trace(lines: &[Vec<Segment>], line: usize, column: usize) -> Option<&Segment> {
+    let segments = lines.get(line)?;
+    let index = segments.partition_point(|segment| segment.column <= column);
+    index.checked_sub(1).map(|index| &segments[index])
+}
+
+/// Builds the encoded mappings string.
+#[derive(Default)]
+struct MappingsBuilder {
+    mappings: String,
+    /// The current column in the output.
+    column: usize,
+    /// Whether the current line in the output already has a segment.
+    line_has_segment: bool,
+    /// The output column of the previous segment.
+    prev_column: usize,
+    /// The original line and column of the previous segment.
+    prev_original: (usize, usize),
+    /// The name index of the previous segment with a name.
+    prev_name: usize,
+    /// The original names of renamed identifiers.
+    names: Vec<String>,
+}
+
+impl MappingsBuilder {
+    /// Add a segment at the current position, mapping to a line and column in the original
+    /// string, or to nothing.
+    fn segment(&mut self, original: Option<(usize, usize)>) {
+        if self.line_has_segment {
+            self.mappings.push(',');
+        }
+        self.line_has_segment = true;
+        vlq::encode(
+            self.column as i64 - self.prev_column as i64,
+            &mut self.mappings,
+        );
+        self.prev_column = self.column;
+        if let Some((line, column)) = original {
+            // There is only one source file
+            vlq::encode(0, &mut self.mappings);
+            vlq::encode(
+                line as i64 - self.prev_original.0 as i64,
+                &mut self.mappings,
+            );
+            vlq::encode(
+                column as i64 - self.prev_original.1 as i64,
+                &mut self.mappings,
+            );
+            self.prev_original = (line, column);
+        }
+    }
+
+    /// Add a segment at the current position that maps to a renamed identifier at a line and
+    /// column in the original string.
+    fn named_segment(&mut self, original: (usize, usize), name: &str) {
+        self.segment(Some(original));
+        let index = match self.names.iter().position(|existing| existing == name) {
+            Some(index) => index,
+            None => {
+                self.names.push(name.to_string());
+                self.names.len() - 1
+            }
+        };
+        vlq::encode(index as i64 - self.prev_name as i64, &mut self.mappings);
+        self.prev_name = index;
+    }
+
+    /// Move the current position past `text`.
+    fn advance(&mut self, text: &str) {
+        let mut lines = text.split('\n');
+        if let Some(first) = lines.next() {
+            self.column += utf16_len(first);
+        }
+        for line in lines {
+            self.mappings.push(';');
+            self.line_has_segment = false;
+            self.prev_column = 0;
+            self.column = utf16_len(line);
+        }
+    }
+
+    /// Add `text` that was copied from index `start` in the original string, with a segment for
+    /// every line, or for every character if `hires` is set.
+    fn add_original(&mut self, text: &str, start: usize, lines: &LineIndex<'_>, hires: bool) {
+        let mut index = start;
+        for line in text.split_inclusive('\n') {
+            if hires {
+                for (offset, c) in line.char_indices().filter(|&(_, c)| c != '\n') {
+                    self.segment(Some(lines.position(index + offset)));
+                    self.advance(c.encode_utf8(&mut [0; 4]));
+                }
+                if line.ends_with('\n') {
+                    self.advance("\n");
+                }
+            } else {
+                if line != "\n" {
+                    self.segment(Some(lines.position(index)));
+                }
+                self.advance(line);
+            }
+            index += line.len();
+        }
+    }
+}
+
+impl Multisplice<'_> {
+    /// Generate a source map from the output to the original string.
+    ///
+    /// Unchanged parts of the original string, and content that was moved or copied from the
+    /// original string, are mapped line by line, or character by character with
+    /// [`SourceMapOptions::hires`]. Replacements and insertions are mapped to the
+    /// start of the range they replace, with the original name of identifiers renamed by
+    /// [`Multisplice::splice_named`]. Banners, footers and a byte order mark kept by
+    /// [`Multisplice::new_bom_aware`] are not mapped.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SourceMapOptions};
+    ///
+    /// let mut splicer = Multisplice::new("a\nb");
+    /// splicer.add_banner("// hi\n");
+    /// splicer.splice(2, 3, "beep");
+    /// let map = splicer.generate_map(&SourceMapOptions::new("input.txt"));
+    /// assert_eq!(map.sources, vec!["input.txt".to_string()]);
+    /// assert_eq!(map.mappings, "A;AAAA;AACA");
+    /// ```
+    pub fn generate_map(&self, options: &SourceMapOptions) -> SourceMap {
+        let lines = LineIndex::new(self.source);
+        let mut builder = MappingsBuilder::default();
+        if let Some(bom) = self.bom {
+            builder.advance(bom);
+        }
+        for chunk in self.chunks(0, self.source.len()) {
+            match chunk {
+                Chunk::Source(range) => builder.add_original(
+                    &self.source[range.clone()],
+                    range.start,
+                    &lines,
+                    options.hires,
+                ),
+                Chunk::Splice(index) => {
+                    let splice = &self.splices[index];
+                    let value = splice.output(&self.rendering);
+                    if value.is_empty() {
+                        continue;
+                    }
+                    if splice.generated {
+                        builder.segment(None);
+                        builder.advance(value);
+                    } else if let Some(origin) = splice.origin() {
+                        builder.add_original(value, origin, &lines, options.hires);
+                    } else {
+                        let original = lines.position(splice.start());
+                        match &splice.meta().name {
+                            Some(name) => builder.named_segment(original, name),
+                            None => builder.segment(Some(original)),
+                        }
+                        builder.advance(value);
+                    }
+                }
+            }
+        }
+
+        SourceMap {
+            file: options.file.clone(),
+            source_root: options.source_root.clone(),
+            sources: vec![options.source.clone()],
+            sources_content: if options.include_content {
+                Some(vec![self.source.to_string()])
+            } else {
+                None
+            },
+            names: builder.names,
+            mappings: builder.mappings,
+        }
+    }
+
+    /// Generate a source map from the output to the files that the original string was
+    /// generated from, by chaining the mappings of our edits with `input_map`, the source map of
+    /// the original string.
+    ///
+    /// Every position in the output is mapped through `input_map` to the position it came from,
+    /// so tools that combine several transformations can produce a single source map. Positions
+    /// that `input_map` does not map are left unmapped. The sources and source root of
+    /// `input_map` are used instead of the ones in `options`, and its embedded contents are kept
+    /// if `options` includes content. Names of identifiers renamed by
+    /// [`Multisplice::splice_named`] take precedence over names in `input_map`.
+    ///
+    /// # Errors
+    /// Returns an error if the mappings of `input_map` are not valid base64 VLQ.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SourceMapOptions};
+    ///
+    /// // A first tool turned `a b c` into `a beep c`...
+    /// let mut first = Multisplice::new("a b c");
+    /// first.splice(2, 3, "beep");
+    /// let first_map = first.generate_map(&SourceMapOptions::new("input.txt").hires(true));
+    /// let intermediate = first.to_string();
+    ///
+    /// // ...and a second tool turns `a beep c` into `a beep sea`.
+    /// let mut second = Multisplice::new(&intermediate);
+    /// second.splice(7, 8, "sea");
+    /// let options = SourceMapOptions::new("intermediate.txt").file("output.txt");
+    /// let map = second.generate_map_with_input(&options, &first_map).unwrap();
+    /// assert_eq!(map.sources, vec!["input.txt".to_string()]);
+    /// assert_eq!(map.file.as_deref(), Some("output.txt"));
+    /// // `sea` maps to `c` in `input.txt`, at column 4.
+    /// assert_eq!(map.mappings, "AAAA,OAAI");
+    /// ```
+    pub fn generate_map_with_input(
+        &self,
+        options: &SourceMapOptions,
+        input_map: &SourceMap,
+    ) -> Result<SourceMap, DecodeError> {
+        let map = self.generate_map(options);
+        let input = decode_mappings(&input_map.mappings)?;
+        let mut names: Vec<String> = vec![];
+        let mut intern = |name: &str| match names.iter().position(|existing| existing == name) {
+            Some(index) => index,
+            None => {
+                names.push(name.to_string());
+                names.len() - 1
+            }
+        };
+
+        let mut lines = decode_mappings(&map.mappings).expect("generated mappings are valid");
+        for segment in lines.iter_mut().flatten() {
+            segment.original = segment.original.and_then(|original| {
+                let traced = trace(&input, original.line, original.column)?.original?;
+                let name = match original.name {
+                    Some(name) => Some(&map.names[name]),
+                    None => traced.name.and_then(|name| input_map.names.get(name)),
+                };
+                Some(OriginalPosition {
+                    name: name.map(|name| intern(name)),
+                    ..traced
+                })
+            });
+        }
+
+        Ok(SourceMap {
+            file: options.file.clone(),
+            source_root: input_map.source_root.clone(),
+            sources: input_map.sources.clone(),
+            sources_content: input_map
+                .sources_content
+                .clone()
+                .filter(|_| options.include_content),
+            names,
+            mappings: encode_mappings(&lines),
+        })
+    }
+}
@@ -0,0 +1,24 @@
+//! Conversion to the `sourcemap` crate's source map type.
+
+use crate::SourceMap;
+
+impl SourceMap {
+    /// Convert to a [`sourcemap::SourceMap`], to compose or adjust it with other source map
+    /// tooling.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SourceMapOptions};
+    ///
+    /// let mut splicer = Multisplice::new("a b c");
+    /// splicer.splice(2, 3, "beep");
+    /// let map = splicer.generate_map(&SourceMapOptions::new("input.txt")).to_sourcemap();
+    /// let token = map.lookup_token(0, 6).unwrap();
+    /// assert_eq!(token.get_source(), Some("input.txt"));
+    /// assert_eq!((token.get_src_line(), token.get_src_col()), (0, 3));
+    /// ```
+    pub fn to_sourcemap(&self) -> ::sourcemap::SourceMap {
+        ::sourcemap::SourceMap::from_slice(self.to_json().as_bytes())
+            .expect("generated source maps are valid")
+    }
+}
@@ -0,0 +1,158 @@
+//! Splicing by the spans of parsers like `proc_macro2`, `swc_common`, `toml_edit` and
+//! `saphyr-parser`.
+
+use crate::{Multisplice, SpliceId};
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// A span of source code that knows its byte range in the original string.
+pub trait SourceSpan {
+    /// Get the byte range of the span in the original string.
+    fn byte_range(&self) -> Range<usize>;
+}
+
+/// This uses the byte range of the span, so it only works if the span has location information.
+/// That is the case when the tokens were parsed from the source string outside of a procedural
+/// macro.
+#[cfg(feature = "proc-macro2")]
+impl SourceSpan for proc_macro2::Span {
+    #[inline]
+    fn byte_range(&self) -> Range<usize> {
+        proc_macro2::Span::byte_range(self)
+    }
+}
+
+/// The positions in a `swc_common::Span` are global to a `SourceMap`, so the span must be paired
+/// with the file that it points into.
+#[cfg(feature = "swc")]
+impl SourceSpan for (swc_common::Span, &swc_common::SourceFile) {
+    #[inline]
+    fn byte_range(&self) -> Range<usize> {
+        let (span, file) = self;
+        let base = file.start_pos.0;
+        (span.lo.0 - base) as usize..(span.hi.0 - base) as usize
+    }
+}
+
+#[cfg(feature = "toml_edit")]
+macro_rules! toml_source_span {
+    ($($node:ty),*) => {
+        $(
+            /// The spans of `toml_edit` nodes are only known for documents parsed with
+            /// `toml_edit::Document`, and are lost when the nodes are changed.
+            ///
+            /// # Panics
+            /// Panics if the node does not have a span.
+            impl SourceSpan for &$node {
+                #[inline]
+                fn byte_range(&self) -> Range<usize> {
+                    self.span()
+                        .expect("toml_edit node has no span, it must come from a parsed Document")
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "toml_edit")]
+toml_source_span!(
+    toml_edit::Item,
+    toml_edit::Value,
+    toml_edit::Key,
+    toml_edit::Table,
+    toml_edit::Array,
+    toml_edit::InlineTable
+);
+
+/// The positions in a `saphyr_parser::Span` count characters, so the span must be paired with the
+/// string that it points into to find the byte range.
+#[cfg(feature = "saphyr-parser")]
+impl SourceSpan for (saphyr_parser::Span, &str) {
+    fn byte_range(&self) -> Range<usize> {
+        let (span, source) = self;
+        let mut chars = source
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(Some(source.len()));
+        let start = chars.nth(span.start.index()).unwrap_or(source.len());
+        let end = match span.end.index().saturating_sub(span.start.index()) {
+            0 => start,
+            len => chars.nth(len - 1).unwrap_or(source.len()),
+        };
+        start..end
+    }
+}
+
+impl<'a> Multisplice<'a> {
+    /// Replace the source code covered by `span` by the string `value`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "proc-macro2")] {
+    /// use multisplice::Multisplice;
+    /// use proc_macro2::TokenStream;
+    ///
+    /// let source = "let x = 1;";
+    /// let tokens = source.parse::<TokenStream>().unwrap();
+    /// let name = tokens.into_iter().nth(1).unwrap();
+    /// let mut splicer = Multisplice::new(source);
+    /// splicer.splice_span(name.span(), "renamed");
+    /// assert_eq!(splicer.to_string(), "let renamed = 1;");
+    /// # }
+    /// ```
+    ///
+    /// With `swc_common`, pass the span together with its source file:
+    /// ```rust
+    /// # #[cfg(feature = "swc")] {
+    /// use multisplice::Multisplice;
+    /// use swc_common::{sync::Lrc, BytePos, FileName, SourceMap, Span};
+    ///
+    /// let map = SourceMap::default();
+    /// map.new_source_file(Lrc::new(FileName::Anon), "let a = 0;");
+    /// let file = map.new_source_file(Lrc::new(FileName::Anon), "let x = 1;");
+    /// let name = Span::new(file.start_pos + BytePos(4), file.start_pos + BytePos(5));
+    /// let mut splicer = Multisplice::new(&file.src);
+    /// splicer.splice_span((name, &*file), "renamed");
+    /// assert_eq!(splicer.to_string(), "let renamed = 1;");
+    /// # }
+    /// ```
+    ///
+    /// With `toml_edit`, pass a node of a parsed `Document`:
+    /// ```rust
+    /// # #[cfg(feature = "toml_edit")] {
+    /// use multisplice::Multisplice;
+    /// use toml_edit::Document;
+    ///
+    /// let source = "# The package\n[package]\nname = \"beep\" # not boop\n";
+    /// let document = Document::parse(source).unwrap();
+    /// let mut splicer = Multisplice::new(source);
+    /// splicer.splice_span(&document["package"]["name"], "\"boop\"");
+    /// assert_eq!(splicer.to_string(), "# The package\n[package]\nname = \"boop\" # not boop\n");
+    /// # }
+    /// ```
+    ///
+    /// With `saphyr-parser`, pass the span together with the string it points into:
+    /// ```rust
+    /// # #[cfg(feature = "saphyr-parser")] {
+    /// use multisplice::Multisplice;
+    /// use saphyr_parser::{Event, Parser};
+    ///
+    /// let source = "# ünïcode\nname: beep # not boop\n";
+    /// let (_, span) = Parser::new_from_str(source)
+    ///     .map(Result::unwrap)
+    ///     .find(|(event, _)| matches!(event, Event::Scalar(value, ..) if value == "beep"))
+    ///     .unwrap();
+    /// let mut splicer = Multisplice::new(source);
+    /// splicer.splice_span((span, source), "boop");
+    /// assert_eq!(splicer.to_string(), "# ünïcode\nname: boop # not boop\n");
+    /// # }
+    /// ```
+    #[inline]
+    pub fn splice_span(
+        &mut self,
+        span: impl SourceSpan,
+        value: impl Into<Cow<'a, str>>,
+    ) -> SpliceId {
+        self.splice_range(span.byte_range(), value)
+    }
+}
@@ -0,0 +1,89 @@
+//! Escaping replacement values for SQL string literals.
+
+use crate::Multisplice;
+use std::borrow::Cow;
+
+/// How a database escapes characters in string literals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SqlDialect {
+    /// Standard SQL, where a single quote is escaped by doubling it and backslashes have no
+    /// special meaning. Used by PostgreSQL (with `standard_conforming_strings`, the default),
+    /// SQLite, SQL Server and Oracle.
+    Standard,
+    /// MySQL and MariaDB, where backslashes start escape sequences too, unless the
+    /// `NO_BACKSLASH_ESCAPES` SQL mode is enabled.
+    MySql,
+}
+
+/// Escape `value` for a standard SQL string literal.
+fn escape_standard(value: &str) -> Cow<'_, str> {
+    if !value.contains('\'') {
+        return Cow::Borrowed(value);
+    }
+    Cow::Owned(value.replace('\'', "''"))
+}
+
+/// Escape `value` for a MySQL string literal.
+fn escape_mysql(value: &str) -> Cow<'_, str> {
+    if !value.contains(&['\'', '\\', '\0'][..]) {
+        return Cow::Borrowed(value);
+    }
+    let mut escaped = String::with_capacity(value.len() + 2);
+    for c in value.chars() {
+        match c {
+            '\'' => escaped.push_str("''"),
+            '\\' => escaped.push_str("\\\\"),
+            '\0' => escaped.push_str("\\0"),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+impl SqlDialect {
+    /// Escape `value` so it can be placed between the single quotes of a string literal.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::SqlDialect;
+    ///
+    /// assert_eq!(SqlDialect::Standard.escape(r"it's C:\"), r"it''s C:\");
+    /// assert_eq!(SqlDialect::MySql.escape(r"it's C:\"), r"it''s C:\\");
+    /// ```
+    pub fn escape(self, value: &str) -> Cow<'_, str> {
+        match self {
+            SqlDialect::Standard => escape_standard(value),
+            SqlDialect::MySql => escape_mysql(value),
+        }
+    }
+}
+
+impl Multisplice<'_> {
+    /// Escape replacement values for string literals in the SQL `dialect` when rendering them.
+    ///
+    /// The values are not quoted, so they should replace the contents of a string literal in the
+    /// query template. Like with [`Multisplice::set_escaper`], values queued with
+    /// [`Multisplice::splice_raw`] are rendered as-is, which can be used to splice in SQL
+    /// fragments.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SqlDialect};
+    ///
+    /// let template = "SELECT * FROM users WHERE name = '?' ORDER BY ?";
+    /// let mut splicer = Multisplice::new(template);
+    /// splicer.set_sql_escaper(SqlDialect::Standard);
+    /// splicer.splice(34, 35, "'; DROP TABLE users; --");
+    /// splicer.splice_raw(46, 47, "name");
+    /// assert_eq!(
+    ///     splicer.to_string(),
+    ///     "SELECT * FROM users WHERE name = '''; DROP TABLE users; --' ORDER BY name",
+    /// );
+    /// ```
+    pub fn set_sql_escaper(&mut self, dialect: SqlDialect) {
+        match dialect {
+            SqlDialect::Standard => self.set_escaper(escape_standard),
+            SqlDialect::MySql => self.set_escaper(escape_mysql),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Identifying splices across splicers, for reconciling edits proposed by several tools.
+
+use crate::{Conflict, Multisplice, Splice, SpliceId};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt,
+    ops::{Range, RangeBounds},
+};
+
+/// Two splices with the same stable ID that make different changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdConflict<'a> {
+    /// The stable ID of both splices.
+    pub stable_id: Cow<'a, str>,
+    /// The range and value of the splice in the splicer that was merged into.
+    pub ours: (Range<usize>, Cow<'a, str>),
+    /// The range and value of the splice in the splicer that was merged in.
+    pub theirs: (Range<usize>, Cow<'a, str>),
+}
+
+/// An error that occurred while merging two splicers by stable ID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StableMergeConflict<'a> {
+    /// Splices that have the same stable ID, but make different changes.
+    pub id_conflicts: Vec<IdConflict<'a>>,
+    /// Splices with different stable IDs, or without one, that overlap.
+    pub conflicts: Vec<Conflict<'a>>,
+}
+
+impl fmt::Display for StableMergeConflict<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Trying to merge splicers with conflicting splices")?;
+        for (i, conflict) in self.id_conflicts.iter().enumerate() {
+            let separator = if i == 0 { ": " } else { ", " };
+            write!(
+                f,
+                "{}{:?} is {:?} => {:?} and {:?} => {:?}",
+                separator,
+                conflict.stable_id,
+                conflict.ours.0,
+                conflict.ours.1,
+                conflict.theirs.0,
+                conflict.theirs.1
+            )?;
+        }
+        for (i, Conflict { ours, theirs }) in self.conflicts.iter().enumerate() {
+            let separator = if i == 0 && self.id_conflicts.is_empty() {
+                ": "
+            } else {
+                ", "
+            };
+            write!(
+                f,
+                "{}{:?} => {:?} overlaps {:?} => {:?}",
+                separator, ours.0, ours.1, theirs.0, theirs.1
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for StableMergeConflict<'_> {}
+
+impl<'a> Multisplice<'a> {
+    /// Replace a range, giving the splice a stable ID that identifies the same change when it is
+    /// made by different splicers, for example by several tools proposing fixes for a file.
+    ///
+    /// See [`Multisplice::merge_by_stable_id`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("let count = 1");
+    /// let id = splicer.splice_with_stable_id(4..9, "total", "rename-count");
+    /// assert_eq!(splicer.stable_id(id), Some("rename-count"));
+    /// ```
+    pub fn splice_with_stable_id(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        value: impl Into<Cow<'a, str>>,
+        stable_id: impl Into<Cow<'a, str>>,
+    ) -> SpliceId {
+        let start = crate::get_start_bound(range.start_bound());
+        let end = crate::get_end_bound(range.end_bound(), self.source.len());
+        let mut splice = Splice::new(self.next_id(), start..end, value.into());
+        splice.meta_mut().stable_id = Some(stable_id.into());
+        self.insert_splice(splice)
+    }
+
+    /// Get the stable ID of a splice queued with [`Multisplice::splice_with_stable_id`].
+    #[inline]
+    pub fn stable_id(&self, id: SpliceId) -> Option<&str> {
+        self.splices
+            .iter()
+            .find(|s| s.id == id)
+            .and_then(|s| s.meta().stable_id.as_deref())
+    }
+
+    /// Merge the splices from `other` into this splicer, matching up splices by their stable ID.
+    ///
+    /// A splice from `other` with the same stable ID, range and value as a splice in this
+    /// splicer is the same change, and is only kept once. Splices with the same stable ID that
+    /// make different changes are reported as [`IdConflict`]s. The remaining splices are merged
+    /// like [`Multisplice::merge`] does, and overlapping splices among them are reported as
+    /// range conflicts.
+    ///
+    /// # Panics
+    /// Panics if `other` splices a different source string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let source = "let count = 1;";
+    /// let mut first = Multisplice::new(source);
+    /// first.splice_with_stable_id(4..9, "total", "rename-count");
+    /// let mut second = Multisplice::new(source);
+    /// second.splice_with_stable_id(4..9, "total", "rename-count");
+    /// second.splice_with_stable_id(13..14, "", "remove-semicolon");
+    /// let merged = first.merge_by_stable_id(second).unwrap();
+    /// assert_eq!(merged.to_string(), "let total = 1");
+    ///
+    /// let mut first = Multisplice::new(source);
+    /// first.splice_with_stable_id(4..9, "total", "rename-count");
+    /// let mut second = Multisplice::new(source);
+    /// second.splice_with_stable_id(4..9, "amount", "rename-count");
+    /// let error = first.merge_by_stable_id(second).unwrap_err();
+    /// assert_eq!(error.id_conflicts[0].stable_id, "rename-count");
+    /// assert_eq!(error.id_conflicts[0].theirs, (4..9, "amount".into()));
+    /// assert!(error.conflicts.is_empty());
+    /// ```
+    pub fn merge_by_stable_id(
+        self,
+        mut other: Multisplice<'a>,
+    ) -> Result<Multisplice<'a>, StableMergeConflict<'a>> {
+        let ours = self
+            .splices
+            .iter()
+            .filter_map(|s| Some((s.meta().stable_id.as_deref()?, s)))
+            .collect::<HashMap<_, _>>();
+        let mut id_conflicts = vec![];
+        let mut matched = HashSet::new();
+        for theirs in other.splices.iter() {
+            let stable_id = match &theirs.meta().stable_id {
+                Some(stable_id) => stable_id,
+                None => continue,
+            };
+            let ours = match ours.get(stable_id.as_ref()) {
+                Some(ours) => ours,
+                None => continue,
+            };
+            matched.insert(theirs.id);
+            if ours.range() != theirs.range() || ours.value != theirs.value {
+                id_conflicts.push(IdConflict {
+                    stable_id: stable_id.clone(),
+                    ours: (ours.range(), ours.value.clone()),
+                    theirs: (theirs.range(), theirs.value.clone()),
+                });
+            }
+        }
+        other.splices.retain(|s| !matched.contains(&s.id));
+
+        match self.merge(other) {
+            Ok(merged) if id_conflicts.is_empty() => Ok(merged),
+            Ok(_) => Err(StableMergeConflict {
+                id_conflicts,
+                conflicts: vec![],
+            }),
+            Err(error) => Err(StableMergeConflict {
+                id_conflicts,
+                conflicts: error.conflicts,
+            }),
+        }
+    }
+}
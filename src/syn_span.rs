@@ -0,0 +1,50 @@
+//! Splicing by the line and column locations of `syn` syntax tree nodes.
+
+use crate::{lines::LineIndex, Multisplice, SpliceId};
+use std::borrow::Cow;
+use std::ops::Range;
+use syn::spanned::Spanned;
+
+impl<'a> Multisplice<'a> {
+    /// Get the byte range of the source code covered by a `syn` syntax tree node.
+    ///
+    /// Unlike `proc_macro2::Span::byte_range()`, this uses the line and column of the span, so
+    /// it is correct even when other strings were parsed before this one. The node must have been
+    /// parsed from the source string outside of a procedural macro.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let source = "fn main() {\r\n    let ü = 1;\r\n}";
+    /// let item: syn::ItemFn = syn::parse_str(source).unwrap();
+    /// let splicer = Multisplice::new(source);
+    /// let range = splicer.syn_range(&item.block.stmts[0]);
+    /// assert_eq!(&source[range], "let ü = 1;");
+    /// ```
+    pub fn syn_range(&self, node: &impl Spanned) -> Range<usize> {
+        let span = node.span();
+        let lines = LineIndex::new(self.source);
+        let (start, end) = (span.start(), span.end());
+        lines.char_offset(start.line, start.column)..lines.char_offset(end.line, end.column)
+    }
+
+    /// Replace the source code covered by a `syn` syntax tree node by the string `value`.
+    ///
+    /// See [`Multisplice::syn_range`] for how the node is located in the source string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let source = "fn main() { let ü = \"😀\"; drop(ü); }";
+    /// let item: syn::ItemFn = syn::parse_str(source).unwrap();
+    /// let mut splicer = Multisplice::new(source);
+    /// splicer.splice_syn(&item.block.stmts[1], "println!(\"{}\", ü);");
+    /// assert_eq!(splicer.to_string(), "fn main() { let ü = \"😀\"; println!(\"{}\", ü); }");
+    /// ```
+    pub fn splice_syn(&mut self, node: &impl Spanned, value: impl Into<Cow<'a, str>>) -> SpliceId {
+        let range = self.syn_range(node);
+        self.splice_range(range, value)
+    }
+}
@@ -0,0 +1,95 @@
+//! Support code for the [`assert_spliced!`](crate::assert_spliced) macro.
+
+use crate::{Multisplice, Provenance};
+use std::fmt::Write;
+use std::ops::Range;
+
+/// Apply `edits` to `source` and panic with a detailed message if the output is not `expected`.
+///
+/// Use [`assert_spliced!`](crate::assert_spliced) instead of calling this directly.
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_spliced(source: &str, edits: &[(Range<usize>, &str)], expected: &str) {
+    let mut splicer = Multisplice::new(source);
+    for (range, value) in edits {
+        splicer.splice_range(range.clone(), *value);
+    }
+    let actual = splicer.to_string();
+    if actual == expected {
+        return;
+    }
+
+    let mut message = String::from("spliced output does not match\n");
+    let _ = writeln!(message, "  source: {:?}", source);
+    let _ = writeln!(message, "  actual: {:?}", actual);
+    let _ = writeln!(message, "expected: {:?}", expected);
+
+    let difference = actual
+        .bytes()
+        .zip(expected.bytes())
+        .position(|(a, b)| a != b)
+        .unwrap_or_else(|| actual.len().min(expected.len()));
+    let mut position = 0;
+    for (text, provenance) in splicer.segments_with_spans() {
+        if difference < position + text.len() || difference == actual.len() {
+            let _ = match provenance {
+                Provenance::Replacement { original_range, .. } => writeln!(
+                    message,
+                    "first difference at output index {}, in the splice of {:?} by {:?}",
+                    difference, original_range, text
+                ),
+                Provenance::Source(range) => writeln!(
+                    message,
+                    "first difference at output index {}, in the original text at {:?}",
+                    difference, range
+                ),
+            };
+            break;
+        }
+        position += text.len();
+    }
+
+    message.push_str("diff:\n");
+    let actual_lines = actual.split('\n').collect::<Vec<_>>();
+    let expected_lines = expected.split('\n').collect::<Vec<_>>();
+    for i in 0..actual_lines.len().max(expected_lines.len()) {
+        match (expected_lines.get(i), actual_lines.get(i)) {
+            (Some(expected), Some(actual)) if expected == actual => {
+                let _ = writeln!(message, "  {}", actual);
+            }
+            (expected, actual) => {
+                if let Some(expected) = expected {
+                    let _ = writeln!(message, "- {}", expected);
+                }
+                if let Some(actual) = actual {
+                    let _ = writeln!(message, "+ {}", actual);
+                }
+            }
+        }
+    }
+    panic!("{}", message);
+}
+
+/// Assert that splicing a source string with a list of edits produces the expected output.
+///
+/// On failure, the panic message shows a line diff between the expected and the actual output,
+/// and which splice produced the first difference.
+///
+/// # Example
+/// ```rust
+/// use multisplice::assert_spliced;
+///
+/// assert_spliced!("a b c d e", [(2..3, "beep"), (6..7, "boop")], "a beep c boop e");
+/// ```
+///
+/// ```rust,should_panic
+/// use multisplice::assert_spliced;
+///
+/// assert_spliced!("a b c d e", [(2..3, "beep")], "a boop c d e");
+/// ```
+#[macro_export]
+macro_rules! assert_spliced {
+    ($source:expr, [$(($range:expr, $value:expr)),* $(,)?], $expected:expr $(,)?) => {
+        $crate::test_utils::assert_spliced($source, &[$(($range, $value)),*], $expected)
+    };
+}
@@ -0,0 +1,65 @@
+//! Splicing by `text_size` ranges.
+
+use crate::{Multisplice, SpliceId};
+use std::{borrow::Cow, ops::Range};
+use text_size::{TextRange, TextSize};
+
+impl<'a> Multisplice<'a> {
+    /// Replace the characters in the range `range` by the string `value`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    /// use text_size::TextRange;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice_text_range(TextRange::new(2.into(), 3.into()), "beep");
+    /// splicer.splice_text_range(TextRange::at(6.into(), 1.into()), "boop");
+    /// assert_eq!(splicer.to_string(), "a beep c boop e");
+    /// ```
+    #[inline]
+    pub fn splice_text_range(
+        &mut self,
+        range: TextRange,
+        value: impl Into<Cow<'a, str>>,
+    ) -> SpliceId {
+        self.splice_range(Range::<usize>::from(range), value)
+    }
+
+    /// Insert `value` at `offset`, as if by [`Multisplice::insert_right`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    /// use text_size::TextSize;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.insert_text_size(TextSize::of("a b "), "beep ");
+    /// assert_eq!(splicer.to_string(), "a b beep c d e");
+    /// ```
+    #[inline]
+    pub fn insert_text_size(
+        &mut self,
+        offset: TextSize,
+        value: impl Into<Cow<'a, str>>,
+    ) -> SpliceId {
+        self.insert_right(offset.into(), value)
+    }
+
+    /// Get a part of the spliced string, using a range from the original string. See
+    /// [`Multisplice::slice`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    /// use text_size::TextRange;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// assert_eq!(splicer.slice_text_range(TextRange::new(2.into(), 5.into())), "beep c");
+    /// ```
+    #[inline]
+    pub fn slice_text_range(&self, range: TextRange) -> Cow<'a, str> {
+        self.slice(range.start().into(), range.end().into())
+    }
+}
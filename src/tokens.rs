@@ -0,0 +1,85 @@
+//! Splicing sequences of lexer tokens.
+
+use crate::MultispliceVec;
+use std::ops::Range;
+
+/// A token that was lexed from a string.
+pub trait Token {
+    /// The range of the string that the token was lexed from.
+    fn span(&self) -> Range<usize>;
+}
+
+impl<T: Token + Clone> MultispliceVec<'_, T> {
+    /// Execute the splices and render the new tokens as text, using the text of each token's span
+    /// in `source`.
+    ///
+    /// Whitespace and comments between two tokens that are still next to each other in the
+    /// output are kept, as well as any text before the first token and after the last token.
+    /// The text around a replaced range of tokens is kept as well. Other tokens are separated by
+    /// `separator`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{MultispliceVec, Token};
+    /// use std::ops::Range;
+    ///
+    /// #[derive(Clone)]
+    /// struct Word(Range<usize>);
+    /// impl Token for Word {
+    ///     fn span(&self) -> Range<usize> {
+    ///         self.0.clone()
+    ///     }
+    /// }
+    ///
+    /// let source = "let  x = y;";
+    /// let tokens = [Word(0..3), Word(5..6), Word(7..8), Word(9..10), Word(10..11)];
+    /// let mut splicer = MultispliceVec::new(&tokens);
+    /// // Swap `x` and `y`
+    /// splicer.splice(1..2, vec![tokens[3].clone()]);
+    /// splicer.splice(3..4, vec![tokens[1].clone()]);
+    /// assert_eq!(splicer.render_tokens(source, " "), "let  y = x;");
+    /// ```
+    pub fn render_tokens(&self, source: &str, separator: &str) -> String {
+        let tokens = self.source;
+        let mut output = String::new();
+        // The original index of the token whose trailing text may follow the previous output token
+        let mut prev: Option<Option<usize>> = None;
+        // `left` and `right` are the original indices of the tokens whose surrounding text may be
+        // placed before and after this output token.
+        let mut push = |token: &T, left: Option<usize>, right: Option<usize>| {
+            match (prev, left) {
+                (None, Some(0)) => output.push_str(&source[..tokens[0].span().start]),
+                (None, _) => (),
+                (Some(Some(prev_index)), Some(index)) if index == prev_index + 1 => output
+                    .push_str(&source[tokens[prev_index].span().end..tokens[index].span().start]),
+                (Some(_), _) => output.push_str(separator),
+            }
+            output.push_str(&source[token.span()]);
+            prev = Some(right);
+        };
+
+        let mut last = 0;
+        for (range, value) in &self.splices {
+            for (index, token) in tokens.iter().enumerate().take(range.start).skip(last) {
+                push(token, Some(index), Some(index));
+            }
+            for (i, token) in value.iter().enumerate() {
+                let is_replacement = !range.is_empty();
+                let left = Some(range.start).filter(|_| is_replacement && i == 0);
+                let right = Some(range.end.wrapping_sub(1))
+                    .filter(|_| is_replacement && i == value.len() - 1);
+                push(token, left, right);
+            }
+            last = range.end;
+        }
+        for (index, token) in tokens.iter().enumerate().skip(last) {
+            push(token, Some(index), Some(index));
+        }
+        if let Some(Some(index)) = prev {
+            if index == tokens.len() - 1 {
+                output.push_str(&source[tokens[index].span().end..]);
+            }
+        }
+        output
+    }
+}
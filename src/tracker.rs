@@ -0,0 +1,116 @@
+//! Mapping many positions in the original string to the output.
+
+use crate::{Chunk, Multisplice};
+
+/// A part of the original string, and where it ends up in the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Piece {
+    /// Where the part starts in the original string.
+    start: usize,
+    /// Where the part ends in the original string.
+    end: usize,
+    /// Where the part, or its replacement, starts in the output.
+    output_start: usize,
+    /// Whether the part was replaced, so every index in it maps to the start of the replacement.
+    replaced: bool,
+}
+
+/// Maps indices into the original string to the output of a [`Multisplice`], in logarithmic
+/// time.
+///
+/// Created by [`Multisplice::offset_tracker`]. The tracker does not borrow the splicer, and
+/// does not change when more splices are queued.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffsetTracker {
+    /// The parts of the original string, in order.
+    pieces: Vec<Piece>,
+    /// The length of the original string.
+    source_len: usize,
+    /// The length of the output.
+    output_len: usize,
+}
+
+impl OffsetTracker {
+    /// Find where an index into the original string ends up in the output, like
+    /// [`Multisplice::map_offset`] does.
+    ///
+    /// # Panics
+    /// Panics if `offset` is past the end of the original string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c d e");
+    /// splicer.splice(2, 3, "beep");
+    /// splicer.insert_left(6, "!");
+    /// let tracker = splicer.offset_tracker();
+    /// for offset in 0..=9 {
+    ///     assert_eq!(tracker.rebase(offset), splicer.map_offset(offset));
+    /// }
+    /// assert_eq!(tracker.rebase(4), 7);
+    /// ```
+    pub fn rebase(&self, offset: usize) -> usize {
+        assert!(
+            offset <= self.source_len,
+            "Trying to rebase an offset past the end of the original string"
+        );
+        let index = self.pieces.partition_point(|piece| piece.start <= offset);
+        match index.checked_sub(1).map(|index| &self.pieces[index]) {
+            Some(piece) if offset < piece.end && piece.replaced => piece.output_start,
+            Some(piece) if offset < piece.end => piece.output_start + offset - piece.start,
+            _ => self.output_len,
+        }
+    }
+}
+
+impl Multisplice<'_> {
+    /// Create an [`OffsetTracker`] that maps indices into the original string to the output
+    /// using the splices queued so far.
+    ///
+    /// Building the tracker walks the splices once. After that, each lookup takes logarithmic
+    /// time, instead of the linear time of [`Multisplice::map_offset`], so editors can keep many
+    /// positions in sync while they keep working with the original string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("let x = 1;");
+    /// splicer.splice(4, 5, "value");
+    /// let tracker = splicer.offset_tracker();
+    /// splicer.splice(8, 9, "2");
+    /// // The tracker only knows about the splices queued before it was created
+    /// assert_eq!(tracker.rebase(9), 13);
+    /// assert_eq!(splicer.map_offset(9), 13);
+    /// assert_eq!(tracker.rebase(8), 12);
+    /// ```
+    pub fn offset_tracker(&self) -> OffsetTracker {
+        let mut pieces: Vec<Piece> = vec![];
+        let mut position = 0;
+        for chunk in self.chunks(0, self.source.len()) {
+            let (range, replaced) = match &chunk {
+                Chunk::Source(range) => (range.clone(), false),
+                Chunk::Splice(index) => (self.splices[*index].range(), true),
+            };
+            // Deletions may overlap, in which case an index maps to the first piece it is in
+            let start = pieces
+                .last()
+                .map_or(range.start, |last| range.start.max(last.end));
+            if start < range.end {
+                pieces.push(Piece {
+                    start,
+                    end: range.end,
+                    output_start: position,
+                    replaced,
+                });
+            }
+            position += self.chunk_str(&chunk).len();
+        }
+        OffsetTracker {
+            pieces,
+            source_len: self.source.len(),
+            output_len: position,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! Checking the queued splices for problems before rendering them.
+
+use crate::{Multisplice, SpliceError};
+use std::ops::Range;
+
+impl Multisplice<'_> {
+    /// Check all the queued splices for ranges that end after the end of the original string,
+    /// that start after they end, that start or end inside a UTF-8 character, or that partially
+    /// overlap another splice. Rendering a splicer with such splices panics or produces broken
+    /// output.
+    ///
+    /// All problems are returned at once, in the order of the splices. Splices that are entirely
+    /// covered by a later, larger splice are not a problem: they are hidden by it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{Multisplice, SpliceError};
+    ///
+    /// let mut splicer = Multisplice::new("a ö c");
+    /// splicer.splice(0, 1, "beep");
+    /// assert_eq!(splicer.validate(), Ok(()));
+    ///
+    /// splicer.splice(3, 4, "o");
+    /// splicer.splice(5, 9, "boop");
+    /// assert_eq!(splicer.validate(), Err(vec![
+    ///     SpliceError::NotCharBoundary { new: 3..4, index: 3 },
+    ///     SpliceError::OutOfBounds { new: 5..9, len: 6 },
+    /// ]));
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<SpliceError>> {
+        let source = self.source;
+        let mut errors = vec![];
+        let mut previous: Option<Range<usize>> = None;
+        for splice in &self.splices {
+            let new = splice.range();
+            if new.start > new.end {
+                errors.push(SpliceError::Inverted { new });
+                continue;
+            }
+            if new.end > source.len() {
+                errors.push(SpliceError::OutOfBounds {
+                    new,
+                    len: source.len(),
+                });
+                continue;
+            }
+            let boundary = [new.start, new.end]
+                .iter()
+                .copied()
+                .find(|index| !source.is_char_boundary(*index));
+            if let Some(index) = boundary {
+                errors.push(SpliceError::NotCharBoundary { new, index });
+                continue;
+            }
+            if splice.is_insertion() {
+                continue;
+            }
+            match &previous {
+                // Partial overlap: starts inside the previous splice, but ends after it
+                Some(existing) if new.start < existing.end && new.end > existing.end => {
+                    errors.push(SpliceError::Overlap {
+                        existing: existing.clone(),
+                        new: new.clone(),
+                    });
+                }
+                // Entirely covered by the previous splice
+                Some(existing) if new.end <= existing.end => continue,
+                _ => (),
+            }
+            previous = Some(new);
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
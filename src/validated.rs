@@ -0,0 +1,104 @@
+//! Checking the output with a user-supplied validator.
+
+use crate::{Chunk, Multisplice, SpliceId};
+use std::{borrow::Cow, error::Error, fmt};
+
+/// The error returned by [`Multisplice::render_validated`] when the output is rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidOutput<E> {
+    /// The error returned by the validator.
+    pub error: E,
+    /// The index in the output at which the validator failed.
+    pub position: usize,
+    /// The index in the original string that `position` came from.
+    pub original: usize,
+    /// The splices whose replacement values contain or touch `position`.
+    pub splices: Vec<SpliceId>,
+}
+
+impl<E: fmt::Display> fmt::Display for InvalidOutput<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid output at index {}: {}",
+            self.position, self.error
+        )
+    }
+}
+
+impl<E: Error + 'static> Error for InvalidOutput<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl<'a> Multisplice<'a> {
+    /// Execute the splices and check the output with `validate`, for example by parsing it.
+    ///
+    /// If `validate` fails, it returns its error together with the index in the output at which
+    /// the problem is. That error is returned together with the splices whose replacement values
+    /// contain or touch that index, and the index in the original string that it came from, so
+    /// the problem can be traced back to the splices that caused it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// fn balanced(output: &str) -> Result<(), (&'static str, usize)> {
+    ///     let mut depth = 0;
+    ///     for (i, c) in output.char_indices() {
+    ///         match c {
+    ///             '(' => depth += 1,
+    ///             ')' if depth == 0 => return Err(("unmatched )", i)),
+    ///             ')' => depth -= 1,
+    ///             _ => (),
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    ///
+    /// let mut splicer = Multisplice::new("a(b) c(d)");
+    /// splicer.splice(2, 3, "beep");
+    /// assert_eq!(splicer.render_validated(balanced).unwrap(), "a(beep) c(d)");
+    ///
+    /// let id = splicer.splice(7, 8, "x)");
+    /// let error = splicer.render_validated(balanced).unwrap_err();
+    /// assert_eq!(error.error, "unmatched )");
+    /// assert_eq!(error.position, 12);
+    /// assert_eq!(error.original, 8);
+    /// assert_eq!(error.splices, vec![id]);
+    /// ```
+    pub fn render_validated<E>(
+        &self,
+        validate: impl FnOnce(&str) -> Result<(), (E, usize)>,
+    ) -> Result<Cow<'a, str>, InvalidOutput<E>> {
+        let output = self.render();
+        let (error, position) = match validate(&output) {
+            Ok(()) => return Ok(output),
+            Err(failure) => failure,
+        };
+
+        let mut splices = vec![];
+        let mut start = 0;
+        for chunk in self.chunks(0, self.source.len()) {
+            let end = start + self.chunk_str(&chunk).len();
+            if start > position {
+                break;
+            }
+            if let Chunk::Splice(index) = chunk {
+                if position <= end {
+                    splices.push(self.splices[index].id);
+                }
+            }
+            start = end;
+        }
+
+        let position = position.min(output.len());
+        Err(InvalidOutput {
+            error,
+            position,
+            original: self.unmap_range(position..position).start,
+            splices,
+        })
+    }
+}
@@ -0,0 +1,161 @@
+//! Mutually exclusive alternatives for a splice, and rendering every combination of them.
+
+use crate::{get_end_bound, get_start_bound, Chunk, Multisplice, Splice, SpliceId};
+use std::borrow::Cow;
+use std::ops::RangeBounds;
+use std::sync::OnceLock;
+
+/// One possible output of a splicer with alternatives.
+///
+/// Returned by [`Multisplice::variants`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    /// The splices with alternatives, and the index of the alternative that was chosen for each.
+    pub choices: Vec<(SpliceId, usize)>,
+    /// The output with these alternatives.
+    pub output: String,
+}
+
+impl<'a> Multisplice<'a> {
+    /// Replace a range with one of several mutually exclusive values.
+    ///
+    /// The first alternative is used when rendering the splicer normally. Use
+    /// [`Multisplice::choose_alternative`] to pick another one, or [`Multisplice::variants`] to
+    /// render every combination of alternatives.
+    ///
+    /// # Panics
+    /// Panics if `alternatives` is empty.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("let x = y;");
+    /// let id = splicer.splice_alternatives(8..9, vec!["y.clone()", "&y"]);
+    /// assert_eq!(splicer.to_string(), "let x = y.clone();");
+    /// splicer.choose_alternative(id, 1);
+    /// assert_eq!(splicer.to_string(), "let x = &y;");
+    /// ```
+    pub fn splice_alternatives<V>(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        alternatives: impl IntoIterator<Item = V>,
+    ) -> SpliceId
+    where
+        V: Into<Cow<'a, str>>,
+    {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        let alternatives = alternatives.into_iter().map(Into::into).collect::<Vec<_>>();
+        let value = alternatives
+            .first()
+            .expect("A splice needs at least one alternative")
+            .clone();
+        let mut splice = Splice::new(self.next_id(), start..end, value);
+        splice.meta_mut().alternatives = alternatives;
+        self.insert_splice(splice)
+    }
+
+    /// Use the alternative at `index` as the value of a splice queued with
+    /// [`Multisplice::splice_alternatives`].
+    ///
+    /// # Panics
+    /// Panics if the splice does not exist or does not have an alternative at `index`.
+    pub fn choose_alternative(&mut self, id: SpliceId, index: usize) {
+        let position = self
+            .splices
+            .iter()
+            .position(|s| s.id == id)
+            .expect("Trying to choose an alternative for a splice that does not exist");
+        let value = self.splices[position]
+            .meta()
+            .alternatives
+            .get(index)
+            .expect("Trying to choose an alternative that does not exist")
+            .clone();
+        self.record_value(position);
+        *self.splices[position].value_mut() = value;
+        self.output = OnceLock::new();
+    }
+
+    /// Render every combination of alternatives of the splices queued with
+    /// [`Multisplice::splice_alternatives`].
+    ///
+    /// The first variant uses the first alternative of every splice. The number of variants is
+    /// the product of the numbers of alternatives, so it grows quickly.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("a b c");
+    /// let first = splicer.splice_alternatives(0..1, vec!["x", "y"]);
+    /// splicer.splice(2, 3, "beep");
+    /// let second = splicer.splice_alternatives(4..5, vec!["1", "2"]);
+    /// let variants = splicer.variants();
+    /// let outputs = variants.iter().map(|v| v.output.as_str()).collect::<Vec<_>>();
+    /// assert_eq!(outputs, vec!["x beep 1", "x beep 2", "y beep 1", "y beep 2"]);
+    /// assert_eq!(variants[2].choices, vec![(first, 1), (second, 0)]);
+    /// ```
+    pub fn variants(&self) -> Vec<Variant> {
+        let chunks = self.chunks(0, self.source.len()).collect::<Vec<_>>();
+        let choosable = chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                Chunk::Splice(index) if !self.splices[*index].meta().alternatives.is_empty() => {
+                    Some(*index)
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut variants = vec![];
+        let mut choices = vec![0; choosable.len()];
+        loop {
+            let mut output = String::new();
+            for chunk in &chunks {
+                match chunk {
+                    Chunk::Splice(index) if choosable.contains(index) => {
+                        let position = choosable.iter().position(|i| i == index).unwrap();
+                        output.push_str(&self.render_alternative(*index, choices[position]));
+                    }
+                    chunk => output.push_str(self.chunk_str(chunk)),
+                }
+            }
+            variants.push(Variant {
+                choices: choosable
+                    .iter()
+                    .zip(&choices)
+                    .map(|(index, choice)| (self.splices[*index].id, *choice))
+                    .collect(),
+                output,
+            });
+
+            // Count up the choices like an odometer, with the last splice changing fastest
+            let next = choices.iter().zip(&choosable).rposition(|(choice, index)| {
+                choice + 1 < self.splices[*index].meta().alternatives.len()
+            });
+            match next {
+                Some(position) => {
+                    choices[position] += 1;
+                    choices[position + 1..].iter_mut().for_each(|c| *c = 0);
+                }
+                None => return variants,
+            }
+        }
+    }
+
+    /// Render an alternative for the splice at `index`, the way its value would be rendered.
+    fn render_alternative(&self, index: usize, choice: usize) -> Cow<'_, str> {
+        let splice = &self.splices[index];
+        let value = &splice.meta().alternatives[choice];
+        let alternative = Splice {
+            raw: splice.raw,
+            ..Splice::new(splice.id, splice.range(), Cow::Borrowed(&**value))
+        };
+        match self.rendering.render(&alternative) {
+            Some(rendered) => Cow::Owned(rendered),
+            None => Cow::Borrowed(value),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Splicing sequences of arbitrary values.
+
+use crate::{get_end_bound, get_start_bound, SpliceError};
+use std::{borrow::Cow, ops::Range, ops::RangeBounds};
+
+/// A multisplice operation on a slice of arbitrary values, like tokens or AST node IDs, instead of
+/// a string.
+///
+/// # Example
+/// ```rust
+/// use multisplice::MultispliceVec;
+///
+/// let source = [1, 2, 3, 4, 5];
+/// let mut splicer = MultispliceVec::new(&source);
+/// splicer.splice(1..2, vec![20, 21]);
+/// splicer.splice(3..5, vec![]);
+/// splicer.insert(5, &[6, 7][..]);
+/// assert_eq!(splicer.to_vec(), vec![1, 20, 21, 3, 6, 7]);
+/// ```
+#[derive(Debug)]
+pub struct MultispliceVec<'a, T: Clone> {
+    /// The original values.
+    pub(crate) source: &'a [T],
+    /// Splice operations, sorted by their start index. At the same index, insertions come
+    /// before replacements.
+    pub(crate) splices: Vec<(Range<usize>, Cow<'a, [T]>)>,
+}
+
+impl<'a, T: Clone> MultispliceVec<'a, T> {
+    /// Create a "multisplicer" for the given slice.
+    #[inline]
+    pub fn new(source: &'a [T]) -> Self {
+        MultispliceVec {
+            source,
+            splices: vec![],
+        }
+    }
+
+    /// Get the original slice.
+    #[inline]
+    pub fn source(&self) -> &'a [T] {
+        self.source
+    }
+
+    /// Replace the values in `range` by `value`.
+    ///
+    /// # Panics
+    /// Panics if the range overlaps a range that was already spliced. Use
+    /// [`MultispliceVec::try_splice`] to handle conflicts instead.
+    pub fn splice(&mut self, range: impl RangeBounds<usize>, value: impl Into<Cow<'a, [T]>>) {
+        if let Err(err) = self.try_splice(range, value) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Insert `value` at `index`. Insertions at the same index are output in the order they were
+    /// queued.
+    ///
+    /// # Panics
+    /// Panics if `index` is inside a spliced range.
+    #[inline]
+    pub fn insert(&mut self, index: usize, value: impl Into<Cow<'a, [T]>>) {
+        self.splice(index..index, value);
+    }
+
+    /// Replace the values in `range` by `value`, or return an error if the range overlaps a
+    /// range that was already spliced.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::{MultispliceVec, SpliceError};
+    ///
+    /// let source = [1, 2, 3, 4, 5];
+    /// let mut splicer = MultispliceVec::new(&source);
+    /// splicer.try_splice(1..3, vec![0]).unwrap();
+    /// assert_eq!(
+    ///     splicer.try_splice(2..4, vec![0]),
+    ///     Err(SpliceError::Overlap { existing: 1..3, new: 2..4 })
+    /// );
+    /// ```
+    pub fn try_splice(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        value: impl Into<Cow<'a, [T]>>,
+    ) -> Result<(), SpliceError> {
+        let start = get_start_bound(range.start_bound());
+        let end = get_end_bound(range.end_bound(), self.source.len());
+        assert!(start <= end && end <= self.source.len());
+        let range = start..end;
+
+        let overlapping = self.splices.iter().find(|(existing, _)| {
+            if existing.is_empty() {
+                start < existing.start && existing.start < end
+            } else if range.is_empty() {
+                existing.start < start && start < existing.end
+            } else {
+                existing.start < end && start < existing.end
+            }
+        });
+        if let Some((existing, _)) = overlapping {
+            return Err(SpliceError::Overlap {
+                existing: existing.clone(),
+                new: range,
+            });
+        }
+
+        let key = (start, !range.is_empty());
+        let insert_at = self
+            .splices
+            .partition_point(|(existing, _)| (existing.start, !existing.is_empty()) <= key);
+        self.splices.insert(insert_at, (range, value.into()));
+        Ok(())
+    }
+
+    /// Execute the splices, returning the new values.
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut result = Vec::with_capacity(self.source.len());
+        let mut last = 0;
+        for (range, value) in &self.splices {
+            result.extend_from_slice(&self.source[last..range.start]);
+            result.extend_from_slice(value);
+            last = range.end;
+        }
+        result.extend_from_slice(&self.source[last..]);
+        result
+    }
+}
@@ -0,0 +1,91 @@
+//! Base64 VLQ encoding, as used in source map mappings.
+//!
+//! # Example
+//! ```rust
+//! use multisplice::vlq;
+//!
+//! let mut segment = String::new();
+//! for value in [0, 4, -1, 1000] {
+//!     vlq::encode(value, &mut segment);
+//! }
+//! assert_eq!(segment, "AIDw+B");
+//! assert_eq!(vlq::decode(&segment), Ok(vec![0, 4, -1, 1000]));
+//! ```
+
+use std::{error::Error, fmt};
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// An error that occurred while decoding base64 VLQ values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input contains a character that is not a base64 digit.
+    InvalidDigit(char),
+    /// The input ends in the middle of a value.
+    UnexpectedEnd,
+    /// A value does not fit in an `i64`.
+    Overflow,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidDigit(c) => write!(f, "Invalid base64 VLQ digit {:?}", c),
+            DecodeError::UnexpectedEnd => write!(f, "Unexpected end of base64 VLQ value"),
+            DecodeError::Overflow => write!(f, "Base64 VLQ value does not fit in 64 bits"),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+/// Append the base64 VLQ encoding of `value` to `out`.
+pub fn encode(value: i64, out: &mut String) {
+    let mut vlq = if value < 0 {
+        (value.unsigned_abs() << 1) | 1
+    } else {
+        (value as u64) << 1
+    };
+    loop {
+        let mut digit = vlq & 0b11111;
+        vlq >>= 5;
+        if vlq > 0 {
+            digit |= 0b100000;
+        }
+        out.push(BASE64[digit as usize] as char);
+        if vlq == 0 {
+            break;
+        }
+    }
+}
+
+/// Decode a sequence of base64 VLQ values, such as a single segment of source map mappings.
+///
+/// The `,` and `;` separators between segments are not accepted.
+pub fn decode(input: &str) -> Result<Vec<i64>, DecodeError> {
+    let mut values = vec![];
+    let mut vlq = 0u64;
+    let mut shift = 0;
+    for c in input.chars() {
+        let digit = BASE64
+            .iter()
+            .position(|&b| b as char == c)
+            .ok_or(DecodeError::InvalidDigit(c))? as u64;
+        let bits = digit & 0b11111;
+        if shift >= 64 || (bits << shift) >> shift != bits {
+            return Err(DecodeError::Overflow);
+        }
+        vlq |= bits << shift;
+        shift += 5;
+        if digit & 0b100000 == 0 {
+            let magnitude = (vlq >> 1) as i64;
+            values.push(if vlq & 1 == 1 { -magnitude } else { magnitude });
+            vlq = 0;
+            shift = 0;
+        }
+    }
+    if shift > 0 {
+        return Err(DecodeError::UnexpectedEnd);
+    }
+    Ok(values)
+}
@@ -0,0 +1,81 @@
+//! JavaScript bindings through `wasm-bindgen`.
+
+use crate::{owned::OwnedSplicer, SourceMapOptions};
+use wasm_bindgen::prelude::*;
+
+/// A splicer that owns its original string, exported to JavaScript as `Multisplice`.
+///
+/// The methods mirror the `magic-string` package, so JavaScript code using a subset of its API can
+/// switch over. Indices are byte offsets into the UTF-8 original string.
+///
+/// # Example
+/// ```rust
+/// use multisplice::JsMultisplice;
+///
+/// let mut splicer = JsMultisplice::new("a b c d e".to_string());
+/// splicer.overwrite(2, 3, "beep").unwrap();
+/// splicer.append_left(5, " c2").unwrap();
+/// splicer.remove(6, 8).unwrap();
+/// assert_eq!(splicer.render(), "a beep c c2 e");
+/// ```
+#[wasm_bindgen(js_name = Multisplice)]
+#[derive(Debug)]
+pub struct JsMultisplice {
+    inner: OwnedSplicer,
+}
+
+#[wasm_bindgen(js_class = Multisplice)]
+impl JsMultisplice {
+    /// Create a splicer for the given string.
+    #[wasm_bindgen(constructor)]
+    pub fn new(source: String) -> Self {
+        JsMultisplice {
+            inner: OwnedSplicer::new(source),
+        }
+    }
+
+    /// Replace the characters from index `start` up to (but not including) index `end` by
+    /// `content`.
+    ///
+    /// Throws if the range is out of bounds, starts or ends inside a UTF-8 character, or overlaps
+    /// a range that was already overwritten.
+    pub fn overwrite(&mut self, start: usize, end: usize, content: &str) -> Result<(), JsError> {
+        self.inner
+            .splice(start..end, content.to_string())
+            .map_err(|err| JsError::new(&err.to_string()))
+    }
+
+    /// Insert `content` at `index`, attached to the content before it.
+    ///
+    /// Throws if the index is out of bounds, inside a UTF-8 character, or inside a range that was
+    /// already overwritten.
+    #[wasm_bindgen(js_name = appendLeft)]
+    pub fn append_left(&mut self, index: usize, content: &str) -> Result<(), JsError> {
+        self.inner
+            .insert_left(index, content.to_string())
+            .map_err(|err| JsError::new(&err.to_string()))
+    }
+
+    /// Remove the characters from index `start` up to (but not including) index `end`.
+    ///
+    /// Throws like [`JsMultisplice::overwrite`].
+    pub fn remove(&mut self, start: usize, end: usize) -> Result<(), JsError> {
+        self.overwrite(start, end, "")
+    }
+
+    /// Execute the edits, returning the new string.
+    #[wasm_bindgen(js_name = toString)]
+    pub fn render(&self) -> String {
+        self.inner.splicer().into_string()
+    }
+
+    /// Generate a source map for the edits, as a JSON string. `source` is the name of the
+    /// original file.
+    #[wasm_bindgen(js_name = generateMap)]
+    pub fn generate_map(&self, source: &str) -> String {
+        self.inner
+            .splicer()
+            .generate_map(&SourceMapOptions::new(source))
+            .to_json()
+    }
+}
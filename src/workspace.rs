@@ -0,0 +1,374 @@
+//! Edit sets spanning multiple files.
+
+use crate::{
+    file::write_atomic, lines::LineIndex, rebase::floor_char_boundary, Multisplice, SpliceError,
+};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// A set of splicers for multiple files, for changes that span a whole project.
+///
+/// # Example
+/// ```rust
+/// use multisplice::Workspace;
+///
+/// let dir = std::env::temp_dir();
+/// let (a, b) = (dir.join("multisplice-workspace-a.txt"), dir.join("multisplice-workspace-b.txt"));
+/// std::fs::write(&a, "a b c").unwrap();
+/// std::fs::write(&b, "d e f").unwrap();
+/// let source_a = std::fs::read_to_string(&a).unwrap();
+/// let source_b = std::fs::read_to_string(&b).unwrap();
+///
+/// let mut workspace = Workspace::new();
+/// workspace.add_file(&a, &source_a);
+/// workspace.add_file(&b, &source_b);
+/// workspace.splice_many(&a, vec![(2..3, "beep")]);
+/// workspace.splice_many(&b, vec![(2..3, "boop"), (0..3, "conflict")]);
+/// assert_eq!(workspace.conflicts().count(), 1);
+/// workspace.splice_many(&b, vec![(2..3, "boop")]);
+/// assert_eq!(workspace.stats().changed_files, 2);
+/// workspace.apply_all().unwrap();
+///
+/// assert_eq!(std::fs::read_to_string(&a).unwrap(), "a beep c");
+/// assert_eq!(std::fs::read_to_string(&b).unwrap(), "d boop f");
+/// # std::fs::remove_file(&a).unwrap();
+/// # std::fs::remove_file(&b).unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct Workspace<'a> {
+    /// The splicer for each file.
+    files: BTreeMap<PathBuf, Multisplice<'a>>,
+    /// Splices that could not be queued.
+    rejected: Vec<Rejected>,
+}
+
+/// A splice that could not be queued.
+#[derive(Debug)]
+struct Rejected {
+    /// The file the splice was meant for.
+    path: PathBuf,
+    /// Why the splice could not be queued.
+    error: SpliceError,
+    /// The replacement value of the rejected splice.
+    value: String,
+    /// The range and replacement value of the splice it conflicted with.
+    existing: Option<(Range<usize>, String)>,
+    /// The note attached to the splice it conflicted with.
+    existing_note: Option<String>,
+}
+
+/// A report of all the splices that could not be queued in a [`Workspace`].
+///
+/// The `Display` implementation formats it for humans, with one entry per rejected splice.
+///
+/// Returned by [`Workspace::conflict_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictReport {
+    /// The rejected splices, in the order they were queued.
+    pub entries: Vec<ConflictEntry>,
+}
+
+/// A splice that could not be queued, with context. Part of a [`ConflictReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictEntry {
+    /// The file the splice was meant for.
+    pub path: PathBuf,
+    /// The one-based line where the rejected splice starts.
+    pub line: usize,
+    /// The one-based column, in characters, where the rejected splice starts.
+    pub column: usize,
+    /// Why the splice could not be queued.
+    pub error: SpliceError,
+    /// The range and replacement value of the rejected splice.
+    pub new: (Range<usize>, String),
+    /// The range and replacement value of the splice it conflicted with, if it conflicted with
+    /// another splice.
+    pub existing: Option<(Range<usize>, String)>,
+    /// The note attached to the splice it conflicted with, explaining why that splice was made.
+    pub existing_note: Option<String>,
+}
+
+impl fmt::Display for ConflictReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{}:{}:{}: {}",
+                entry.path.display(),
+                entry.line,
+                entry.column,
+                entry.error
+            )?;
+            writeln!(f, "  new:      {:?} {:?}", entry.new.0, entry.new.1)?;
+            if let Some((range, value)) = &entry.existing {
+                writeln!(f, "  existing: {:?} {:?}", range, value)?;
+            }
+            if let Some(note) = &entry.existing_note {
+                writeln!(f, "  note:     {}", note)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Statistics about the changes in a [`Workspace`].
+///
+/// Returned by [`Workspace::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WorkspaceStats {
+    /// The number of files in the workspace.
+    pub files: usize,
+    /// The number of files whose contents change.
+    pub changed_files: usize,
+    /// The number of splices that are applied, across all files.
+    pub splices: usize,
+    /// The number of bytes of the original files that are replaced.
+    pub bytes_removed: usize,
+    /// The number of bytes of replacement values that are inserted.
+    pub bytes_inserted: usize,
+}
+
+impl<'a> Workspace<'a> {
+    /// Create an empty workspace.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file with the given contents, returning its splicer. If the file was already added,
+    /// this returns the existing splicer.
+    pub fn add_file(&mut self, path: impl Into<PathBuf>, source: &'a str) -> &mut Multisplice<'a> {
+        self.files
+            .entry(path.into())
+            .or_insert_with(|| Multisplice::new(source))
+    }
+
+    /// Get the splicer for a file.
+    #[inline]
+    pub fn file(&self, path: impl AsRef<Path>) -> Option<&Multisplice<'a>> {
+        self.files.get(path.as_ref())
+    }
+
+    /// Get the splicer for a file, for queueing splices.
+    #[inline]
+    pub fn file_mut(&mut self, path: impl AsRef<Path>) -> Option<&mut Multisplice<'a>> {
+        self.files.get_mut(path.as_ref())
+    }
+
+    /// Iterate over the files in the workspace, sorted by path.
+    pub fn files(&self) -> impl Iterator<Item = (&Path, &Multisplice<'a>)> {
+        self.files
+            .iter()
+            .map(|(path, splicer)| (path.as_path(), splicer))
+    }
+
+    /// Replace many ranges of a file at once.
+    ///
+    /// Like [`Multisplice::splice_many`], none of the splices are applied if any of them conflict.
+    /// The conflicts are added to [`Workspace::conflicts`]. Returns whether the splices were
+    /// queued.
+    ///
+    /// # Panics
+    /// Panics if the file was not added to the workspace.
+    pub fn splice_many<V>(
+        &mut self,
+        path: impl AsRef<Path>,
+        edits: impl IntoIterator<Item = (Range<usize>, V)>,
+    ) -> bool
+    where
+        V: Into<Cow<'a, str>>,
+    {
+        let path = path.as_ref();
+        let splicer = self
+            .files
+            .get_mut(path)
+            .expect("Trying to splice a file that is not in the workspace");
+        let edits = edits
+            .into_iter()
+            .map(|(range, value)| (range, value.into()))
+            .collect::<Vec<_>>();
+        let value_of = |splicer: &Multisplice<'_>, range: &Range<usize>| {
+            let existing = splicer
+                .splices
+                .iter()
+                .find(|s| s.range() == *range)
+                .map(|s| s.value.to_string());
+            existing.or_else(|| {
+                edits
+                    .iter()
+                    .find(|(r, _)| r == range)
+                    .map(|(_, value)| value.to_string())
+            })
+        };
+        let errors = match splicer.splice_many(edits.clone()) {
+            Ok(()) => return true,
+            Err(errors) => errors,
+        };
+        for error in errors {
+            let (new, existing) = match &error {
+                SpliceError::Overlap { existing, new } => (new, Some(existing)),
+                SpliceError::LengthMismatch { new, .. }
+                | SpliceError::Protected { new, .. }
+                | SpliceError::OutOfBounds { new, .. }
+                | SpliceError::Inverted { new }
+                | SpliceError::NotCharBoundary { new, .. } => (new, None),
+            };
+            let value = edits
+                .iter()
+                .find(|(range, _)| range == new)
+                .map_or_else(String::new, |(_, value)| value.to_string());
+            let existing_note = existing.and_then(|range| {
+                let existing = splicer.splices.iter().find(|s| s.range() == *range)?;
+                existing.meta().note.as_deref().map(str::to_string)
+            });
+            let existing = existing.and_then(|range| {
+                let value = value_of(splicer, range)?;
+                Some((range.clone(), value))
+            });
+            self.rejected.push(Rejected {
+                path: path.to_path_buf(),
+                error,
+                value,
+                existing,
+                existing_note,
+            });
+        }
+        false
+    }
+
+    /// Iterate over the splices that could not be queued by [`Workspace::splice_many`], across
+    /// all files.
+    pub fn conflicts(&self) -> impl Iterator<Item = (&Path, &SpliceError)> {
+        self.rejected
+            .iter()
+            .map(|rejected| (rejected.path.as_path(), &rejected.error))
+    }
+
+    /// Get a report of the splices that could not be queued by [`Workspace::splice_many`],
+    /// with their line and column, and the splices they conflicted with.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Workspace;
+    ///
+    /// let mut workspace = Workspace::new();
+    /// workspace.add_file("a.txt", "a\nb c d");
+    /// workspace.splice_many("a.txt", vec![(4..5, "beep")]);
+    /// workspace.splice_many("a.txt", vec![(2..5, "boop")]);
+    /// let report = workspace.conflict_report();
+    /// assert_eq!(report.entries[0].line, 2);
+    /// assert_eq!(report.entries[0].existing, Some((4..5, "beep".to_string())));
+    /// assert_eq!(report.to_string(), "\
+    /// a.txt:2:1: Trying to splice an already spliced range: 2..5 overlaps 4..5
+    ///   new:      2..5 \"boop\"
+    ///   existing: 4..5 \"beep\"
+    /// ");
+    ///
+    /// let mut workspace = Workspace::new();
+    /// let a = workspace.add_file("a.txt", "a b c");
+    /// a.splice_with_note(2..3, "beep", "b is deprecated");
+    /// workspace.splice_many("a.txt", vec![(2..3, "boop")]);
+    /// assert!(workspace.conflict_report().to_string().ends_with("  note:     b is deprecated\n"));
+    ///
+    /// let mut workspace = Workspace::new();
+    /// workspace.add_file("a.txt", "a\nbc");
+    /// workspace.splice_many("a.txt", vec![(3..10, "beep")]);
+    /// let report = workspace.conflict_report();
+    /// assert_eq!((report.entries[0].line, report.entries[0].column), (2, 2));
+    ///
+    /// let mut workspace = Workspace::new();
+    /// workspace.add_file("a.txt", "aéb");
+    /// workspace.splice_many("a.txt", vec![(2..3, "x")]);
+    /// let report = workspace.conflict_report();
+    /// assert_eq!((report.entries[0].line, report.entries[0].column), (1, 2));
+    /// ```
+    pub fn conflict_report(&self) -> ConflictReport {
+        let mut lines = BTreeMap::new();
+        let entries = self
+            .rejected
+            .iter()
+            .map(|rejected| {
+                let new = match &rejected.error {
+                    SpliceError::Overlap { new, .. }
+                    | SpliceError::LengthMismatch { new, .. }
+                    | SpliceError::Protected { new, .. }
+                    | SpliceError::OutOfBounds { new, .. }
+                    | SpliceError::Inverted { new }
+                    | SpliceError::NotCharBoundary { new, .. } => new.clone(),
+                };
+                let source = self.files[&rejected.path].source();
+                let index = lines
+                    .entry(&rejected.path)
+                    .or_insert_with(|| LineIndex::new(source));
+                // Rejected ranges may be out of bounds or inside a character
+                let start = floor_char_boundary(source, new.start.min(source.len()));
+                let (line, byte_column) = index.byte_position(start);
+                let line_start = start - byte_column;
+                let column = source[line_start..start].chars().count();
+                ConflictEntry {
+                    path: rejected.path.clone(),
+                    line: line + 1,
+                    column: column + 1,
+                    error: rejected.error.clone(),
+                    new: (new, rejected.value.clone()),
+                    existing: rejected.existing.clone(),
+                    existing_note: rejected.existing_note.clone(),
+                }
+            })
+            .collect();
+        ConflictReport { entries }
+    }
+
+    /// Get statistics about the changes across all files.
+    pub fn stats(&self) -> WorkspaceStats {
+        let mut stats = WorkspaceStats {
+            files: self.files.len(),
+            ..WorkspaceStats::default()
+        };
+        for splicer in self.files.values() {
+            let deltas = splicer.deltas();
+            stats.splices += deltas.len();
+            if splicer.has_changes() {
+                stats.changed_files += 1;
+            }
+            for (range, new_len, _) in deltas {
+                stats.bytes_removed += range.len();
+                stats.bytes_inserted += new_len;
+            }
+        }
+        stats
+    }
+
+    /// Write the output of every file whose contents change.
+    ///
+    /// Each file is replaced through a temporary file, like [`splice_file`](crate::splice_file)
+    /// does. Stops at the first error.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Workspace;
+    ///
+    /// let path = std::env::temp_dir().join("multisplice-workspace-whole.txt");
+    /// std::fs::write(&path, "abc").unwrap();
+    /// let source = std::fs::read_to_string(&path).unwrap();
+    ///
+    /// let mut workspace = Workspace::new();
+    /// workspace.add_file(&path, &source);
+    /// workspace.splice_many(&path, vec![(0..3, "xyz")]);
+    /// workspace.apply_all().unwrap();
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "xyz");
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn apply_all(&self) -> io::Result<()> {
+        for (path, splicer) in &self.files {
+            if splicer.has_changes() {
+                write_atomic(path, &splicer.render())?;
+            }
+        }
+        Ok(())
+    }
+}
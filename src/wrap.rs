@@ -0,0 +1,71 @@
+//! Wrapping long replacement values while rendering.
+
+use crate::{Chunk, Multisplice};
+
+/// Check if `text`, starting at `column`, has a line that is longer than `width` characters.
+fn overflows(text: &str, column: usize, width: usize) -> bool {
+    let mut column = column;
+    for line in text.split('\n') {
+        if column + line.chars().count() > width {
+            return true;
+        }
+        column = 0;
+    }
+    false
+}
+
+/// Get the column that the output is at after `text`, if it was at `column` before it.
+fn advance(text: &str, column: usize) -> usize {
+    match text.rfind('\n') {
+        Some(i) => text[i + 1..].chars().count(),
+        None => column + text.chars().count(),
+    }
+}
+
+impl Multisplice<'_> {
+    /// Execute the splices, wrapping replacement values that would make a line of the output
+    /// longer than `width` characters.
+    ///
+    /// `wrap` receives such a value and the column, counted in characters, at which it starts in
+    /// the output, and returns the wrapped value. Values that fit are output unchanged, and the
+    /// original string is never wrapped.
+    ///
+    /// # Example
+    /// ```rust
+    /// use multisplice::Multisplice;
+    ///
+    /// let mut splicer = Multisplice::new("call(args);");
+    /// splicer.splice(5, 9, "first, second, third");
+    /// let output = splicer.render_wrapped(20, |value, column| {
+    ///     let indent = format!(",\n{}", " ".repeat(column));
+    ///     value.split(", ").collect::<Vec<_>>().join(&indent)
+    /// });
+    /// assert_eq!(output, "call(first,\n     second,\n     third);");
+    ///
+    /// let output = splicer.render_wrapped(40, |_, _| unreachable!());
+    /// assert_eq!(output, "call(first, second, third);");
+    /// ```
+    pub fn render_wrapped(
+        &self,
+        width: usize,
+        mut wrap: impl FnMut(&str, usize) -> String,
+    ) -> String {
+        let mut output = String::with_capacity(self.output_len(0, self.source.len()));
+        let mut column = 0;
+        for chunk in self.chunks(0, self.source.len()) {
+            let text = self.chunk_str(&chunk);
+            match chunk {
+                Chunk::Splice(_) if overflows(text, column, width) => {
+                    let wrapped = wrap(text, column);
+                    column = advance(&wrapped, column);
+                    output.push_str(&wrapped);
+                }
+                _ => {
+                    column = advance(text, column);
+                    output.push_str(text);
+                }
+            }
+        }
+        output
+    }
+}
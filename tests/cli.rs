@@ -0,0 +1,62 @@
+//! Tests for the `multisplice` command line tool.
+#![cfg(feature = "cli")]
+
+use std::{fs, path::PathBuf, process::Command};
+
+/// Write a plan and a file to splice to the temporary directory, and run the tool on them.
+fn run(name: &str, plan: &str, source: &str) -> (std::process::Output, PathBuf) {
+    let dir = std::env::temp_dir();
+    let plan_path = dir.join(format!("multisplice-cli-{}.json", name));
+    let file_path = dir.join(format!("multisplice-cli-{}.txt", name));
+    fs::write(&plan_path, plan).unwrap();
+    fs::write(&file_path, source).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_multisplice"))
+        .arg(&plan_path)
+        .arg(&file_path)
+        .output()
+        .unwrap();
+    fs::remove_file(&plan_path).unwrap();
+    (output, file_path)
+}
+
+#[test]
+fn applies_plan() {
+    let (output, path) = run(
+        "apply",
+        r#"[{"start":2,"end":3,"replace":"beep"}]"#,
+        "a b c",
+    );
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "a beep c");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn rejects_out_of_bounds_edit() {
+    let (output, path) = run(
+        "out-of-bounds",
+        r#"[{"start":2,"end":10,"replace":"x"}]"#,
+        "abc",
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("multisplice: "), "{}", stderr);
+    assert!(!stderr.contains("panicked"), "{}", stderr);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "abc");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn rejects_mid_character_edit() {
+    let (output, path) = run(
+        "mid-character",
+        r#"[{"start":2,"end":3,"replace":"x"}]"#,
+        "aéb",
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("multisplice: "), "{}", stderr);
+    assert!(!stderr.contains("panicked"), "{}", stderr);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "aéb");
+    fs::remove_file(&path).unwrap();
+}